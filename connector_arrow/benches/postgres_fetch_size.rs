@@ -0,0 +1,55 @@
+//! Compares reading a large result with different portal fetch sizes.
+//!
+//! Run with:
+//! ```sh
+//! POSTGRES_URL=postgres://... cargo bench --features src_postgres --bench postgres_fetch_size
+//! ```
+
+use std::time::{Duration, Instant};
+
+use connector_arrow::api::{Connector, Statement};
+use connector_arrow::postgres::PostgresConnection;
+
+const QUERY: &str = "SELECT i, md5(i::text) AS a FROM generate_series(1, 200000) AS i";
+
+const ITERATIONS: u32 = 10;
+
+fn init() -> PostgresConnection {
+    let dburl = std::env::var("POSTGRES_URL").unwrap();
+    let client = postgres::Client::connect(&dburl, postgres::NoTls).unwrap();
+    PostgresConnection::new(client)
+}
+
+fn read(conn: &mut PostgresConnection, fetch_size: Option<u32>) {
+    let mut stmt = conn.query(QUERY).unwrap();
+    if let Some(fetch_size) = fetch_size {
+        stmt = stmt.with_portal_fetch_size(fetch_size).unwrap();
+    }
+    let reader = stmt.start([]).unwrap();
+    for batch in reader {
+        batch.unwrap();
+    }
+}
+
+fn measure(conn: &mut PostgresConnection, fetch_size: Option<u32>) -> Duration {
+    // warm up
+    read(conn, fetch_size);
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        read(conn, fetch_size);
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let mut conn = init();
+
+    let all = measure(&mut conn, None);
+    println!("fetch all:   {all:?} per query");
+
+    for fetch_size in [100, 1000, 10000, 100000] {
+        let duration = measure(&mut conn, Some(fetch_size));
+        println!("fetch {fetch_size:>6}: {duration:?} per query");
+    }
+}
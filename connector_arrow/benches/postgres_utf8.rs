@@ -0,0 +1,40 @@
+//! Compares reading text from PostgreSQL with and without UTF-8 validation.
+//!
+//! Run with:
+//! ```sh
+//! POSTGRES_URL=postgres://... cargo bench --features src_postgres --bench postgres_utf8
+//! ```
+
+use std::time::{Duration, Instant};
+
+use connector_arrow::postgres::PostgresConnection;
+
+const QUERY: &str = "SELECT repeat(md5(i::text), 8) AS a, repeat('žćč', 32) AS b
+    FROM generate_series(1, 100000) AS i";
+
+const ITERATIONS: u32 = 10;
+
+fn init() -> PostgresConnection {
+    let dburl = std::env::var("POSTGRES_URL").unwrap();
+    let client = postgres::Client::connect(&dburl, postgres::NoTls).unwrap();
+    PostgresConnection::new(client)
+}
+
+fn measure(conn: &mut PostgresConnection) -> Duration {
+    // warm up
+    connector_arrow::query(conn, QUERY).unwrap();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        connector_arrow::query(conn, QUERY).unwrap();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let checked = measure(&mut init());
+    println!("utf8 checked:   {checked:?} per query");
+
+    let unchecked = measure(&mut unsafe { init().with_utf8_unchecked(true) });
+    println!("utf8 unchecked: {unchecked:?} per query");
+}
@@ -0,0 +1,401 @@
+//! A connection to any of the supported data stores, chosen at runtime.
+//!
+//! ```ignore
+//! let mut conn = AnyConnection::connect("sqlite://data.db")?;
+//! let batches = connector_arrow::query(&mut conn, "SELECT 1")?;
+//! ```
+
+use std::path::Path;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::api::{
+    Append, AppenderOptions, ArrowValue, CancelToken, Connector, ForeignKey, QueryCost,
+    ReadOptions, ResultReader, SchemaEdit, SchemaGet, Statement, StatementCacheStats,
+};
+use crate::{ConnectorError, TableCreateError, TableDropError};
+
+#[cfg(feature = "src_duckdb")]
+use crate::duckdb::DuckDBConnection;
+#[cfg(feature = "src_mysql")]
+use crate::mysql::MySQLConnection;
+#[cfg(feature = "src_postgres")]
+use crate::postgres::PostgresConnection;
+#[cfg(feature = "src_sqlite")]
+use crate::sqlite::SQLiteConnection;
+
+#[cfg(feature = "src_mysql")]
+type MySQLConn = MySQLConnection<mysql::Conn>;
+
+/// Matches over all variants of an enum, binding the inner value to `$inner`.
+/// When `$wrap` is provided, the result is mapped into the same variant of `$wrap`.
+macro_rules! dispatch {
+    ($value: expr, $enum: ident, $inner: ident => $body: expr) => {
+        match $value {
+            #[cfg(feature = "src_postgres")]
+            $enum::Postgres($inner) => $body,
+            #[cfg(feature = "src_sqlite")]
+            $enum::SQLite($inner) => $body,
+            #[cfg(feature = "src_duckdb")]
+            $enum::DuckDB($inner) => $body,
+            #[cfg(feature = "src_mysql")]
+            $enum::MySQL($inner) => $body,
+        }
+    };
+    ($value: expr, $enum: ident, $inner: ident => $body: expr, $wrap: ident) => {
+        match $value {
+            #[cfg(feature = "src_postgres")]
+            $enum::Postgres($inner) => $body.map($wrap::Postgres),
+            #[cfg(feature = "src_sqlite")]
+            $enum::SQLite($inner) => $body.map($wrap::SQLite),
+            #[cfg(feature = "src_duckdb")]
+            $enum::DuckDB($inner) => $body.map($wrap::DuckDB),
+            #[cfg(feature = "src_mysql")]
+            $enum::MySQL($inner) => $body.map($wrap::MySQL),
+        }
+    };
+}
+
+/// Connection to one of the supported data stores, which implements [Connector],
+/// [SchemaGet] and [SchemaEdit] by dispatching to the wrapped connection.
+///
+/// Useful for applications that decide which data store to connect to at runtime.
+/// Because the data store is not known statically, [Connector::type_db_into_arrow]
+/// and [Connector::type_arrow_into_db] always return `None`.
+#[allow(clippy::large_enum_variant)]
+pub enum AnyConnection {
+    #[cfg(feature = "src_postgres")]
+    Postgres(PostgresConnection),
+    #[cfg(feature = "src_sqlite")]
+    SQLite(SQLiteConnection),
+    #[cfg(feature = "src_duckdb")]
+    DuckDB(DuckDBConnection),
+    #[cfg(feature = "src_mysql")]
+    MySQL(MySQLConn),
+}
+
+impl AnyConnection {
+    /// Connects to the data store, chosen by the scheme of the URL:
+    /// - `postgres://` or `postgresql://`, without TLS,
+    /// - `sqlite://<path>`, where an empty path or `:memory:` opens an in-memory database,
+    /// - `duckdb://<path>`, where an empty path or `:memory:` opens an in-memory database,
+    /// - `mysql://`.
+    ///
+    /// Returns [ConnectorError::UnsupportedUrlScheme] if the scheme is not recognized
+    /// or the feature of the data store is not enabled.
+    pub fn connect(url: &str) -> Result<Self, ConnectorError> {
+        let Some((scheme, _path)) = url.split_once("://") else {
+            return Err(ConnectorError::UnsupportedUrlScheme(url.to_string()));
+        };
+
+        match scheme {
+            #[cfg(feature = "src_postgres")]
+            "postgres" | "postgresql" => {
+                let client = postgres::Client::connect(url, postgres::NoTls)
+                    .map_err(crate::postgres::PostgresError::Postgres)?;
+                Ok(AnyConnection::Postgres(PostgresConnection::new(client)))
+            }
+            #[cfg(feature = "src_sqlite")]
+            "sqlite" => {
+                let conn = if is_in_memory(_path) {
+                    rusqlite::Connection::open_in_memory()?
+                } else {
+                    rusqlite::Connection::open(_path)?
+                };
+                Ok(AnyConnection::SQLite(SQLiteConnection::new(conn)))
+            }
+            #[cfg(feature = "src_duckdb")]
+            "duckdb" => {
+                let conn = if is_in_memory(_path) {
+                    duckdb::Connection::open_in_memory()?
+                } else {
+                    duckdb::Connection::open(_path)?
+                };
+                Ok(AnyConnection::DuckDB(DuckDBConnection::new(conn)))
+            }
+            #[cfg(feature = "src_mysql")]
+            "mysql" => {
+                let conn = mysql::Conn::new(url)?;
+                Ok(AnyConnection::MySQL(MySQLConnection::new(conn)))
+            }
+            _ => Err(ConnectorError::UnsupportedUrlScheme(scheme.to_string())),
+        }
+    }
+}
+
+#[cfg(any(feature = "src_sqlite", feature = "src_duckdb"))]
+fn is_in_memory(path: &str) -> bool {
+    path.is_empty() || path == ":memory:"
+}
+
+#[cfg(feature = "src_postgres")]
+impl From<PostgresConnection> for AnyConnection {
+    fn from(conn: PostgresConnection) -> Self {
+        AnyConnection::Postgres(conn)
+    }
+}
+
+#[cfg(feature = "src_sqlite")]
+impl From<SQLiteConnection> for AnyConnection {
+    fn from(conn: SQLiteConnection) -> Self {
+        AnyConnection::SQLite(conn)
+    }
+}
+
+#[cfg(feature = "src_duckdb")]
+impl From<DuckDBConnection> for AnyConnection {
+    fn from(conn: DuckDBConnection) -> Self {
+        AnyConnection::DuckDB(conn)
+    }
+}
+
+#[cfg(feature = "src_mysql")]
+impl From<MySQLConn> for AnyConnection {
+    fn from(conn: MySQLConn) -> Self {
+        AnyConnection::MySQL(conn)
+    }
+}
+
+impl Connector for AnyConnection {
+    type Stmt<'conn>
+        = AnyStatement<'conn>
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = AnyAppender<'conn>
+    where
+        Self: 'conn;
+
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.query(query), AnyStatement)
+    }
+
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.query_with(query, options), AnyStatement)
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.execute(query))
+    }
+
+    fn execute_batch(&mut self, statements: &[&str]) -> Result<Vec<u64>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.execute_batch(statements))
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.query_schema(query))
+    }
+
+    fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.append(table_name), AnyAppender)
+    }
+
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.append_with(table_name, options), AnyAppender)
+    }
+
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.delete_by_keys(table_name, key_columns, keys))
+    }
+
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.merge(table_name, key_columns, batch))
+    }
+
+    fn append_or_create(
+        &mut self,
+        table_name: &str,
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.append_or_create(table_name, batch))
+    }
+
+    fn load_parquet(&mut self, table_name: &str, path: &Path) -> Result<u64, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.load_parquet(table_name, path))
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.estimate_row_count(table_name))
+    }
+
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.explain_cost(query))
+    }
+
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.set_statement_cache_size(size))
+    }
+
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        dispatch!(self, AnyConnection, c => c.statement_cache_stats())
+    }
+
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.cancel_token())
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.list_catalogs())
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.begin_transaction())
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.end_transaction(commit))
+    }
+
+    fn type_db_into_arrow(_database_ty: &str) -> Option<DataType> {
+        None
+    }
+
+    fn type_arrow_into_db(_ty: &DataType) -> Option<String> {
+        None
+    }
+}
+
+impl SchemaGet for AnyConnection {
+    fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.table_list())
+    }
+
+    fn table_get(&mut self, name: &str) -> Result<SchemaRef, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.table_get(name))
+    }
+
+    fn table_exists(&mut self, name: &str) -> Result<bool, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.table_exists(name))
+    }
+
+    fn table_primary_key(&mut self, name: &str) -> Result<Vec<String>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.table_primary_key(name))
+    }
+
+    fn table_foreign_keys(&mut self, name: &str) -> Result<Vec<ForeignKey>, ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.table_foreign_keys(name))
+    }
+}
+
+impl SchemaEdit for AnyConnection {
+    fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        dispatch!(self, AnyConnection, c => c.table_create(name, schema))
+    }
+
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        dispatch!(self, AnyConnection, c => c.table_create_temp(name, schema))
+    }
+
+    fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
+        dispatch!(self, AnyConnection, c => c.table_drop(name))
+    }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.rename_table(from, to))
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyConnection, c => c.rename_column(table, from, to))
+    }
+}
+
+/// Statement of [AnyConnection].
+pub enum AnyStatement<'conn> {
+    #[cfg(feature = "src_postgres")]
+    Postgres(<PostgresConnection as Connector>::Stmt<'conn>),
+    #[cfg(feature = "src_sqlite")]
+    SQLite(<SQLiteConnection as Connector>::Stmt<'conn>),
+    #[cfg(feature = "src_duckdb")]
+    DuckDB(<DuckDBConnection as Connector>::Stmt<'conn>),
+    #[cfg(feature = "src_mysql")]
+    MySQL(<MySQLConn as Connector>::Stmt<'conn>),
+}
+
+impl<'conn> Statement<'conn> for AnyStatement<'conn> {
+    type Reader<'stmt>
+        = AnyReader<'conn, 'stmt>
+    where
+        Self: 'stmt;
+
+    fn start_named(
+        &mut self,
+        params: &[(&str, &dyn ArrowValue)],
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        dispatch!(self, AnyStatement, s => s.start_named(params), AnyReader)
+    }
+
+    fn start_batch(
+        &mut self,
+        args: (&RecordBatch, usize),
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        dispatch!(self, AnyStatement, s => s.start_batch(args), AnyReader)
+    }
+}
+
+/// Result reader of [AnyStatement].
+pub enum AnyReader<'conn: 'stmt, 'stmt> {
+    #[cfg(feature = "src_postgres")]
+    Postgres(<<PostgresConnection as Connector>::Stmt<'conn> as Statement<'conn>>::Reader<'stmt>),
+    #[cfg(feature = "src_sqlite")]
+    SQLite(<<SQLiteConnection as Connector>::Stmt<'conn> as Statement<'conn>>::Reader<'stmt>),
+    #[cfg(feature = "src_duckdb")]
+    DuckDB(<<DuckDBConnection as Connector>::Stmt<'conn> as Statement<'conn>>::Reader<'stmt>),
+    #[cfg(feature = "src_mysql")]
+    MySQL(<<MySQLConn as Connector>::Stmt<'conn> as Statement<'conn>>::Reader<'stmt>),
+}
+
+impl<'stmt> ResultReader<'stmt> for AnyReader<'_, 'stmt> {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        dispatch!(self, AnyReader, r => r.get_schema())
+    }
+}
+
+impl Iterator for AnyReader<'_, '_> {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        dispatch!(self, AnyReader, r => r.next())
+    }
+}
+
+/// Appender of [AnyConnection].
+pub enum AnyAppender<'conn> {
+    #[cfg(feature = "src_postgres")]
+    Postgres(<PostgresConnection as Connector>::Append<'conn>),
+    #[cfg(feature = "src_sqlite")]
+    SQLite(<SQLiteConnection as Connector>::Append<'conn>),
+    #[cfg(feature = "src_duckdb")]
+    DuckDB(<DuckDBConnection as Connector>::Append<'conn>),
+    #[cfg(feature = "src_mysql")]
+    MySQL(<MySQLConn as Connector>::Append<'conn>),
+}
+
+impl<'conn> Append<'conn> for AnyAppender<'conn> {
+    fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyAppender, a => a.append(batch))
+    }
+
+    fn finish(self) -> Result<(), ConnectorError> {
+        dispatch!(self, AnyAppender, a => a.finish())
+    }
+}
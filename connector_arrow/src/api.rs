@@ -4,13 +4,19 @@
 //! - [Connector], providing [Connector::query] and [Connector::append] functions,
 //! - [SchemaGet], for schema introspection,
 //! - [SchemaEdit], for basic schema migration commands,
+//! - [AsyncConnector], an asynchronous variant of [Connector].
 
 use std::any::Any;
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
 
 use arrow::datatypes::{DataType, SchemaRef};
 use arrow::record_batch::RecordBatch;
 
 use crate::errors::{ConnectorError, TableCreateError, TableDropError};
+use crate::transaction::Transaction;
+use crate::util::{ArrowReader, TimeoutReader};
 
 /// Ability to query data from a data store and append data into the data store.
 pub trait Connector {
@@ -25,9 +31,246 @@ pub trait Connector {
     /// Prepare a query to the data store, using data store's preferred query language.
     fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError>;
 
+    /// Execute a statement that does not return rows (i.e. DDL, `UPDATE` or `DELETE`).
+    /// Returns the number of affected rows.
+    ///
+    /// Default implementation runs the statement via [Connector::query], discards
+    /// its results and returns 0.
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let mut stmt = self.query(query)?;
+        let reader = stmt.start([])?;
+        for batch in reader {
+            batch?;
+        }
+        Ok(0)
+    }
+
+    /// Execute multiple statements in a single transaction. If any of the statements
+    /// fails, the transaction is rolled back and the error is returned.
+    /// Returns the number of affected rows for each of the statements.
+    ///
+    /// Note that some data stores (i.e. MySQL) implicitly commit DDL statements,
+    /// which cannot be rolled back.
+    fn execute_batch(&mut self, statements: &[&str]) -> Result<Vec<u64>, ConnectorError>
+    where
+        Self: Sized,
+    {
+        crate::dml::execute_batch(self, statements, ["BEGIN", "COMMIT", "ROLLBACK"])
+    }
+
+    /// Describe the schema of the result of a query, without reading its rows.
+    ///
+    /// Backends that can describe a prepared statement do not execute the query.
+    /// Default implementation executes the query wrapped into
+    /// `SELECT * FROM (...) LIMIT 0`, which is cheap, but requires the query to be a `SELECT`.
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        let query = query.trim().trim_end_matches(';');
+        let query = format!("SELECT * FROM ({query}) AS _q LIMIT 0");
+
+        let mut stmt = self.query(&query)?;
+        let mut reader = stmt.start([])?;
+        reader.get_schema()
+    }
+
+    /// Prepare a query, with non-default options for reading its results.
+    ///
+    /// Default implementation supports only [ReadOptions::on_unexpected_null],
+    /// assuming that the connector reads all columns as nullable.
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        if options.prefetch_rows.is_some() || options.batch_size.is_some() {
+            return Err(ConnectorError::NotSupported {
+                connector_name: std::any::type_name::<Self>(),
+                feature: "read options",
+            });
+        }
+        self.query(query)
+    }
+
     /// Prepare an appender for the given table.
     fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError>;
 
+    /// Prepare an appender for the given table, with non-default options.
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        if options != AppenderOptions::default() {
+            return Err(ConnectorError::NotSupported {
+                connector_name: std::any::type_name::<Self>(),
+                feature: "appender options",
+            });
+        }
+        self.append(table_name)
+    }
+
+    /// Delete rows of a table that match any of the keys.
+    ///
+    /// `keys` must contain a column for each of the `key_columns`. Rows are deleted
+    /// with parameterized `DELETE ... WHERE (k1, k2) IN (...)` statements, chunked
+    /// so the number of parameters stays within the data store limits.
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &RecordBatch,
+    ) -> Result<(), ConnectorError>
+    where
+        Self: Sized,
+    {
+        let placeholder = |i| format!("${i}");
+        let ident = crate::util::escape::escaped_ident;
+        crate::dml::delete_by_keys(self, table_name, key_columns, keys, placeholder, ident)
+    }
+
+    /// Upsert rows of a batch into a table: rows that match an existing row on all
+    /// `key_columns` replace values of that row and other rows are inserted.
+    ///
+    /// Data stores that support `MERGE` (PostgreSQL 15+, MS SQL Server) execute a
+    /// single `MERGE` statement per chunk of rows. MySQL uses `INSERT ... ON DUPLICATE KEY UPDATE`,
+    /// so it requires a unique key over `key_columns`. Default implementation falls back to
+    /// [Connector::delete_by_keys], followed by [Connector::append], within a transaction.
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError>
+    where
+        Self: Sized,
+    {
+        crate::dml::merge_by_delete(self, table_name, key_columns, batch)
+    }
+
+    /// Append a batch into a table, creating the table first if it does not exist.
+    ///
+    /// The table is created via [SchemaEdit::table_create], with the schema of the
+    /// batch. If the table exists, but its columns do not match the columns of the batch,
+    /// [ConnectorError::TableSchemaMismatch] is returned, listing the mismatched columns.
+    fn append_or_create(
+        &mut self,
+        table_name: &str,
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError>
+    where
+        Self: SchemaGet + SchemaEdit + Sized,
+    {
+        crate::dml::append_or_create(self, table_name, batch)
+    }
+
+    /// Load all rows of a Parquet file into an existing table.
+    /// Returns the number of loaded rows.
+    ///
+    /// Data stores that can read Parquet files (DuckDB) load the file without decoding
+    /// it in Rust. Default implementation reads the file with the `parquet` crate and
+    /// appends its batches in a single [Connector::append], which requires feature `src_parquet`.
+    fn load_parquet(&mut self, table_name: &str, path: &Path) -> Result<u64, ConnectorError>
+    where
+        Self: Sized,
+    {
+        crate::dml::load_parquet(self, table_name, path)
+    }
+
+    /// Estimate the number of rows in a table.
+    ///
+    /// Where available, the estimate is read from catalog statistics, which is
+    /// cheap but only approximate: it may be stale until the data store refreshes
+    /// its statistics (i.e. after `ANALYZE`).
+    ///
+    /// Default implementation falls back to an exact `SELECT COUNT(*)`, which
+    /// requires a full scan.
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError>
+    where
+        Self: Sized,
+    {
+        crate::dml::count_rows(self, table_name)
+    }
+
+    /// Estimate the number of result rows and the cost of a query, as reported by
+    /// the query planner of the data store. The query is planned, but not executed,
+    /// and must not contain parameters.
+    ///
+    /// Default implementation returns [ConnectorError::NotSupported].
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        let _ = query;
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "explain_cost",
+        })
+    }
+
+    /// Set the maximum number of prepared statements that are kept by the connection
+    /// and reused when the same query is prepared again. Size of 0 disables the cache.
+    ///
+    /// Default implementation returns [ConnectorError::NotSupported].
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        let _ = size;
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "statement cache",
+        })
+    }
+
+    /// Counters of the prepared statement cache, or None if the connector does not
+    /// have a statement cache.
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        None
+    }
+
+    /// Returns a token that can cancel queries of this connection from another thread.
+    /// A cancelled query fails with [ConnectorError::Cancelled].
+    ///
+    /// Default implementation returns [ConnectorError::NotSupported].
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "query cancellation",
+        })
+    }
+
+    /// Lists names of databases (catalogs) on the server of this connection.
+    ///
+    /// Default implementation returns [ConnectorError::NotSupported].
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "list_catalogs",
+        })
+    }
+
+    /// Start a transaction, which can group queries, statements and appends
+    /// into a single unit of work that is committed or rolled back as a whole.
+    ///
+    /// The transaction is rolled back when the returned guard is dropped without
+    /// calling [Transaction::commit].
+    fn transaction(&mut self) -> Result<Transaction<'_, Self>, ConnectorError>
+    where
+        Self: Sized,
+    {
+        Transaction::begin(self)
+    }
+
+    /// Begin a transaction. Prefer [Connector::transaction], which also ends it.
+    ///
+    /// Default implementation executes `BEGIN`.
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.execute("BEGIN")?;
+        Ok(())
+    }
+
+    /// End the transaction started by [Connector::begin_transaction], by either
+    /// committing or rolling back its changes.
+    ///
+    /// Default implementation executes `COMMIT` or `ROLLBACK`.
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.execute(if commit { "COMMIT" } else { "ROLLBACK" })?;
+        Ok(())
+    }
+
     /// Describes how database types map into the arrow types.
     /// None means that querying this type will return [DataType::Binary] with field
     /// metadata [METADATA_DB_TYPE] set to original type name.
@@ -39,6 +282,28 @@ pub trait Connector {
     fn type_arrow_into_db(_ty: &DataType) -> Option<String>;
 }
 
+/// Ability to query data from a data store and append data into the data store,
+/// without blocking the thread while waiting for the data store.
+///
+/// Unlike [Connector], results of a query are collected into memory before they are returned.
+pub trait AsyncConnector {
+    /// Execute a query and read all of its results.
+    fn query(
+        &mut self,
+        query: &str,
+    ) -> impl Future<Output = Result<ArrowReader, ConnectorError>> + Send;
+
+    /// Execute a statement that does not return rows. Returns the number of affected rows.
+    fn execute(&mut self, query: &str) -> impl Future<Output = Result<u64, ConnectorError>> + Send;
+
+    /// Append the batches into the table, in a single transaction.
+    fn append(
+        &mut self,
+        table_name: &str,
+        batches: Vec<RecordBatch>,
+    ) -> impl Future<Output = Result<(), ConnectorError>> + Send;
+}
+
 /// A task that is to be executed in the data store, over a connection.
 pub trait Statement<'conn> {
     type Reader<'stmt>: ResultReader<'stmt>
@@ -56,6 +321,48 @@ pub trait Statement<'conn> {
         self.start_batch((&batch, 0))
     }
 
+    /// Execute this statement once, cancelling it if its result is not read within the timeout.
+    /// Returns a reader that can retrieve the result schema and data.
+    ///
+    /// `token` must be obtained from the connection of this statement, via
+    /// [Connector::cancel_token]. The timeout applies only to this execution and does not
+    /// change any settings of the connection. When the statement was cancelled because
+    /// of the timeout, the reader yields [ConnectorError::Timeout].
+    fn start_with_timeout<'p, I>(
+        &mut self,
+        args: I,
+        token: CancelToken,
+        timeout: Duration,
+    ) -> Result<TimeoutReader<Self::Reader<'_>>, ConnectorError>
+    where
+        I: IntoIterator<Item = &'p dyn ArrowValue>,
+    {
+        let timer = crate::util::Timer::start(token, timeout);
+        match self.start(args) {
+            Ok(reader) => Ok(TimeoutReader::new(reader, timer)),
+            Err(e) => Err(timer.map_err(e)),
+        }
+    }
+
+    /// Execute this statement once, binding parameters by name.
+    /// Returns a reader that can retrieve the result schema and data.
+    ///
+    /// Placeholder syntax depends on the data store:
+    /// - PostgreSQL: `:name` (rewritten to positional `$N` when the statement is prepared),
+    /// - SQLite: `:name`, `@name` or `$name`.
+    ///
+    /// Returns [ConnectorError::UnboundParameter] if a placeholder has no value.
+    fn start_named(
+        &mut self,
+        params: &[(&str, &dyn ArrowValue)],
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        let _ = params;
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "named parameters",
+        })
+    }
+
     /// Execute this statement once.
     /// Query arguments are read from record batch, from the specified row.
     /// Returns a reader that can retrieve the result schema and data.
@@ -75,10 +382,142 @@ pub trait ResultReader<'stmt>: Iterator<Item = Result<RecordBatch, ConnectorErro
 /// that this field was created from.
 pub const METADATA_DB_TYPE: &str = "db_type";
 
+/// Options for [Connector::query_with].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Number of rows fetched from the data store in one round-trip.
+    /// By default, the data store sends all rows of the result at once.
+    pub prefetch_rows: Option<usize>,
+
+    /// Number of rows in each of the produced record batches.
+    /// By default, the connector chooses the size.
+    pub batch_size: Option<usize>,
+
+    /// What to do when a column that the data store declared as non-nullable contains a NULL.
+    pub on_unexpected_null: NullPolicy,
+}
+
+/// Handling of NULLs in columns that are declared as non-nullable, see [ReadOptions].
+///
+/// Only MySQL declares result columns as non-nullable, other connectors read all
+/// columns as nullable, so these policies have the same effect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NullPolicy {
+    /// Return [ConnectorError::DataSchemaMismatch].
+    #[default]
+    Error,
+
+    /// Read all columns as nullable, so a NULL is read as any other value.
+    Promote,
+}
+
+/// Options for [Connector::append_with].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppenderOptions {
+    /// For columns that are `NOT NULL` and have a default value, replace nulls with
+    /// the default value. Nulls in `NOT NULL` columns without a default produce an error.
+    pub null_as_default: bool,
+
+    /// What to do with rows that conflict with existing rows on a unique constraint.
+    pub conflict: ConflictMode,
+}
+
+/// Handling of appended rows that violate a unique constraint, see [AppenderOptions].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConflictMode {
+    /// Return an error.
+    #[default]
+    Error,
+
+    /// Skip the conflicting rows.
+    Ignore,
+
+    /// Update existing rows with values of the conflicting rows.
+    /// Contains the columns of the unique constraint that the rows conflict on.
+    Replace(Vec<String>),
+}
+
+impl ConflictMode {
+    /// Checks that all conflict columns exist in the table.
+    #[cfg(any(
+        feature = "src_postgres",
+        feature = "src_sqlite",
+        feature = "src_mysql"
+    ))]
+    pub(crate) fn validate(
+        &self,
+        table_name: &str,
+        columns: &[&str],
+    ) -> Result<(), ConnectorError> {
+        let ConflictMode::Replace(conflict_columns) = self else {
+            return Ok(());
+        };
+        for column in conflict_columns {
+            if !columns.contains(&column.as_str()) {
+                return Err(ConnectorError::ColumnNotFound {
+                    table_name: table_name.to_string(),
+                    column: column.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Estimates of the query planner, see [Connector::explain_cost].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueryCost {
+    /// Estimated number of rows of the result.
+    pub estimated_rows: u64,
+    /// Estimated cost of executing the query, in units of the data store.
+    /// It can be compared only to other estimates of the same data store.
+    pub estimated_cost: f64,
+}
+
+/// Counters of the prepared statement cache, see [Connector::set_statement_cache_size].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    /// Number of prepared queries that were found in the cache.
+    pub hits: u64,
+    /// Number of prepared queries that were not found in the cache.
+    pub misses: u64,
+    /// Number of statements currently in the cache.
+    pub size: usize,
+}
+
+/// Cancels the query that is currently executing on a connection, see [Connector::cancel_token].
+pub struct CancelToken {
+    cancel: Box<dyn Fn() -> Result<(), ConnectorError> + Send + Sync>,
+}
+
+impl CancelToken {
+    /// Creates a token from a function that requests the cancellation.
+    pub fn new<F>(cancel: F) -> Self
+    where
+        F: Fn() -> Result<(), ConnectorError> + Send + Sync + 'static,
+    {
+        CancelToken {
+            cancel: Box::new(cancel),
+        }
+    }
+
+    /// Requests cancellation of the query that is currently executing on the connection.
+    /// Does nothing if no query is executing.
+    ///
+    /// Cancellation is a race: the query might complete before the request is processed.
+    pub fn cancel(&self) -> Result<(), ConnectorError> {
+        (self.cancel)()
+    }
+}
+
+impl std::fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CancelToken")
+    }
+}
+
 /// Receive [RecordBatch]es that have to be written to a table in the data store.
 pub trait Append<'conn> {
-    // TODO: add ON CONFLICT parameter
-
     fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError>;
 
     fn finish(self) -> Result<(), ConnectorError>;
@@ -86,16 +525,99 @@ pub trait Append<'conn> {
 
 /// Schema introspection
 pub trait SchemaGet {
+    /// List names of tables in the current schema (or database).
+    ///
+    /// Uses a catalog query that does not read column definitions, so it is cheap even for
+    /// schemas with many tables. Views and tables internal to the data store are skipped.
     fn table_list(&mut self) -> Result<Vec<String>, ConnectorError>;
 
     fn table_get(&mut self, name: &str) -> Result<SchemaRef, ConnectorError>;
+
+    /// Checks if a table is listed by [SchemaGet::table_list].
+    ///
+    /// Names are compared according to the identifier rules of the data store:
+    /// case-sensitive by default, case-insensitive for SQLite and DuckDB and
+    /// depending on `lower_case_table_names` for MySQL.
+    fn table_exists(&mut self, name: &str) -> Result<bool, ConnectorError> {
+        Ok(self.table_list()?.iter().any(|t| t == name))
+    }
+
+    /// Names of the columns that form the primary key of the table, in key order.
+    /// Tables without a primary key yield an empty vec.
+    fn table_primary_key(&mut self, name: &str) -> Result<Vec<String>, ConnectorError> {
+        let _ = name;
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "primary key introspection",
+        })
+    }
+
+    /// Foreign keys of the table, ordered by the name of the constraint, or by declaration
+    /// when the data store does not name them. Tables without foreign keys yield an empty vec.
+    fn table_foreign_keys(&mut self, name: &str) -> Result<Vec<ForeignKey>, ConnectorError> {
+        let _ = name;
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "foreign key introspection",
+        })
+    }
+}
+
+/// A foreign key constraint, see [SchemaGet::table_foreign_keys].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKey {
+    /// Columns of the table that reference the other table, in key order.
+    pub columns: Vec<String>,
+
+    pub referenced_table: String,
+
+    /// Columns of the referenced table, matching `columns` by position.
+    pub referenced_columns: Vec<String>,
 }
 
 /// Schema migration
 pub trait SchemaEdit {
     fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError>;
 
+    /// Create a temporary table, which is visible only to this connection and
+    /// is dropped by the data store when the connection (session) is closed.
+    ///
+    /// Useful for staging data before merging it into other tables.
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        let _ = (name, schema);
+        Err(TableCreateError::Connector(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "temporary tables",
+        }))
+    }
+
     fn table_drop(&mut self, name: &str) -> Result<(), TableDropError>;
+
+    /// Like [SchemaEdit::table_drop], but does not error when the table does not exist.
+    fn table_drop_if_exists(&mut self, name: &str) -> Result<(), ConnectorError> {
+        match self.table_drop(name) {
+            Ok(()) | Err(TableDropError::TableNonexistent) => Ok(()),
+            Err(TableDropError::Connector(e)) => Err(e),
+        }
+    }
+
+    /// Rename a table.
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let _ = (from, to);
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "renaming tables",
+        })
+    }
+
+    /// Rename a column of a table.
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let _ = (table, from, to);
+        Err(ConnectorError::NotSupported {
+            connector_name: std::any::type_name::<Self>(),
+            feature: "renaming columns",
+        })
+    }
 }
 
 /// A value from the Arrow type system.
@@ -0,0 +1,417 @@
+//! Generic data-manipulation and data-inspection statements, composed from [Connector::query].
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, RecordBatch};
+use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+use itertools::Itertools;
+
+use crate::api::{Append, Connector, SchemaEdit, SchemaGet, Statement};
+use crate::util::escape::{escaped_ident, EscapedIdent};
+use crate::{ConnectorError, TableCreateError};
+
+/// Maximum number of parameters bound to a single statement.
+/// This is the lowest limit of all supported data stores (SQLite before 3.32).
+const MAX_PARAMS: usize = 999;
+
+/// Delete rows of a table that match any of the keys, using `DELETE` statements.
+///
+/// Keys are bound as parameters, with placeholders produced by `placeholder`
+/// from 1-based parameter index and identifiers escaped by `ident`.
+pub(crate) fn delete_by_keys<C: Connector>(
+    conn: &mut C,
+    table_name: &str,
+    key_columns: &[&str],
+    keys: &RecordBatch,
+    placeholder: fn(usize) -> String,
+    ident: fn(&str) -> EscapedIdent<'_>,
+) -> Result<(), ConnectorError> {
+    if key_columns.is_empty() || keys.num_rows() == 0 {
+        return Ok(());
+    }
+
+    let key_arrays: Vec<_> = key_columns
+        .iter()
+        .map(|name| keys.column_by_name(name).cloned())
+        .collect::<Option<_>>()
+        .ok_or_else(|| {
+            ConnectorError::DataSchemaMismatch("keys batch does not contain all key columns".into())
+        })?;
+
+    let key_count = key_columns.len();
+    let rows_per_chunk = (MAX_PARAMS / key_count).max(1);
+
+    let keys_sql = key_columns.iter().map(|k| ident(k)).join(", ");
+    let keys_sql = if key_count == 1 {
+        keys_sql
+    } else {
+        format!("({keys_sql})")
+    };
+
+    for chunk_start in (0..keys.num_rows()).step_by(rows_per_chunk) {
+        let chunk_len = rows_per_chunk.min(keys.num_rows() - chunk_start);
+
+        // one row of params, with each key of each row as a separate column
+        let mut fields = Vec::with_capacity(chunk_len * key_count);
+        let mut params: Vec<ArrayRef> = Vec::with_capacity(chunk_len * key_count);
+        let mut tuples = Vec::with_capacity(chunk_len);
+        for row in chunk_start..(chunk_start + chunk_len) {
+            let mut placeholders = Vec::with_capacity(key_count);
+            for array in &key_arrays {
+                params.push(array.slice(row, 1));
+                fields.push(Field::new("", array.data_type().clone(), true));
+                placeholders.push(placeholder(params.len()));
+            }
+
+            let tuple = placeholders.join(", ");
+            tuples.push(if key_count == 1 {
+                tuple
+            } else {
+                format!("({tuple})")
+            });
+        }
+        let params = RecordBatch::try_new(Arc::new(Schema::new(fields)), params)?;
+
+        let query = format!(
+            "DELETE FROM {} WHERE {keys_sql} IN ({})",
+            ident(table_name),
+            tuples.join(", ")
+        );
+        log::debug!("query: {query}");
+
+        let mut stmt = conn.query(&query)?;
+        let reader = stmt.start_batch((&params, 0))?;
+        for batch in reader {
+            batch?;
+        }
+    }
+    Ok(())
+}
+
+/// Upsert rows of a batch into a table, using a `MERGE` statement.
+///
+/// Rows are matched on the `key_columns`. Matched rows have all other columns updated,
+/// and rows that are not matched are inserted. Batch is bound as parameters, with
+/// placeholders produced by `placeholder` from 1-based parameter index.
+#[cfg(any(feature = "src_postgres", feature = "src_tiberius"))]
+pub(crate) fn merge<C: Connector>(
+    conn: &mut C,
+    table_name: &str,
+    key_columns: &[&str],
+    batch: &RecordBatch,
+    placeholder: fn(usize) -> String,
+) -> Result<(), ConnectorError> {
+    if batch.num_rows() == 0 {
+        return Ok(());
+    }
+
+    let schema = batch.schema();
+    if key_columns.is_empty()
+        || !key_columns
+            .iter()
+            .all(|k| schema.column_with_name(k).is_some())
+    {
+        return Err(ConnectorError::DataSchemaMismatch(
+            "batch does not contain all key columns".into(),
+        ));
+    }
+
+    let columns = schema.fields().iter().map(|f| f.name()).collect_vec();
+    let columns_sql = columns.iter().map(|c| escaped_ident(c)).join(", ");
+    let on_sql = key_columns
+        .iter()
+        .map(|k| format!("_t.{k} = _s.{k}", k = escaped_ident(k)))
+        .join(" AND ");
+    let update_sql = columns
+        .iter()
+        .filter(|c| !key_columns.contains(&c.as_str()))
+        .map(|c| format!("{c} = _s.{c}", c = escaped_ident(c)))
+        .join(", ");
+    let matched_sql = if update_sql.is_empty() {
+        String::new()
+    } else {
+        format!(" WHEN MATCHED THEN UPDATE SET {update_sql}")
+    };
+    let insert_sql = columns
+        .iter()
+        .map(|c| format!("_s.{}", escaped_ident(c)))
+        .join(", ");
+
+    // placeholders are cast, so data store can infer types of the VALUES columns
+    let casts = schema
+        .fields()
+        .iter()
+        .map(|f| C::type_arrow_into_db(f.data_type()))
+        .collect_vec();
+
+    let rows_per_chunk = (MAX_PARAMS / columns.len()).max(1);
+    for chunk_start in (0..batch.num_rows()).step_by(rows_per_chunk) {
+        let chunk_len = rows_per_chunk.min(batch.num_rows() - chunk_start);
+
+        let mut fields = Vec::with_capacity(chunk_len * columns.len());
+        let mut params: Vec<ArrayRef> = Vec::with_capacity(chunk_len * columns.len());
+        let mut tuples = Vec::with_capacity(chunk_len);
+        for row in chunk_start..(chunk_start + chunk_len) {
+            let mut placeholders = Vec::with_capacity(columns.len());
+            for (array, cast) in batch.columns().iter().zip(&casts) {
+                params.push(array.slice(row, 1));
+                fields.push(Field::new("", array.data_type().clone(), true));
+
+                let p = placeholder(params.len());
+                placeholders.push(match cast {
+                    Some(ty) => format!("CAST({p} AS {ty})"),
+                    None => p,
+                });
+            }
+            tuples.push(format!("({})", placeholders.join(", ")));
+        }
+        let params = RecordBatch::try_new(Arc::new(Schema::new(fields)), params)?;
+
+        let query = format!(
+            "MERGE INTO {table} AS _t \
+            USING (VALUES {values}) AS _s ({columns_sql}) ON {on_sql}{matched_sql} \
+            WHEN NOT MATCHED THEN INSERT ({columns_sql}) VALUES ({insert_sql});",
+            table = escaped_ident(table_name),
+            values = tuples.join(", "),
+        );
+        log::debug!("query: {query}");
+
+        let mut stmt = conn.query(&query)?;
+        let reader = stmt.start_batch((&params, 0))?;
+        for batch in reader {
+            batch?;
+        }
+    }
+    Ok(())
+}
+
+/// Upsert rows of a batch into a table by deleting rows that match the keys
+/// and appending the batch, within a transaction of the connection.
+pub(crate) fn merge_by_delete<C: Connector>(
+    conn: &mut C,
+    table_name: &str,
+    key_columns: &[&str],
+    batch: &RecordBatch,
+) -> Result<(), ConnectorError> {
+    conn.begin_transaction()?;
+
+    if let Err(e) = delete_and_append(conn, table_name, key_columns, batch) {
+        // report the original error, even if rollback fails
+        if let Err(rollback_err) = conn.end_transaction(false) {
+            log::warn!("rollback failed: {rollback_err}");
+        }
+        return Err(e);
+    }
+
+    conn.end_transaction(true)
+}
+
+/// Deletes rows that match the keys and appends the batch.
+/// Is atomic only when executed within a transaction.
+pub(crate) fn delete_and_append<C: Connector>(
+    conn: &mut C,
+    table_name: &str,
+    key_columns: &[&str],
+    batch: &RecordBatch,
+) -> Result<(), ConnectorError> {
+    conn.delete_by_keys(table_name, key_columns, batch)?;
+
+    let mut appender = conn.append(table_name)?;
+    appender.append(batch.clone())?;
+    appender.finish()
+}
+
+/// Executes statements within a transaction, which is controlled by
+/// `begin`, `commit` and `rollback` statements of the data store.
+pub(crate) fn execute_batch<C: Connector>(
+    conn: &mut C,
+    statements: &[&str],
+    [begin, commit, rollback]: [&str; 3],
+) -> Result<Vec<u64>, ConnectorError> {
+    conn.execute(begin)?;
+
+    let mut affected = Vec::with_capacity(statements.len());
+    for statement in statements {
+        match conn.execute(statement) {
+            Ok(a) => affected.push(a),
+            Err(e) => {
+                // report the original error, even if rollback fails
+                if let Err(rollback_err) = conn.execute(rollback) {
+                    log::warn!("rollback failed: {rollback_err}");
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    conn.execute(commit)?;
+    Ok(affected)
+}
+
+pub(crate) fn append_or_create<C: Connector + SchemaGet + SchemaEdit>(
+    conn: &mut C,
+    table_name: &str,
+    batch: &RecordBatch,
+) -> Result<(), ConnectorError> {
+    let exists = conn.table_list()?.iter().any(|t| t == table_name);
+    if exists {
+        let table_schema = conn.table_get(table_name)?;
+        let mismatched = schema_mismatches::<C>(&table_schema, &batch.schema());
+        if !mismatched.is_empty() {
+            return Err(ConnectorError::TableSchemaMismatch {
+                table_name: table_name.to_string(),
+                columns: mismatched,
+            });
+        }
+    } else {
+        match conn.table_create(table_name, batch.schema()) {
+            Ok(()) => {}
+            Err(TableCreateError::TableExists) => {}
+            Err(TableCreateError::Connector(e)) => return Err(e),
+        }
+    }
+
+    let mut appender = conn.append(table_name)?;
+    appender.append(batch.clone())?;
+    appender.finish()
+}
+
+#[cfg(feature = "src_parquet")]
+pub(crate) fn load_parquet<C: Connector>(
+    conn: &mut C,
+    table_name: &str,
+    path: &std::path::Path,
+) -> Result<u64, ConnectorError> {
+    // connection to the file resolves its name against its parent directory
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut file = crate::parquet::ParquetConnection::new(path);
+    let mut stmt = file.query(&file_name)?;
+    let reader = stmt.start([])?;
+
+    let mut appender = conn.append(table_name)?;
+    let mut rows = 0;
+    for batch in reader {
+        let batch = batch?;
+        rows += batch.num_rows() as u64;
+        appender.append(batch)?;
+    }
+    appender.finish()?;
+    Ok(rows)
+}
+
+#[cfg(not(feature = "src_parquet"))]
+pub(crate) fn load_parquet<C: Connector>(
+    _conn: &mut C,
+    _table_name: &str,
+    _path: &std::path::Path,
+) -> Result<u64, ConnectorError> {
+    Err(ConnectorError::NotSupported {
+        connector_name: std::any::type_name::<C>(),
+        feature: "load_parquet without feature src_parquet",
+    })
+}
+
+/// Describes columns of the batch that cannot be appended into the table.
+///
+/// Types of the table are compared to the types of the batch, after they have been
+/// converted into the database type and back, since that is the type a column would
+/// get when created from this batch.
+fn schema_mismatches<C: Connector>(table: &Schema, batch: &Schema) -> Vec<String> {
+    let mut mismatched = Vec::new();
+    for field in batch.fields() {
+        let Ok(table_field) = table.field_with_name(field.name()) else {
+            mismatched.push(format!("{} (missing in table)", field.name()));
+            continue;
+        };
+
+        let batch_ty = field.data_type();
+        let created_ty = C::type_arrow_into_db(batch_ty).and_then(|t| C::type_db_into_arrow(&t));
+        let table_ty = table_field.data_type();
+        if table_ty != batch_ty && Some(table_ty) != created_ty.as_ref() {
+            mismatched.push(format!(
+                "{} (table has {table_ty}, batch has {batch_ty})",
+                field.name()
+            ));
+        }
+    }
+    for field in table.fields() {
+        if batch.field_with_name(field.name()).is_err() {
+            mismatched.push(format!("{} (missing in batch)", field.name()));
+        }
+    }
+    mismatched
+}
+
+pub(crate) fn count_rows<C: Connector>(
+    conn: &mut C,
+    table_name: &str,
+) -> Result<u64, ConnectorError> {
+    let query = format!("SELECT COUNT(*) FROM {}", escaped_ident(table_name));
+    let batches = crate::query(conn, &query)?;
+
+    let count = batches
+        .iter()
+        .find(|b| b.num_rows() > 0)
+        .ok_or(ConnectorError::NoResultSets)?
+        .column(0);
+    let count = arrow::compute::cast(count, &DataType::UInt64)?;
+    Ok(count.as_primitive::<UInt64Type>().value(0))
+}
+
+/// Splits a query into queries that each return rows of one range of the
+/// partition column. Ranges are computed from min and max of the column,
+/// which must be numeric. Their union returns all rows of the original query.
+#[cfg(feature = "pool")]
+pub(crate) fn partition_queries<C: Connector>(
+    conn: &mut C,
+    base_query: &str,
+    partition_column: &str,
+    num_partitions: usize,
+) -> Result<Vec<String>, ConnectorError> {
+    use arrow::array::Array;
+    use arrow::datatypes::Int64Type;
+
+    let base_query = base_query.trim().trim_end_matches(';');
+    let column = escaped_ident(partition_column);
+
+    let query = format!("SELECT MIN({column}), MAX({column}) FROM ({base_query}) AS _q");
+    let batches = crate::query(conn, &query)?;
+    let bounds = batches
+        .iter()
+        .find(|b| b.num_rows() > 0)
+        .ok_or(ConnectorError::NoResultSets)?;
+    let min = arrow::compute::cast(bounds.column(0), &DataType::Int64)?;
+    let max = arrow::compute::cast(bounds.column(1), &DataType::Int64)?;
+    let (min, max) = (
+        min.as_primitive::<Int64Type>(),
+        max.as_primitive::<Int64Type>(),
+    );
+    if min.is_null(0) || max.is_null(0) {
+        // there are no rows with non-null values in the partition column
+        return Ok(vec![base_query.to_string()]);
+    }
+    let (min, max) = (min.value(0) as i128, max.value(0) as i128);
+
+    let num_partitions = (num_partitions as i128).min(max - min + 1);
+    if num_partitions <= 1 {
+        return Ok(vec![base_query.to_string()]);
+    }
+    let step = (max - min + 1 + num_partitions - 1) / num_partitions;
+
+    let queries = (0..num_partitions).map(|i| {
+        let lower = min + i * step;
+        let upper = lower + step;
+
+        // first and last partitions are unbounded, so all rows are included
+        // even when values were truncated when casting into Int64
+        let condition = if i == 0 {
+            format!("{column} < {upper} OR {column} IS NULL")
+        } else if i == num_partitions - 1 {
+            format!("{column} >= {lower}")
+        } else {
+            format!("{column} >= {lower} AND {column} < {upper}")
+        };
+        format!("SELECT * FROM ({base_query}) AS _q WHERE {condition}")
+    });
+    Ok(queries.collect())
+}
@@ -5,9 +5,9 @@ use itertools::zip_eq;
 use itertools::Itertools;
 
 use crate::impl_consume_unsupported;
-use crate::types::{ArrowType, FixedSizeBinaryType, NullType};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType, NullType};
 use crate::util::transport::{self, Consume, ConsumeTy};
-use crate::util::ArrayCellRef;
+use crate::util::{coerce, ArrayCellRef};
 use crate::{api::Append, ConnectorError};
 
 pub struct DuckDBAppender<'conn> {
@@ -16,6 +16,7 @@ pub struct DuckDBAppender<'conn> {
 
 impl<'conn> Append<'conn> for DuckDBAppender<'conn> {
     fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError> {
+        let batch = coerce::decode_dictionaries(batch)?;
         let schema = batch.schema();
         let mut cell_refs = zip_eq(batch.columns(), schema.fields())
             .map(|(array, field)| ArrayCellRef {
@@ -87,6 +88,32 @@ impl ConsumeTy<TimestampMicrosecondType> for Vec<Value> {
     }
 }
 
+// duckdb crate cannot bind `Value::Decimal`, so decimals are passed as text,
+// which DuckDB casts into the type of the column
+impl ConsumeTy<Decimal128Type> for Vec<Value> {
+    fn consume(&mut self, ty: &DataType, value: i128) {
+        self.push(Value::Text(crate::util::decimal::decimal128_to_string(
+            ty, value,
+        )));
+    }
+
+    fn consume_null(&mut self, _ty: &DataType) {
+        self.push(Value::Null);
+    }
+}
+
+impl ConsumeTy<Decimal256Type> for Vec<Value> {
+    fn consume(&mut self, ty: &DataType, value: i256) {
+        self.push(Value::Text(crate::util::decimal::decimal256_to_string(
+            ty, value,
+        )));
+    }
+
+    fn consume_null(&mut self, _ty: &DataType) {
+        self.push(Value::Null);
+    }
+}
+
 impl_consume_ty!(BooleanType, Value::Boolean);
 impl_consume_ty!(Int8Type, Value::TinyInt);
 impl_consume_ty!(Int16Type, Value::SmallInt);
@@ -126,7 +153,8 @@ impl_consume_unsupported!(
         IntervalYearMonthType,
         IntervalDayTimeType,
         IntervalMonthDayNanoType,
-        Decimal128Type,
-        Decimal256Type,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
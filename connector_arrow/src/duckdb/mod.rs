@@ -10,10 +10,12 @@ use arrow::datatypes::{DataType, TimeUnit};
 use arrow::record_batch::RecordBatch;
 use itertools::Itertools;
 
+use std::path::Path;
 use std::sync::Arc;
 
-use crate::api::{Connector, ResultReader, Statement};
+use crate::api::{Connector, QueryCost, ResultReader, Statement};
 use crate::errors::ConnectorError;
+use crate::util::escape::escaped_ident;
 use crate::util::{transport, ArrayCellRef};
 
 pub struct DuckDBConnection {
@@ -32,6 +34,35 @@ impl DuckDBConnection {
     pub fn inner_mut(&mut self) -> &mut duckdb::Connection {
         &mut self.inner
     }
+
+    /// Read Parquet files that match a path or a glob pattern (i.e. `data/*.parquet`),
+    /// using DuckDB's `read_parquet`.
+    ///
+    /// Requires DuckDB `parquet` extension, which is built in with feature `parquet`
+    /// of the `duckdb` crate, or is loaded at runtime.
+    pub fn query_parquet(&mut self, glob: &str) -> Result<Vec<RecordBatch>, ConnectorError> {
+        self.query_files("read_parquet", glob)
+    }
+
+    /// Read CSV files that match a path or a glob pattern (i.e. `data/*.csv`),
+    /// using DuckDB's `read_csv_auto`, which detects the delimiter, header and column types.
+    pub fn query_csv(&mut self, glob: &str) -> Result<Vec<RecordBatch>, ConnectorError> {
+        self.query_files("read_csv_auto", glob)
+    }
+
+    /// Read JSON files that match a path or a glob pattern (i.e. `data/*.json`),
+    /// using DuckDB's `read_json_auto`, which detects the format and column types.
+    ///
+    /// Requires DuckDB `json` extension, which is built in with feature `json`
+    /// of the `duckdb` crate, or is loaded at runtime.
+    pub fn query_json(&mut self, glob: &str) -> Result<Vec<RecordBatch>, ConnectorError> {
+        self.query_files("read_json_auto", glob)
+    }
+
+    fn query_files(&mut self, func: &str, glob: &str) -> Result<Vec<RecordBatch>, ConnectorError> {
+        let glob = glob.replace('\'', "''");
+        crate::query(self, &format!("SELECT * FROM {func}('{glob}')"))
+    }
 }
 
 impl Connector for DuckDBConnection {
@@ -50,12 +81,38 @@ impl Connector for DuckDBConnection {
 
         Ok(DuckDBStatement { stmt })
     }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        Ok(self.inner.execute(query, [])? as u64)
+    }
+
     fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
         Ok(DuckDBAppender {
             inner: self.inner.appender(table_name)?,
         })
     }
 
+    /// Loads the file with `INSERT INTO ... SELECT * FROM read_parquet(...)`.
+    ///
+    /// Requires DuckDB `parquet` extension (see [DuckDBConnection::query_parquet]).
+    fn load_parquet(&mut self, table_name: &str, path: &Path) -> Result<u64, ConnectorError> {
+        let path = path.to_string_lossy().replace('\'', "''");
+        let table = escaped_ident(table_name);
+        self.execute(&format!(
+            "INSERT INTO {table} SELECT * FROM read_parquet('{path}')"
+        ))
+    }
+
+    /// DuckDB does not report costs of its plans, so the cost is estimated as the sum of
+    /// estimated cardinalities of all operators, which is what its join order optimizer uses.
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        schema::explain_cost(self, query)
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        schema::list_catalogs(self)
+    }
+
     fn type_db_into_arrow(database_ty: &str) -> Option<DataType> {
         Some(match database_ty {
             "BOOLEAN" => DataType::Boolean,
@@ -76,6 +133,11 @@ impl Connector for DuckDBConnection {
 
             "BLOB" => DataType::Binary,
             "VARCHAR" => DataType::Utf8,
+
+            _ if database_ty.starts_with("DECIMAL") => {
+                let (p, s) = parse_decimal_args(database_ty)?;
+                DataType::Decimal128(p, s)
+            }
             _ => return None,
         })
     }
@@ -113,8 +175,9 @@ impl Connector for DuckDBConnection {
             DataType::Binary | DataType::FixedSizeBinary(_) | DataType::LargeBinary => "BLOB",
             DataType::Utf8 | DataType::LargeUtf8 => "VARCHAR",
 
-            DataType::Decimal128(_, _) => todo!(),
-            DataType::Decimal256(_, _) => todo!(),
+            DataType::Decimal128(p, s) | DataType::Decimal256(p, s) => {
+                return decimal_ty(*p, *s);
+            }
 
             _ => return None,
         };
@@ -122,6 +185,31 @@ impl Connector for DuckDBConnection {
     }
 }
 
+/// Maximum precision of DuckDB `DECIMAL`.
+const DECIMAL_MAX_PRECISION: u8 = 38;
+
+/// Type name of a `DECIMAL` that can hold values of Arrow decimal of given
+/// precision and scale. DuckDB does not support precision above 38 or negative scale.
+fn decimal_ty(precision: u8, scale: i8) -> Option<String> {
+    if precision > DECIMAL_MAX_PRECISION || scale < 0 || scale as u8 > precision {
+        return None;
+    }
+    Some(format!("DECIMAL({precision},{scale})"))
+}
+
+/// Parses precision and scale from `DECIMAL(p,s)`. Plain `DECIMAL` is `DECIMAL(18,3)`.
+fn parse_decimal_args(ty: &str) -> Option<(u8, i8)> {
+    let args = ty.strip_prefix("DECIMAL")?.trim();
+    if args.is_empty() {
+        return Some((18, 3));
+    }
+    let args = args.strip_prefix('(')?.strip_suffix(')')?;
+    let (p, s) = args.split_once(',').unwrap_or((args, "0"));
+    let (p, s) = (p.trim().parse::<u8>().ok()?, s.trim().parse::<i8>().ok()?);
+
+    decimal_ty(p, s).map(|_| (p, s))
+}
+
 #[doc(hidden)]
 pub struct DuckDBStatement<'conn> {
     stmt: duckdb::Statement<'conn>,
@@ -151,6 +239,10 @@ impl<'conn> Statement<'conn> for DuckDBStatement<'conn> {
     }
 }
 
+/// Reads query results using DuckDB's native Arrow output.
+///
+/// Result schema is not derived from [Connector::type_db_into_arrow], so nested types
+/// (`LIST`, `STRUCT`, `MAP`) are passed through unchanged.
 #[doc(hidden)]
 pub struct DuckDBReader<'stmt> {
     arrow: duckdb::Arrow<'stmt>,
@@ -1,7 +1,6 @@
 use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
-use itertools::Itertools;
 
-use crate::api::{SchemaEdit, SchemaGet};
+use crate::api::{QueryCost, SchemaEdit, SchemaGet};
 use crate::util::escape::escaped_ident;
 use crate::{ConnectorError, TableCreateError, TableDropError};
 
@@ -9,7 +8,12 @@ use super::DuckDBConnection;
 
 impl SchemaGet for DuckDBConnection {
     fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
-        let query_tables = "SHOW TABLES;";
+        let query_tables = "
+            SELECT table_name FROM duckdb_tables()
+            WHERE database_name = current_database()
+                AND schema_name = current_schema()
+                AND NOT internal
+        ";
         let mut statement = self.inner.prepare(query_tables)?;
         let mut tables_res = statement.query([])?;
 
@@ -21,6 +25,12 @@ impl SchemaGet for DuckDBConnection {
         Ok(table_names)
     }
 
+    /// Identifiers are case-insensitive in DuckDB, even when quoted.
+    fn table_exists(&mut self, name: &str) -> Result<bool, ConnectorError> {
+        let tables = self.table_list()?;
+        Ok(tables.iter().any(|t| t.eq_ignore_ascii_case(name)))
+    }
+
     fn table_get(&mut self, name: &str) -> Result<arrow::datatypes::SchemaRef, ConnectorError> {
         let query_schema = format!("SELECT * FROM {} WHERE FALSE;", escaped_ident(name));
         let mut statement = self.inner.prepare(&query_schema)?;
@@ -28,61 +38,121 @@ impl SchemaGet for DuckDBConnection {
 
         Ok(results.get_schema())
     }
+
+    fn table_primary_key(&mut self, name: &str) -> Result<Vec<String>, ConnectorError> {
+        let query = "
+            SELECT unnest(constraint_column_names) FROM duckdb_constraints()
+            WHERE database_name = current_database()
+                AND schema_name = current_schema()
+                AND table_name = ?
+                AND constraint_type = 'PRIMARY KEY'
+        ";
+        let mut statement = self.inner.prepare(query)?;
+        let mut columns_res = statement.query([name])?;
+
+        let mut key = Vec::new();
+        while let Some(row) = columns_res.next()? {
+            key.push(row.get(0)?);
+        }
+        Ok(key)
+    }
 }
 
 impl SchemaEdit for DuckDBConnection {
     fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
-        let column_defs = schema
-            .fields()
-            .iter()
-            .map(|field| {
-                let ty = ty_from_arrow(field.data_type());
-
-                let is_nullable =
-                    field.is_nullable() || matches!(field.data_type(), DataType::Null);
-                let not_null = if is_nullable { "" } else { " NOT NULL" };
-
-                let name = escaped_ident(field.name());
-                format!("{name} {ty}{not_null}")
-            })
-            .join(",");
-
-        let ddl = format!("CREATE TABLE {} ({column_defs});", escaped_ident(name));
-
-        let res = self.inner.execute(&ddl, []);
-        match res {
-            Ok(_) => Ok(()),
-            Err(e)
-                if e.to_string().starts_with("Catalog Error: Table with name")
-                    && e.to_string().contains("already exists!") =>
-            {
-                Err(TableCreateError::TableExists)
-            }
-            Err(e) => Err(TableCreateError::Connector(ConnectorError::DuckDB(e))),
-        }
+        table_create(self, name, schema, false)
+    }
+
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        table_create(self, name, schema, true)
     }
 
     fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
-        // TODO: properly escape
-        let ddl = format!("DROP TABLE {};", escaped_ident(name));
-
-        let res = self.inner.execute(&ddl, []);
-
-        match res {
-            Ok(_) => Ok(()),
-            Err(e)
-                if e.to_string().starts_with("Catalog Error: Table with name ")
-                    && e.to_string().contains("does not exist!") =>
-            {
-                Err(TableDropError::TableNonexistent)
-            }
-            Err(e) => Err(TableDropError::Connector(e.into())),
+        table_drop(self, name)
+    }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "ALTER TABLE {} RENAME TO {};",
+            escaped_ident(from),
+            escaped_ident(to)
+        );
+        self.inner.execute(&ddl, [])?;
+        Ok(())
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {};",
+            escaped_ident(table),
+            escaped_ident(from),
+            escaped_ident(to)
+        );
+        self.inner.execute(&ddl, [])?;
+        Ok(())
+    }
+}
+
+fn table_create(
+    conn: &mut DuckDBConnection,
+    name: &str,
+    schema: SchemaRef,
+    temporary: bool,
+) -> Result<(), TableCreateError> {
+    let column_defs = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let ty = ty_from_arrow(field.data_type())?;
+
+            let is_nullable = field.is_nullable() || matches!(field.data_type(), DataType::Null);
+            let not_null = if is_nullable { "" } else { " NOT NULL" };
+
+            let name = escaped_ident(field.name());
+            Ok(format!("{name} {ty}{not_null}"))
+        })
+        .collect::<Result<Vec<_>, ConnectorError>>()?
+        .join(",");
+
+    let temp = if temporary { "TEMPORARY " } else { "" };
+    let ddl = format!(
+        "CREATE {temp}TABLE {} ({column_defs});",
+        escaped_ident(name)
+    );
+
+    let res = conn.inner.execute(&ddl, []);
+    match res {
+        Ok(_) => Ok(()),
+        Err(e)
+            if e.to_string().starts_with("Catalog Error: Table with name")
+                && e.to_string().contains("already exists!") =>
+        {
+            Err(TableCreateError::TableExists)
+        }
+        Err(e) => Err(TableCreateError::Connector(ConnectorError::DuckDB(e))),
+    }
+}
+
+fn table_drop(conn: &mut DuckDBConnection, name: &str) -> Result<(), TableDropError> {
+    // TODO: properly escape
+    let ddl = format!("DROP TABLE {};", escaped_ident(name));
+
+    let res = conn.inner.execute(&ddl, []);
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(e)
+            if e.to_string().starts_with("Catalog Error: Table with name ")
+                && e.to_string().contains("does not exist!") =>
+        {
+            Err(TableDropError::TableNonexistent)
         }
+        Err(e) => Err(TableDropError::Connector(e.into())),
     }
 }
 
-fn ty_from_arrow(data_type: &DataType) -> &'static str {
-    match data_type {
+fn ty_from_arrow(data_type: &DataType) -> Result<String, ConnectorError> {
+    let ty = match data_type {
         // there is no Null type in DuckDB, so we fallback to some other type that is nullable
         DataType::Null => "BIGINT",
 
@@ -119,13 +189,73 @@ fn ty_from_arrow(data_type: &DataType) -> &'static str {
         DataType::Struct(_) => unimplemented!(),
         DataType::Union(_, _) => unimplemented!(),
         DataType::Dictionary(_, _) => unimplemented!(),
-        DataType::Decimal128(_, _) => unimplemented!(),
-        DataType::Decimal256(_, _) => unimplemented!(),
+        DataType::Decimal128(p, s) | DataType::Decimal256(p, s) => {
+            return super::decimal_ty(*p, *s).ok_or(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::duckdb",
+                feature: "DECIMAL with precision above 38 or negative scale",
+            });
+        }
         DataType::Map(_, _) => unimplemented!(),
         DataType::RunEndEncoded(_, _) => unimplemented!(),
         DataType::BinaryView => todo!(),
         DataType::Utf8View => todo!(),
         DataType::ListView(_) => todo!(),
         DataType::LargeListView(_) => todo!(),
+    };
+    Ok(ty.to_string())
+}
+
+pub(super) fn list_catalogs(conn: &mut DuckDBConnection) -> Result<Vec<String>, ConnectorError> {
+    // contains columns: seq, name, file
+    let mut statement = conn.inner.prepare("PRAGMA database_list;")?;
+    let mut rows = statement.query([])?;
+
+    let mut names = Vec::new();
+    while let Some(row) = rows.next()? {
+        names.push(row.get(1)?);
     }
+    Ok(names)
+}
+
+pub(super) fn explain_cost(
+    conn: &mut DuckDBConnection,
+    query: &str,
+) -> Result<QueryCost, ConnectorError> {
+    // contains columns: explain_key, explain_value
+    let mut statement = conn.inner.prepare(&format!("EXPLAIN {query}"))?;
+    let mut rows = statement.query([])?;
+
+    let mut plan = None;
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        if key == "physical_plan" {
+            plan = Some(row.get::<_, String>(1)?);
+        }
+    }
+    let plan = plan.ok_or(ConnectorError::NoResultSets)?;
+
+    let cells = plan.lines().flat_map(|line| line.split('│'));
+    let cardinalities: Vec<u64> = cells.filter_map(parse_cardinality).collect();
+    let Some(estimated_rows) = cardinalities.first() else {
+        let msg = format!("cannot parse estimates from query plan: {plan}");
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, msg).into());
+    };
+    Ok(QueryCost {
+        estimated_rows: *estimated_rows,
+        estimated_cost: cardinalities.iter().sum::<u64>() as f64,
+    })
+}
+
+/// Operators are rendered as boxes, which contain the estimated cardinality as `~N Rows`
+/// (or `EC: N` before DuckDB 1.1). The root operator is rendered first, boxes of
+/// its descendants below it, side by side.
+fn parse_cardinality(cell: &str) -> Option<u64> {
+    let cell = cell.trim();
+    let number = match cell.strip_prefix("EC:") {
+        Some(number) => number,
+        None => cell
+            .strip_prefix('~')?
+            .trim_end_matches(char::is_alphabetic),
+    };
+    number.trim().replace(',', "").parse().ok()
 }
@@ -27,21 +27,61 @@ pub enum ConnectorError {
     #[error("When converting values from database representation into into an Arrow types, it fell out of supported range.")]
     DataOutOfRange,
 
+    #[error("Value in column `{column}` at row {row} fell out of supported range of Arrow types.")]
+    DataOutOfRangeAt { column: String, row: usize },
+
     #[error("{connector_name} does not support {feature}")]
     NotSupported {
         connector_name: &'static str,
         feature: &'static str,
     },
 
+    #[error("Statement `{0}` is not registered")]
+    StatementNotRegistered(String),
+
+    #[error("Parameter `{0}` is not bound")]
+    UnboundParameter(String),
+
+    #[error("Batch size must be greater than zero")]
+    InvalidBatchSize,
+
+    #[error("Column `{column}` does not exist in table `{table_name}`")]
+    ColumnNotFound { table_name: String, column: String },
+
+    #[error("Schema of table `{table_name}` does not match: {}", columns.join(", "))]
+    TableSchemaMismatch {
+        table_name: String,
+        columns: Vec<String>,
+    },
+
+    #[error("URL scheme `{0}` is not supported")]
+    UnsupportedUrlScheme(String),
+
+    #[error("Query was cancelled")]
+    Cancelled,
+
+    #[error("Query did not complete within {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Append was rolled back, because a previous batch failed")]
+    AppendRolledBack,
+
     #[error(transparent)]
     UrlEncoding(#[from] FromUtf8Error),
 
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     #[error(transparent)]
     Arrow(#[from] arrow::error::ArrowError),
 
+    #[cfg(feature = "pool")]
+    #[error(transparent)]
+    Pool(#[from] r2d2::Error),
+
     #[cfg(feature = "src_sqlite")]
     #[error(transparent)]
-    SQLite(#[from] rusqlite::Error),
+    SQLite(rusqlite::Error),
 
     #[cfg(feature = "src_duckdb")]
     #[error(transparent)]
@@ -49,15 +89,67 @@ pub enum ConnectorError {
 
     #[cfg(feature = "src_postgres")]
     #[error(transparent)]
-    Postgres(#[from] super::postgres::PostgresError),
+    Postgres(super::postgres::PostgresError),
 
     #[cfg(feature = "src_mysql")]
     #[error(transparent)]
-    MySQL(#[from] mysql::Error),
+    MySQL(mysql::Error),
 
     #[cfg(feature = "src_tiberius")]
     #[error(transparent)]
     Tiberius(#[from] tiberius::error::Error),
+
+    #[cfg(feature = "src_parquet")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "src_iceberg")]
+    #[error(transparent)]
+    Iceberg(#[from] iceberg::Error),
+
+    #[cfg(feature = "src_trino")]
+    #[error(transparent)]
+    Trino(#[from] super::trino::TrinoError),
+}
+
+#[cfg(feature = "src_sqlite")]
+impl From<rusqlite::Error> for ConnectorError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::OperationInterrupted) => ConnectorError::Cancelled,
+            _ => ConnectorError::SQLite(err),
+        }
+    }
+}
+
+#[cfg(feature = "src_postgres")]
+impl From<super::postgres::PostgresError> for ConnectorError {
+    fn from(err: super::postgres::PostgresError) -> Self {
+        use super::postgres::PostgresError;
+        use postgres::error::SqlState;
+
+        match err {
+            PostgresError::Postgres(e) if e.code() == Some(&SqlState::QUERY_CANCELED) => {
+                ConnectorError::Cancelled
+            }
+            _ => ConnectorError::Postgres(err),
+        }
+    }
+}
+
+#[cfg(feature = "src_mysql")]
+impl From<mysql::Error> for ConnectorError {
+    fn from(err: mysql::Error) -> Self {
+        // returned for queries killed with `KILL QUERY`
+        const ER_QUERY_INTERRUPTED: u16 = 1317;
+
+        match err {
+            mysql::Error::MySqlError(e) if e.code == ER_QUERY_INTERRUPTED => {
+                ConnectorError::Cancelled
+            }
+            _ => ConnectorError::MySQL(err),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
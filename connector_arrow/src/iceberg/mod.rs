@@ -0,0 +1,103 @@
+//! Provides `connector_arrow` traits for [Apache Iceberg](https://iceberg.apache.org) tables,
+//! read with [iceberg crate](https://docs.rs/iceberg).
+//!
+//! [IcebergConnection] is read-only. Its tables are the tables of a namespace of an Iceberg
+//! catalog, whose data files are read from the object store of the catalog.
+//!
+//! ```ignore
+//! let catalog = RestCatalog::new(RestCatalogConfig::builder().uri(uri).build());
+//! let namespace = NamespaceIdent::new("db".into());
+//! let mut conn = IcebergConnection::new(rt, catalog, namespace);
+//!
+//! let batches = connector_arrow::query(&mut conn, "events")?;
+//! ```
+
+mod query;
+mod schema;
+
+#[doc(hidden)]
+pub use query::{IcebergReader, IcebergStatement};
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use iceberg::table::Table;
+use iceberg::{Catalog, NamespaceIdent, TableIdent};
+use tokio::runtime::Runtime;
+
+use crate::api::{unimplemented, Connector, SchemaGet};
+use crate::ConnectorError;
+
+pub struct IcebergConnection<C: Catalog> {
+    rt: Arc<Runtime>,
+    catalog: C,
+    namespace: NamespaceIdent,
+}
+
+impl<C: Catalog> IcebergConnection<C> {
+    /// Creates a connection to tables of a namespace of the catalog.
+    /// Requests to the catalog and to the object store are executed on the runtime.
+    pub fn new(rt: Arc<Runtime>, catalog: C, namespace: NamespaceIdent) -> Self {
+        IcebergConnection {
+            rt,
+            catalog,
+            namespace,
+        }
+    }
+
+    pub fn catalog(&self) -> &C {
+        &self.catalog
+    }
+
+    fn load_table(&self, name: &str) -> Result<Table, ConnectorError> {
+        let ident = TableIdent::new(self.namespace.clone(), name.to_string());
+        Ok(self.rt.block_on(self.catalog.load_table(&ident))?)
+    }
+}
+
+impl<C: Catalog> Connector for IcebergConnection<C> {
+    type Stmt<'conn>
+        = IcebergStatement<'conn, C>
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = unimplemented::Appender
+    where
+        Self: 'conn;
+
+    /// Accepts name of a table in the namespace of this connection.
+    /// The statement scans all rows of the current snapshot of the table.
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        Ok(IcebergStatement {
+            conn: self,
+            table_name: query.trim().to_string(),
+        })
+    }
+
+    fn execute(&mut self, _query: &str) -> Result<u64, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::iceberg",
+            feature: "execute",
+        })
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        self.table_get(query.trim())
+    }
+
+    fn append<'a>(&'a mut self, _table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::iceberg",
+            feature: "append",
+        })
+    }
+
+    fn type_db_into_arrow(_database_ty: &str) -> Option<DataType> {
+        None
+    }
+
+    fn type_arrow_into_db(_ty: &DataType) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use iceberg::scan::ArrowRecordBatchStream;
+use iceberg::Catalog;
+use tokio::runtime::Runtime;
+
+use crate::api::{ResultReader, Statement};
+use crate::ConnectorError;
+
+use super::IcebergConnection;
+
+pub struct IcebergStatement<'conn, C: Catalog> {
+    pub(super) conn: &'conn IcebergConnection<C>,
+    pub(super) table_name: String,
+}
+
+impl<'conn, C: Catalog> Statement<'conn> for IcebergStatement<'conn, C> {
+    type Reader<'stmt>
+        = IcebergReader
+    where
+        Self: 'stmt;
+
+    fn start_batch(
+        &mut self,
+        args: (&RecordBatch, usize),
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        if args.0.num_columns() > 0 {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::iceberg",
+                feature: "query parameters",
+            });
+        }
+
+        let table = self.conn.load_table(&self.table_name)?;
+        let schema = super::schema::arrow_schema(&table)?;
+
+        // a table without snapshots has no data files to scan
+        if table.metadata().current_snapshot().is_none() {
+            return Ok(IcebergReader {
+                rt: self.conn.rt.clone(),
+                schema,
+                stream: None,
+            });
+        }
+
+        let scan = table.scan().select_all().build()?;
+        let stream = self.conn.rt.block_on(scan.to_arrow())?;
+        Ok(IcebergReader {
+            rt: self.conn.rt.clone(),
+            schema,
+            stream: Some(stream),
+        })
+    }
+}
+
+pub struct IcebergReader {
+    rt: Arc<Runtime>,
+    schema: SchemaRef,
+    stream: Option<ArrowRecordBatchStream>,
+}
+
+impl ResultReader<'_> for IcebergReader {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        Ok(self.schema.clone())
+    }
+}
+
+impl Iterator for IcebergReader {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream = self.stream.as_mut()?;
+        let batch = match self.rt.block_on(stream.next())? {
+            Ok(batch) => batch,
+            Err(e) => return Some(Err(e.into())),
+        };
+        Some(
+            batch
+                .with_schema(self.schema.clone())
+                .map_err(ConnectorError::from),
+        )
+    }
+}
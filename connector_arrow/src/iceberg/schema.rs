@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use iceberg::table::Table;
+use iceberg::Catalog;
+
+use crate::api::SchemaGet;
+use crate::ConnectorError;
+
+use super::IcebergConnection;
+
+impl<C: Catalog> SchemaGet for IcebergConnection<C> {
+    /// Lists tables in the namespace of this connection.
+    fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
+        let tables = self
+            .rt
+            .block_on(self.catalog.list_tables(&self.namespace))?;
+
+        let mut names: Vec<_> = tables.into_iter().map(|t| t.name).collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Returns the current schema of the table, converted to Arrow.
+    fn table_get(&mut self, name: &str) -> Result<SchemaRef, ConnectorError> {
+        let table = self.load_table(name)?;
+        arrow_schema(&table)
+    }
+}
+
+pub(super) fn arrow_schema(table: &Table) -> Result<SchemaRef, ConnectorError> {
+    let schema = table.metadata().current_schema();
+    Ok(Arc::new(iceberg::arrow::schema_to_arrow_schema(schema)?))
+}
@@ -54,26 +54,53 @@
 //! This situation is made much worse by unusually high cadence of major version
 //! releases of arrow-rs, even without breaking changes.
 
+#[cfg(any(
+    feature = "src_postgres",
+    feature = "src_sqlite",
+    feature = "src_duckdb",
+    feature = "src_mysql"
+))]
+pub mod any;
 pub mod api;
+mod dml;
 mod errors;
 mod params;
+#[cfg(feature = "pool")]
+pub mod pool;
+pub mod prepared_only;
+pub mod query_cache;
+mod query_iter;
+#[cfg(feature = "sink_csv")]
+pub mod sink;
+pub mod transaction;
 pub mod types;
 pub mod util;
 
 #[cfg(feature = "src_duckdb")]
 pub mod duckdb;
+#[cfg(feature = "src_iceberg")]
+pub mod iceberg;
 #[cfg(feature = "src_mysql")]
 pub mod mysql;
+#[cfg(feature = "src_parquet")]
+pub mod parquet;
 #[cfg(feature = "src_postgres")]
 pub mod postgres;
 #[cfg(feature = "src_sqlite")]
 pub mod sqlite;
 #[cfg(feature = "src_tiberius")]
 pub mod tiberius;
+#[cfg(feature = "src_trino")]
+pub mod trino;
 
 pub use arrow;
 pub use errors::*;
+pub use query_iter::{query_iter, QueryIter};
 
+use std::collections::HashMap;
+use std::time::Duration;
+
+use arrow::datatypes::DataType;
 use arrow::record_batch::RecordBatch;
 
 use self::api::{Connector, Statement};
@@ -92,3 +119,38 @@ pub fn query<C: Connector>(conn: &mut C, query: &str) -> Result<Vec<RecordBatch>
     let batches = reader.collect::<Result<_, _>>()?;
     Ok(batches)
 }
+
+/// Execute a single query and return the results, with columns cast to the given types.
+///
+/// Columns are matched by name, see [util::coerce::cast_columns].
+pub fn query_with_coercions<C: Connector>(
+    conn: &mut C,
+    query: &str,
+    coercions: HashMap<String, DataType>,
+) -> Result<Vec<RecordBatch>, ConnectorError> {
+    let batches = self::query(conn, query)?;
+    let batches = batches
+        .iter()
+        .map(|b| util::coerce::cast_columns(b, &coercions))
+        .collect::<Result<_, _>>()?;
+    Ok(batches)
+}
+
+/// Execute a single query and return the results, cancelling the query if it does not
+/// complete within the timeout.
+///
+/// Cancellation uses [Connector::cancel_token], see [Statement::start_with_timeout].
+/// Returns [ConnectorError::Timeout] when the query was cancelled because of the timeout.
+pub fn query_with_timeout<C: Connector>(
+    conn: &mut C,
+    query: &str,
+    timeout: Duration,
+) -> Result<Vec<RecordBatch>, ConnectorError> {
+    let token = conn.cancel_token()?;
+
+    let mut stmt = conn.query(query)?;
+    let reader = stmt.start_with_timeout([], token, timeout)?;
+
+    let batches = reader.collect::<Result<_, _>>()?;
+    Ok(batches)
+}
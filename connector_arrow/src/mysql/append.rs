@@ -1,64 +1,194 @@
+use std::collections::HashMap;
+
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 use itertools::{zip_eq, Itertools};
 use mysql::prelude::Queryable;
 use mysql::Value;
 
-use crate::api::Append;
-use crate::types::{FixedSizeBinaryType, NullType};
+use crate::api::{Append, ConflictMode, StatementCacheStats};
+use crate::types::{FixedSizeBinaryType, ListType, NullType};
 use crate::util::escape::escaped_ident_bt;
 use crate::util::transport::{self, Consume, ConsumeTy};
-use crate::util::ArrayCellRef;
+use crate::util::{coerce, ArrayCellRef};
 use crate::{impl_consume_unsupported, ConnectorError};
 
+/// Maximum number of placeholders in a prepared statement.
+const MAX_PARAMS: usize = u16::MAX as usize;
+
+/// Name of the savepoint used by appenders within an open transaction.
+const SAVEPOINT: &str = "connector_arrow_append";
+
 pub struct MySQLAppender<'conn, C: Queryable> {
     table: String,
     client: &'conn mut C,
+    rows_per_insert: usize,
+    rolled_back: bool,
+
+    /// Rows are written within a savepoint of an open transaction,
+    /// instead of in a transaction of their own.
+    savepoint: bool,
+
+    /// `ON DUPLICATE KEY UPDATE` clause appended to each `INSERT`, or empty.
+    on_duplicate: String,
+
+    /// Prepared `INSERT` statements, keyed by number of columns and rows.
+    statements: HashMap<(usize, usize), mysql::Statement>,
+    hits: u64,
 }
 
 impl<'conn, C: Queryable> MySQLAppender<'conn, C> {
-    pub fn new(client: &'conn mut C, table_name: &str) -> Result<Self, ConnectorError> {
-        client.query_drop("START TRANSACTION;")?;
+    pub fn new(
+        client: &'conn mut C,
+        table_name: &str,
+        savepoint: bool,
+    ) -> Result<Self, ConnectorError> {
+        if savepoint {
+            client.query_drop(format!("SAVEPOINT {SAVEPOINT};"))?;
+        } else {
+            client.query_drop("START TRANSACTION;")?;
+        }
         Ok(Self {
             table: table_name.to_owned(),
             client,
+            rows_per_insert: 1000,
+            rolled_back: false,
+            savepoint,
+            on_duplicate: String::new(),
+            statements: HashMap::new(),
+            hits: 0,
         })
     }
-}
 
-impl<'conn, C: Queryable> Append<'conn> for MySQLAppender<'conn, C> {
-    fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError> {
-        // TODO: 30 is a guess, we need benchmarking to find the optimum value
-        const BATCH_SIZE: usize = 30;
+    /// Creates an appender that resolves rows conflicting on a unique key of the table,
+    /// with `INSERT ... ON DUPLICATE KEY UPDATE`.
+    pub fn new_upsert(
+        client: &'conn mut C,
+        table_name: &str,
+        columns: &[&str],
+        conflict: &ConflictMode,
+        savepoint: bool,
+    ) -> Result<Self, ConnectorError> {
+        // MySQL has no DO NOTHING, so ignored rows assign a column to itself
+        let no_op = columns
+            .first()
+            .map(|c| {
+                format!(
+                    " ON DUPLICATE KEY UPDATE {c} = {c}",
+                    c = escaped_ident_bt(c)
+                )
+            })
+            .unwrap_or_default();
 
-        let last_batch_size = batch.num_rows() % BATCH_SIZE;
+        let on_duplicate = match conflict {
+            ConflictMode::Error => String::new(),
+            ConflictMode::Ignore => no_op,
+            ConflictMode::Replace(conflict_columns) => {
+                let updates = columns
+                    .iter()
+                    .filter(|c| !conflict_columns.iter().any(|k| k == *c))
+                    .map(|c| format!("{c} = VALUES({c})", c = escaped_ident_bt(c)))
+                    .join(", ");
+                if updates.is_empty() {
+                    no_op
+                } else {
+                    format!(" ON DUPLICATE KEY UPDATE {updates}")
+                }
+            }
+        };
 
-        let batch_query = insert_query(&self.table, batch.num_columns(), BATCH_SIZE);
-        for batch_number in 0..(batch.num_rows() / BATCH_SIZE) {
-            let rows_range = (batch_number * BATCH_SIZE)..((batch_number + 1) * BATCH_SIZE);
+        let mut appender = Self::new(client, table_name, savepoint)?;
+        appender.on_duplicate = on_duplicate;
+        Ok(appender)
+    }
 
-            let params: Vec<Value> = collect_args(&batch, rows_range);
-            self.client.exec_iter(&batch_query, params)?;
+    /// Set the number of rows inserted by each `INSERT` statement. Defaults to 1000.
+    ///
+    /// The number is lowered when needed, so a statement has at most 65535 parameters.
+    pub fn with_rows_per_insert(mut self, rows_per_insert: usize) -> Result<Self, ConnectorError> {
+        if rows_per_insert == 0 {
+            return Err(ConnectorError::InvalidBatchSize);
         }
+        self.rows_per_insert = rows_per_insert;
+        Ok(self)
+    }
 
-        if last_batch_size > 0 {
-            let rows_range = (batch.num_rows() - last_batch_size)..batch.num_rows();
+    fn insert_chunks(&mut self, batch: &RecordBatch) -> Result<(), ConnectorError> {
+        let max_rows = (MAX_PARAMS / batch.num_columns().max(1)).max(1);
+        let chunk_size = self.rows_per_insert.min(max_rows);
 
-            let last_query = insert_query(&self.table, batch.num_columns(), last_batch_size);
-            let params: Vec<Value> = collect_args(&batch, rows_range);
-            self.client.exec_iter(&last_query, params)?;
-        }
+        for chunk_start in (0..batch.num_rows()).step_by(chunk_size) {
+            let rows_range = chunk_start..(chunk_start + chunk_size).min(batch.num_rows());
 
+            let params: Vec<Value> = collect_args(batch, rows_range.clone())?;
+            let stmt = self.prepare(batch.num_columns(), rows_range.len())?;
+            self.client.exec_drop(stmt, params)?;
+        }
         Ok(())
     }
 
+    /// Prepares an `INSERT` of given shape, or reuses the statement prepared
+    /// by a previous chunk or batch of the same shape.
+    fn prepare(&mut self, cols: usize, rows: usize) -> Result<mysql::Statement, ConnectorError> {
+        if let Some(stmt) = self.statements.get(&(cols, rows)) {
+            self.hits += 1;
+            return Ok(stmt.clone());
+        }
+        let stmt = self
+            .client
+            .prep(insert_query(&self.table, cols, rows, &self.on_duplicate))?;
+        self.statements.insert((cols, rows), stmt.clone());
+        Ok(stmt)
+    }
+
+    /// Counters of `INSERT` statements prepared by this appender.
+    /// Each distinct number of rows per statement is prepared once.
+    pub fn statement_cache_stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            hits: self.hits,
+            misses: self.statements.len() as u64,
+            size: self.statements.len(),
+        }
+    }
+}
+
+impl<'conn, C: Queryable> Append<'conn> for MySQLAppender<'conn, C> {
+    /// Inserts the batch in chunks of multi-row `INSERT` statements.
+    /// If any of the chunks fails, the whole append is rolled back.
+    fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError> {
+        if self.rolled_back {
+            return Err(ConnectorError::AppendRolledBack);
+        }
+
+        let batch = coerce::decode_dictionaries(batch)?;
+        let res = self.insert_chunks(&batch);
+        if res.is_err() {
+            if self.savepoint {
+                self.client
+                    .query_drop(format!("ROLLBACK TO SAVEPOINT {SAVEPOINT};"))?;
+            } else {
+                self.client.query_drop("ROLLBACK;")?;
+            }
+            self.rolled_back = true;
+        }
+        res
+    }
+
     fn finish(self) -> Result<(), ConnectorError> {
-        self.client.query_drop("COMMIT;")?;
+        if self.rolled_back {
+            return Err(ConnectorError::AppendRolledBack);
+        }
+        if self.savepoint {
+            self.client
+                .query_drop(format!("RELEASE SAVEPOINT {SAVEPOINT};"))?;
+        } else {
+            self.client.query_drop("COMMIT;")?;
+        }
         Ok(())
     }
 }
 
-fn insert_query(table_name: &str, cols: usize, rows: usize) -> String {
+fn insert_query(table_name: &str, cols: usize, rows: usize, on_duplicate: &str) -> String {
     let values = (0..rows)
         .map(|_| {
             let row = (0..cols).map(|_| "?").join(",");
@@ -67,12 +197,15 @@ fn insert_query(table_name: &str, cols: usize, rows: usize) -> String {
         .join(",");
 
     format!(
-        "INSERT INTO {} VALUES {values}",
+        "INSERT INTO {} VALUES {values}{on_duplicate}",
         escaped_ident_bt(table_name)
     )
 }
 
-fn collect_args(batch: &RecordBatch, rows_range: std::ops::Range<usize>) -> Vec<Value> {
+pub(super) fn collect_args(
+    batch: &RecordBatch,
+    rows_range: std::ops::Range<usize>,
+) -> Result<Vec<Value>, ConnectorError> {
     let mut res = Vec::with_capacity(rows_range.len() * batch.num_columns());
 
     let schema = batch.schema();
@@ -87,10 +220,10 @@ fn collect_args(batch: &RecordBatch, rows_range: std::ops::Range<usize>) -> Vec<
     for row_number in rows_range {
         for cell in &mut row {
             cell.row_number = row_number;
-            transport::transport(cell.field, cell as &_, &mut res).unwrap();
+            transport::transport(cell.field, cell as &_, &mut res)?;
         }
     }
-    res
+    Ok(res)
 }
 
 impl Consume for Vec<Value> {}
@@ -166,6 +299,9 @@ impl_consume_unsupported!(
         IntervalMonthDayNanoType,
         IntervalYearMonthType,
         LargeUtf8Type,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
 
@@ -5,17 +5,105 @@ mod types;
 
 use arrow::datatypes::*;
 use mysql::prelude::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
-use crate::api::Connector;
+use crate::api::{
+    Append, AppenderOptions, CancelToken, ConflictMode, Connector, NullPolicy, ReadOptions,
+    SchemaGet,
+};
 use crate::ConnectorError;
 
 pub struct MySQLConnection<Q: Queryable> {
     queryable: Q,
+    spatial_srid: bool,
+    session_time_zone: bool,
+    assume_utc: bool,
+
+    /// Set while a transaction started by [Connector::transaction] is open.
+    in_transaction: bool,
+
+    /// Options of a new connection that kills queries of this connection, and id of this connection.
+    cancel: Option<(mysql::Opts, u32)>,
 }
 
 impl<Q: Queryable> MySQLConnection<Q> {
     pub fn new(conn: Q) -> Self {
-        MySQLConnection { queryable: conn }
+        MySQLConnection {
+            queryable: conn,
+            spatial_srid: false,
+            session_time_zone: false,
+            assume_utc: false,
+            in_transaction: false,
+            cancel: None,
+        }
+    }
+
+    /// Read spatial columns (i.e. `GEOMETRY` or `POINT`) as
+    /// `Struct { srid: UInt32, wkb: Binary }` instead of plain WKB `Binary`.
+    pub fn with_spatial_srid(mut self, enabled: bool) -> Self {
+        self.spatial_srid = enabled;
+        self
+    }
+
+    /// Read `TIMESTAMP` columns as `Timestamp(Microsecond, offset)` instead of `Utf8`.
+    ///
+    /// MySQL returns `TIMESTAMP` values in the session time zone, so before each query
+    /// the session's current offset from UTC is determined and used as the timezone
+    /// of the Arrow field. This is exact when the session time zone is a fixed offset
+    /// (i.e. `SET time_zone = '+02:00'`). With a named time zone that observes DST,
+    /// values from the other side of a DST transition will be off by the DST shift.
+    pub fn with_session_time_zone(mut self, enabled: bool) -> Self {
+        self.session_time_zone = enabled;
+        self
+    }
+
+    /// Set the session time zone to UTC and read `DATETIME` and `TIMESTAMP` columns
+    /// as `Timestamp(Microsecond, "+00:00")` instead of `Utf8`.
+    ///
+    /// `DATETIME` values are assumed to be in UTC. The session time zone must not
+    /// be changed afterwards, since `TIMESTAMP` values would be returned in the new zone.
+    pub fn with_assume_utc(mut self, enabled: bool) -> Result<Self, ConnectorError> {
+        if enabled {
+            self.queryable.query_drop("SET time_zone = '+00:00'")?;
+        }
+        self.assume_utc = enabled;
+        Ok(self)
+    }
+
+    /// Enable [Connector::cancel_token], which kills the running query of this connection
+    /// with `KILL QUERY`, sent over a new connection that is opened with `opts`.
+    ///
+    /// Options should be the ones this connection was opened with, because
+    /// only the same user (or a user with `CONNECTION_ADMIN`) can kill its queries.
+    pub fn with_cancellation(mut self, opts: mysql::Opts) -> Result<Self, ConnectorError> {
+        let id: Option<u32> = self.queryable.query_first("SELECT CONNECTION_ID()")?;
+        self.cancel = Some((opts, id.unwrap_or_default()));
+        Ok(self)
+    }
+
+    /// Offset of the session time zone from UTC, if timestamps are read as Timestamp.
+    fn utc_offset(&mut self) -> Result<Option<i32>, ConnectorError> {
+        types::utc_offset(&mut self.queryable, self.session_time_zone, self.assume_utc)
+    }
+
+    /// Prepares a statement, reporting a syntax error of a query with `RETURNING`
+    /// as not supported, since MySQL cannot return rows of INSERT, UPDATE or DELETE.
+    fn prepare(&mut self, query: &str) -> Result<mysql::Statement, ConnectorError> {
+        static RETURNING: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bRETURNING\b").unwrap());
+        const ER_PARSE_ERROR: u16 = 1064;
+
+        match self.queryable.prep(query) {
+            Err(mysql::Error::MySqlError(e))
+                if e.code == ER_PARSE_ERROR && RETURNING.is_match(query) =>
+            {
+                Err(ConnectorError::NotSupported {
+                    connector_name: "connector_arrow::mysql",
+                    feature: "RETURNING",
+                })
+            }
+            res => Ok(res?),
+        }
     }
 
     pub fn unwrap(self) -> Q {
@@ -39,15 +127,157 @@ impl<Q: Queryable> Connector for MySQLConnection<Q> {
         Self: 'conn;
 
     fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
-        let stmt = self.queryable.prep(query)?;
+        let stmt = self.prepare(query)?;
         Ok(query::MySQLStatement {
             queryable: &mut self.queryable,
             stmt,
+            spatial_srid: self.spatial_srid,
+            session_time_zone: self.session_time_zone,
+            assume_utc: self.assume_utc,
+            batch_size: 1024,
+            on_unexpected_null: NullPolicy::Error,
         })
     }
 
+    /// Only `batch_size` is supported. The client always reads all rows of the result
+    /// from the connection, so they cannot be prefetched in chunks.
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        if options.prefetch_rows.is_some() {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::mysql",
+                feature: "prefetch_rows",
+            });
+        }
+        let mut stmt = self.query(query)?;
+        stmt.on_unexpected_null = options.on_unexpected_null;
+        match options.batch_size {
+            Some(batch_size) => stmt.with_batch_size(batch_size),
+            None => Ok(stmt),
+        }
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let result = self.queryable.query_iter(query)?;
+        Ok(result.affected_rows())
+    }
+
     fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
-        append::MySQLAppender::new(&mut self.queryable, table_name)
+        append::MySQLAppender::new(&mut self.queryable, table_name, self.in_transaction)
+    }
+
+    /// Supports [ConflictMode], which is resolved on any unique key of the table.
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        if options.null_as_default {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::mysql",
+                feature: "null_as_default",
+            });
+        }
+        if options.conflict == ConflictMode::Error {
+            return self.append(table_name);
+        }
+
+        let schema = self.table_get(table_name)?;
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        options.conflict.validate(table_name, &columns)?;
+
+        append::MySQLAppender::new_upsert(
+            &mut self.queryable,
+            table_name,
+            &columns,
+            &options.conflict,
+            self.in_transaction,
+        )
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.queryable.query_drop("START TRANSACTION")?;
+        self.in_transaction = true;
+        Ok(())
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.in_transaction = false;
+        self.queryable
+            .query_drop(if commit { "COMMIT" } else { "ROLLBACK" })?;
+        Ok(())
+    }
+
+    /// Requires [MySQLConnection::with_cancellation].
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        let Some((opts, id)) = self.cancel.clone() else {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::mysql",
+                feature: "query cancellation",
+            });
+        };
+        Ok(CancelToken::new(move || {
+            let mut conn = mysql::Conn::new(opts.clone())?;
+            conn.query_drop(format!("KILL QUERY {id}"))?;
+            Ok(())
+        }))
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        // prepared statement contains description of the result
+        let stmt = self.prepare(query)?;
+        let utc_offset = self.utc_offset()?;
+        types::get_result_schema(
+            stmt.columns(),
+            self.spatial_srid,
+            utc_offset,
+            self.assume_utc,
+        )
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError> {
+        schema::estimate_row_count(self, table_name)
+    }
+
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &arrow::record_batch::RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        let placeholder = |_| "?".to_string();
+        let ident = crate::util::escape::escaped_ident_bt;
+        crate::dml::delete_by_keys(self, table_name, key_columns, keys, placeholder, ident)
+    }
+
+    /// Upserts with `INSERT ... ON DUPLICATE KEY UPDATE`, so the table must have
+    /// a primary key or a unique index over `key_columns`.
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &arrow::record_batch::RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        let conflict = ConflictMode::Replace(key_columns.iter().map(|k| k.to_string()).collect());
+        let options = AppenderOptions {
+            conflict,
+            ..AppenderOptions::default()
+        };
+
+        let mut appender = self.append_with(table_name, options)?;
+        appender.append(batch.clone())?;
+        appender.finish()
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        schema::list_catalogs(self)
     }
 
     fn type_db_into_arrow(ty: &str) -> Option<DataType> {
@@ -80,9 +310,13 @@ impl<Q: Queryable> Connector for MySQLConnection<Q> {
             ("real" | "float" | "float4", _) => DataType::Float32,
             ("double" | "float8", _) => DataType::Float64,
 
-            ("bit" | "tinyblob" | "mediumblob" | "longblob" | "blob" | "binary", _) => {
-                DataType::Binary
-            }
+            // four-digit year, sent as a 2-byte integer
+            ("year", _) => DataType::Int16,
+
+            // BIT(n) is sent as ceil(n / 8) bytes, most significant byte first
+            ("bit", _) => DataType::Binary,
+
+            ("tinyblob" | "mediumblob" | "longblob" | "blob" | "binary", _) => DataType::Binary,
 
             ("tinytext" | "mediumtext" | "longtext" | "text" | "varchar" | "char", _) => {
                 DataType::Utf8
@@ -90,6 +324,16 @@ impl<Q: Queryable> Connector for MySQLConnection<Q> {
 
             ("decimal" | "numeric" | "newdecimal", _) => DataType::Utf8,
 
+            // values of SET are comma-separated members
+            ("enum" | "set", _) => DataType::Utf8,
+
+            // spatial types are returned as WKB, with the SRID prefix stripped
+            (
+                "geometry" | "point" | "linestring" | "polygon" | "multipoint" | "multilinestring"
+                | "multipolygon" | "geometrycollection" | "geomcollection",
+                _,
+            ) => DataType::Binary,
+
             // MySQL DATETIME has range 1000-01-01 00:00:00.000000 and
             // 9999-12-31 23:59:59.999999 with microsecond precision.
             // Arrow Date64 has only millisecond precision, so it cannot be used.
@@ -97,8 +341,9 @@ impl<Q: Queryable> Connector for MySQLConnection<Q> {
             // to connector_arrow. Because of that, it also cannot be converted to a timestamp,
             // even a timestamp of unknown timezone.
             // So we default to Utf8.
-            // TODO: if we send `SET timezone = 'UTC'` before executing queries, we could convert
-            // to timestamp in 'UTC' timezone.
+            // TIMESTAMP can be read as timestamp with the session offset, see
+            // [MySQLConnection::with_session_time_zone], and both DATETIME and TIMESTAMP
+            // can be read as UTC timestamps, see [MySQLConnection::with_assume_utc].
             ("datetime" | "timestamp", _) => DataType::Utf8,
 
             _ => return None,
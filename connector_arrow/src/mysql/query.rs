@@ -2,9 +2,9 @@ use arrow::{datatypes::*, record_batch::RecordBatch};
 use mysql::prelude::*;
 use pac_cell::PacCell;
 
-use crate::api::{ResultReader, Statement};
+use crate::api::{NullPolicy, ResultReader, Statement};
 use crate::impl_produce_unsupported;
-use crate::types::{ArrowType, FixedSizeBinaryType, NullType};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType, NullType};
 use crate::util::transport::ProduceTy;
 use crate::util::{self, transport::Produce};
 use crate::ConnectorError;
@@ -12,6 +12,23 @@ use crate::ConnectorError;
 pub struct MySQLStatement<'conn, Q: Queryable> {
     pub(super) stmt: mysql::Statement,
     pub(super) queryable: &'conn mut Q,
+    pub(super) spatial_srid: bool,
+    pub(super) session_time_zone: bool,
+    pub(super) assume_utc: bool,
+    pub(super) batch_size: usize,
+    pub(super) on_unexpected_null: NullPolicy,
+}
+
+impl<Q: Queryable> MySQLStatement<'_, Q> {
+    /// Set the number of rows in each of the produced record batches.
+    /// The last batch may contain fewer rows. Defaults to 1024.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Result<Self, ConnectorError> {
+        if batch_size == 0 {
+            return Err(ConnectorError::InvalidBatchSize);
+        }
+        self.batch_size = batch_size;
+        Ok(self)
+    }
 }
 
 impl<'conn, C: Queryable> Statement<'conn> for MySQLStatement<'conn, C> {
@@ -22,15 +39,37 @@ impl<'conn, C: Queryable> Statement<'conn> for MySQLStatement<'conn, C> {
 
     fn start_batch<'p>(
         &mut self,
-        _args: (&RecordBatch, usize),
+        args: (&RecordBatch, usize),
     ) -> Result<Self::Reader<'_>, ConnectorError> {
-        let query_result = self.queryable.exec_iter(&self.stmt, ())?;
+        let (session_time_zone, assume_utc) = (self.session_time_zone, self.assume_utc);
+        let utc_offset = super::types::utc_offset(self.queryable, session_time_zone, assume_utc)?;
+
+        let (batch, row) = args;
+        let params = super::append::collect_args(batch, row..(row + 1))?;
+        let query_result = self.queryable.exec_iter(&self.stmt, params)?;
+        let (spatial_srid, batch_size) = (self.spatial_srid, self.batch_size);
+        let promote_nullable = self.on_unexpected_null == NullPolicy::Promote;
 
         // PacCell is needed so we can return query_result and result_set that mutably borrows query result.
         let pac = PacCell::try_new(query_result, |qr| -> Result<_, ConnectorError> {
             let result_set = qr.iter().ok_or(ConnectorError::NoResultSets)?;
-            let schema = super::types::get_result_schema(&result_set)?;
-            Ok(MySQLResultReader { result_set, schema })
+            let mut schema = super::types::get_result_schema(
+                result_set.columns().as_ref(),
+                spatial_srid,
+                utc_offset,
+                assume_utc,
+            )?;
+            if promote_nullable {
+                schema = util::coerce::coerce_schema(schema, |_| None, Some(true));
+            }
+            let row_schema = super::types::get_row_schema(&schema);
+            Ok(MySQLResultReader {
+                result_set,
+                schema,
+                row_schema,
+                utc_offset: utc_offset.unwrap_or_default(),
+                batch_size,
+            })
         })?;
         Ok(MySQLQueryResult(pac))
     }
@@ -55,7 +94,10 @@ impl Iterator for MySQLQueryResult<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.with_mut(|reader| {
             let schema = reader.schema.clone();
-            util::next_batch_from_rows(&schema, reader, 1024).transpose()
+            let row_schema = reader.row_schema.clone();
+            let batch_size = reader.batch_size;
+            let batch = util::next_batch_from_rows(&row_schema, reader, batch_size).transpose()?;
+            Some(batch.and_then(|b| super::types::convert_spatial(b, &schema)))
         })
     }
 }
@@ -63,6 +105,14 @@ impl Iterator for MySQLQueryResult<'_> {
 struct MySQLResultReader<'stmt> {
     result_set: mysql::ResultSet<'stmt, 'stmt, 'stmt, 'stmt, mysql::Binary>,
     schema: SchemaRef,
+
+    /// Schema that rows are read into, before converting spatial columns.
+    row_schema: SchemaRef,
+
+    /// Offset of the session time zone from UTC, in seconds.
+    utc_offset: i32,
+
+    batch_size: usize,
 }
 
 impl<'s> util::RowsReader<'s> for MySQLResultReader<'s> {
@@ -73,13 +123,19 @@ impl<'s> util::RowsReader<'s> for MySQLResultReader<'s> {
 
     fn next_row(&mut self) -> Result<Option<Self::CellReader<'_>>, ConnectorError> {
         let row = self.result_set.next().transpose()?;
-        Ok(row.map(|row| MySQLCellReader { row, cell: 0 }))
+        let utc_offset = self.utc_offset;
+        Ok(row.map(|row| MySQLCellReader {
+            row,
+            cell: 0,
+            utc_offset,
+        }))
     }
 }
 
 struct MySQLCellReader {
     row: mysql::Row,
     cell: usize,
+    utc_offset: i32,
 }
 
 impl util::CellReader<'_> for MySQLCellReader {
@@ -92,6 +148,7 @@ impl util::CellReader<'_> for MySQLCellReader {
         let r = MySQLCellRef {
             row: &mut self.row,
             cell: self.cell,
+            utc_offset: self.utc_offset,
         };
         self.cell += 1;
 
@@ -103,6 +160,7 @@ impl util::CellReader<'_> for MySQLCellReader {
 struct MySQLCellRef<'a> {
     row: &'a mut mysql::Row,
     cell: usize,
+    utc_offset: i32,
 }
 
 impl<'r> Produce<'r> for MySQLCellRef<'r> {}
@@ -112,7 +170,9 @@ macro_rules! impl_produce_ty {
         $(
             impl<'r> ProduceTy<'r, $t> for $p {
                 fn produce(self) -> Result<<$t as ArrowType>::Native, ConnectorError> {
-                    Ok(self.row.take(self.cell).unwrap())
+                    ProduceTy::<$t>::produce_opt(self).and_then(|x| {
+                        x.ok_or_else(|| ConnectorError::DataSchemaMismatch("unexpected NULL".into()))
+                    })
                 }
                 fn produce_opt(self) -> Result<Option<<$t as ArrowType>::Native>, ConnectorError> {
                     let res = self.row.take_opt(self.cell).unwrap();
@@ -152,7 +212,6 @@ impl_produce_unsupported!(
         Float16Type,
         TimestampSecondType,
         TimestampMillisecondType,
-        TimestampMicrosecondType,
         TimestampNanosecondType,
         Date32Type,
         Date64Type,
@@ -172,6 +231,9 @@ impl_produce_unsupported!(
         FixedSizeBinaryType,
         Decimal128Type,
         Decimal256Type,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
 
@@ -208,3 +270,32 @@ impl<'r> ProduceTy<'r, Utf8Type> for MySQLCellRef<'r> {
         }
     }
 }
+
+impl<'r> ProduceTy<'r, TimestampMicrosecondType> for MySQLCellRef<'r> {
+    fn produce(self) -> Result<i64, ConnectorError> {
+        ProduceTy::<TimestampMicrosecondType>::produce_opt(self).and_then(|x| {
+            x.ok_or_else(|| ConnectorError::DataSchemaMismatch("unexpected NULL".into()))
+        })
+    }
+    fn produce_opt(self) -> Result<Option<i64>, ConnectorError> {
+        let res: mysql::Value = self.row.take(self.cell).unwrap();
+        match res {
+            mysql::Value::NULL => Ok(None),
+            mysql::Value::Date(year, month, day, hour, minutes, seconds, micro_seconds) => {
+                // value is in session time zone, which is utc_offset ahead of UTC
+                let date_time = chrono::NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+                    .and_then(|d| {
+                        d.and_hms_micro_opt(hour as u32, minutes as u32, seconds as u32, micro_seconds)
+                    })
+                    .ok_or_else(|| {
+                        ConnectorError::DataSchemaMismatch(format!(
+                            "invalid TIMESTAMP: {year:04}-{month:02}-{day:02} {hour:02}:{minutes:02}:{seconds:02}"
+                        ))
+                    })?;
+                let local_micro = date_time.and_utc().timestamp_micros();
+                Ok(Some(local_micro - self.utc_offset as i64 * 1_000_000))
+            }
+            v => Err(ConnectorError::from(mysql::Error::FromValueError(v))),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use itertools::Itertools;
 use mysql::prelude::Queryable;
 
 use crate::{
-    api::{Connector, SchemaEdit, SchemaGet},
+    api::{Connector, ForeignKey, SchemaEdit, SchemaGet},
     mysql::MySQLConnection,
     util::escape::escaped_ident_bt,
     ConnectorError, TableCreateError, TableDropError,
@@ -13,7 +13,11 @@ use crate::{
 
 impl<C: Queryable> SchemaGet for super::MySQLConnection<C> {
     fn table_list(&mut self) -> Result<Vec<String>, crate::ConnectorError> {
-        let mut results = self.queryable.exec_iter("SHOW TABLES;", ())?;
+        let query = "
+            SELECT table_name FROM information_schema.tables
+            WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE'
+        ";
+        let mut results = self.queryable.exec_iter(query, ())?;
         let result = results.iter().ok_or(crate::ConnectorError::NoResultSets)?;
 
         let table_names = result
@@ -24,6 +28,16 @@ impl<C: Queryable> SchemaGet for super::MySQLConnection<C> {
         Ok(table_names)
     }
 
+    /// Case-sensitivity of the comparison depends on `lower_case_table_names` of the server.
+    fn table_exists(&mut self, name: &str) -> Result<bool, crate::ConnectorError> {
+        let query = "
+            SELECT COUNT(*) FROM information_schema.tables
+            WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE' AND table_name = ?
+        ";
+        let count: Option<u64> = self.queryable.exec_first(query, (name,))?;
+        Ok(count.unwrap_or_default() > 0)
+    }
+
     fn table_get(
         &mut self,
         name: &str,
@@ -48,6 +62,72 @@ impl<C: Queryable> SchemaGet for super::MySQLConnection<C> {
 
         Ok(Arc::new(Schema::new(fields)))
     }
+
+    fn table_primary_key(&mut self, name: &str) -> Result<Vec<String>, crate::ConnectorError> {
+        let query = "
+            SELECT kcu.column_name
+            FROM information_schema.table_constraints tc
+            JOIN information_schema.key_column_usage kcu USING (
+                constraint_schema, constraint_name, table_name
+            )
+            WHERE tc.constraint_type = 'PRIMARY KEY'
+                AND tc.table_schema = DATABASE() AND tc.table_name = ?
+            ORDER BY kcu.ordinal_position
+        ";
+        let key: Vec<String> = self.queryable.exec(query, (name,))?;
+        Ok(key)
+    }
+
+    fn table_foreign_keys(&mut self, name: &str) -> Result<Vec<ForeignKey>, crate::ConnectorError> {
+        let query = "
+            SELECT constraint_name, column_name, referenced_table_name, referenced_column_name
+            FROM information_schema.key_column_usage
+            WHERE table_schema = DATABASE() AND table_name = ?
+                AND referenced_table_name IS NOT NULL
+            ORDER BY constraint_name, ordinal_position
+        ";
+        let rows: Vec<(String, String, String, String)> = self.queryable.exec(query, (name,))?;
+
+        // one row per column, grouped by constraint
+        let mut keys: Vec<(String, ForeignKey)> = Vec::new();
+        for (constraint, column, referenced_table, referenced_column) in rows {
+            if keys.last().is_none_or(|(last, _)| *last != constraint) {
+                let key = ForeignKey {
+                    columns: Vec::new(),
+                    referenced_table,
+                    referenced_columns: Vec::new(),
+                };
+                keys.push((constraint, key));
+            }
+            let (_, key) = keys.last_mut().unwrap();
+            key.columns.push(column);
+            key.referenced_columns.push(referenced_column);
+        }
+        Ok(keys.into_iter().map(|(_, key)| key).collect())
+    }
+}
+
+/// Reads the estimate from `information_schema.tables.table_rows`.
+/// For InnoDB tables this number is sampled and may vary by as much as 40% to 50%.
+pub(super) fn estimate_row_count<C: Queryable>(
+    conn: &mut MySQLConnection<C>,
+    table_name: &str,
+) -> Result<u64, ConnectorError> {
+    let query = "
+        SELECT table_rows FROM information_schema.tables
+        WHERE table_schema = DATABASE() AND table_name = ?
+    ";
+    let table_rows: Option<Option<u64>> = conn.queryable.exec_first(query, (table_name,))?;
+
+    match table_rows.flatten() {
+        Some(table_rows) => Ok(table_rows),
+        // views do not have statistics
+        None => {
+            let query = format!("SELECT COUNT(*) FROM {}", escaped_ident_bt(table_name));
+            let count: Option<u64> = conn.queryable.query_first(query)?;
+            count.ok_or(ConnectorError::NoResultSets)
+        }
+    }
 }
 
 impl<C: Queryable> SchemaEdit for super::MySQLConnection<C> {
@@ -98,4 +178,32 @@ impl<C: Queryable> SchemaEdit for super::MySQLConnection<C> {
             Err(e) => Err(TableDropError::Connector(ConnectorError::MySQL(e))),
         }
     }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "RENAME TABLE {} TO {}",
+            escaped_ident_bt(from),
+            escaped_ident_bt(to)
+        );
+        self.queryable.query_drop(ddl)?;
+        Ok(())
+    }
+
+    /// Requires MySQL 8.0 or later.
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {}",
+            escaped_ident_bt(table),
+            escaped_ident_bt(from),
+            escaped_ident_bt(to)
+        );
+        self.queryable.query_drop(ddl)?;
+        Ok(())
+    }
+}
+
+pub(super) fn list_catalogs<C: Queryable>(
+    conn: &mut MySQLConnection<C>,
+) -> Result<Vec<String>, ConnectorError> {
+    Ok(conn.queryable.query("SHOW DATABASES")?)
 }
@@ -1,28 +1,43 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
 use std::sync::Arc;
 
+use arrow::array::*;
 use arrow::datatypes::*;
 use mysql::consts::{ColumnFlags, ColumnType};
-use mysql::prelude::Protocol;
 
-use crate::api::Connector;
+use crate::api::{Connector, METADATA_DB_TYPE};
 use crate::ConnectorError;
 
-pub fn get_result_schema<'a, P: Protocol>(
-    result: &mysql::ResultSet<'a, 'a, 'a, 'a, P>,
+pub fn get_result_schema(
+    columns: &[mysql::Column],
+    spatial_srid: bool,
+    utc_offset: Option<i32>,
+    datetime_utc: bool,
 ) -> Result<SchemaRef, ConnectorError> {
     let mut fields = Vec::new();
-    for column in result.columns().as_ref() {
+    for column in columns {
         let is_unsigned = !(column.flags() & ColumnFlags::UNSIGNED_FLAG).is_empty();
         let is_not_null = !(column.flags() & ColumnFlags::NOT_NULL_FLAG).is_empty();
         let _is_blob = !(column.flags() & ColumnFlags::BLOB_FLAG).is_empty();
         let is_binary = !(column.flags() & ColumnFlags::BINARY_FLAG).is_empty();
 
         let db_ty = get_name_of_column_type(&column.column_type(), is_unsigned, is_binary);
-        fields.push(create_field(
-            column.name_str().to_string(),
-            db_ty,
-            !is_not_null,
-        ));
+        let mut field = create_field(column.name_str().to_string(), db_ty, !is_not_null);
+        if spatial_srid && db_ty == "geometry" {
+            field = field.with_data_type(spatial_struct_type());
+        }
+        let is_timestamp = match db_ty {
+            "timestamp" | "timestamp2" => true,
+            "datetime" | "datetime2" => datetime_utc,
+            _ => false,
+        };
+        if let (Some(offset), true) = (utc_offset, is_timestamp) {
+            let tz = format_utc_offset(offset);
+            field =
+                field.with_data_type(DataType::Timestamp(TimeUnit::Microsecond, Some(tz.into())));
+        }
+        fields.push(field);
     }
 
     Ok(Arc::new(Schema::new(fields)))
@@ -32,7 +47,12 @@ pub fn create_field(name: String, db_ty: &str, nullable: bool) -> Field {
     let data_type = super::MySQLConnection::<mysql::Conn>::type_db_into_arrow(db_ty);
     let data_type = data_type.unwrap_or_else(|| todo!("db type: {db_ty}"));
 
-    Field::new(name, data_type, nullable)
+    let mut metadata = HashMap::new();
+    if db_ty == "geometry" {
+        metadata.insert(METADATA_DB_TYPE.to_string(), db_ty.to_string());
+    }
+
+    Field::new(name, data_type, nullable).with_metadata(metadata)
 }
 
 fn get_name_of_column_type(col_ty: &ColumnType, unsigned: bool, binary: bool) -> &'static str {
@@ -100,3 +120,109 @@ fn get_name_of_column_type(col_ty: &ColumnType, unsigned: bool, binary: bool) ->
         (MYSQL_TYPE_UNKNOWN, _, _) => "unknown",
     }
 }
+
+/// Offset of the session time zone from UTC in seconds, when TIMESTAMP columns are
+/// read as timestamps: either queried from the session or zero, if it was set to UTC.
+pub fn utc_offset<Q: mysql::prelude::Queryable>(
+    queryable: &mut Q,
+    session_time_zone: bool,
+    assume_utc: bool,
+) -> Result<Option<i32>, ConnectorError> {
+    if assume_utc {
+        Ok(Some(0))
+    } else if session_time_zone {
+        session_utc_offset(queryable).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn session_utc_offset<Q: mysql::prelude::Queryable>(
+    queryable: &mut Q,
+) -> Result<i32, ConnectorError> {
+    let offset: Option<i32> =
+        queryable.query_first("SELECT TIMESTAMPDIFF(SECOND, UTC_TIMESTAMP(), NOW())")?;
+    offset.ok_or(ConnectorError::NoResultSets)
+}
+
+/// Formats offset in seconds as `+HH:MM`, which is a timezone understood by arrow.
+fn format_utc_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+    format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Type of spatial columns, when they are read with SRID.
+pub fn spatial_struct_type() -> DataType {
+    DataType::Struct(Fields::from(vec![
+        Field::new("srid", DataType::UInt32, false),
+        Field::new("wkb", DataType::Binary, false),
+    ]))
+}
+
+/// Returns the schema that rows are read into: spatial columns are read
+/// as [DataType::Binary] containing MySQL internal geometry format.
+pub fn get_row_schema(schema: &SchemaRef) -> SchemaRef {
+    let fields = schema.fields().iter().map(|f| {
+        if is_spatial(f) {
+            Arc::new(f.as_ref().clone().with_data_type(DataType::Binary))
+        } else {
+            f.clone()
+        }
+    });
+    Arc::new(Schema::new(fields.collect::<Vec<_>>()))
+}
+
+fn is_spatial(field: &Field) -> bool {
+    field.metadata().get(METADATA_DB_TYPE).map(String::as_str) == Some("geometry")
+}
+
+/// Converts spatial columns from MySQL internal geometry format, which is
+/// 4 byte little-endian SRID followed by WKB, into:
+/// - WKB binary, if output schema contains [DataType::Binary],
+/// - struct of SRID and WKB, if output schema contains [spatial_struct_type].
+pub fn convert_spatial(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+) -> Result<RecordBatch, ConnectorError> {
+    let mut columns = batch.columns().to_vec();
+    for (index, field) in schema.fields().iter().enumerate() {
+        if !is_spatial(field) {
+            continue;
+        }
+        let array = columns[index].as_binary::<i32>();
+
+        let mut srids = UInt32Builder::with_capacity(array.len());
+        let mut wkbs = BinaryBuilder::with_capacity(array.len(), array.value_data().len());
+        for value in array.iter() {
+            let Some(value) = value else {
+                srids.append_value(0);
+                wkbs.append_value([]);
+                continue;
+            };
+            if value.len() < 4 {
+                return Err(ConnectorError::DataSchemaMismatch(format!(
+                    "invalid geometry value in column {}",
+                    field.name()
+                )));
+            }
+            let (srid, wkb) = value.split_at(4);
+            srids.append_value(u32::from_le_bytes(srid.try_into().unwrap()));
+            wkbs.append_value(wkb);
+        }
+        let wkbs = wkbs.finish();
+
+        columns[index] = match field.data_type() {
+            DataType::Struct(fields) => Arc::new(StructArray::new(
+                fields.clone(),
+                vec![Arc::new(srids.finish()), Arc::new(wkbs)],
+                array.nulls().cloned(),
+            )),
+            _ => {
+                let (offsets, values, _) = wkbs.into_parts();
+                Arc::new(BinaryArray::new(offsets, values, array.nulls().cloned()))
+            }
+        };
+    }
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
@@ -5,7 +5,7 @@ use std::any::Any;
 use std::sync::Arc;
 
 use crate::api::ArrowValue;
-use crate::types::{FixedSizeBinaryType, NullType};
+use crate::types::{FixedSizeBinaryType, ListType, NullType};
 use crate::util::transport::{Produce, ProduceTy};
 use crate::util::ArrowRowWriter;
 use crate::{impl_produce_unsupported, ConnectorError};
@@ -33,6 +33,24 @@ pub(crate) fn vec_to_record_batch(
     })
 }
 
+/// Orders named parameters by position of their names.
+#[cfg(any(feature = "src_postgres", feature = "src_sqlite"))]
+pub(crate) fn named_to_record_batch(
+    names: &[String],
+    params: &[(&str, &dyn ArrowValue)],
+) -> Result<RecordBatch, ConnectorError> {
+    let args = names
+        .iter()
+        .map(|name| {
+            let param = params.iter().find(|(n, _)| n == name);
+            param
+                .map(|(_, value)| *value)
+                .ok_or_else(|| ConnectorError::UnboundParameter(name.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    vec_to_record_batch(args)
+}
+
 impl<'r> Produce<'r> for &'r dyn ArrowValue {}
 
 macro_rules! impl_arrow_value_plain {
@@ -154,4 +172,13 @@ impl_arrow_value_tuple!(String, (LargeUtf8Type,));
 
 impl_arrow_value_tuple!(Vec<u8>, (LargeBinaryType, FixedSizeBinaryType,));
 
-impl_produce_unsupported!(&'r dyn ArrowValue, (NullType, Float16Type,));
+impl_produce_unsupported!(
+    &'r dyn ArrowValue,
+    (
+        NullType,
+        Float16Type,
+        BinaryViewType,
+        StringViewType,
+        ListType,
+    )
+);
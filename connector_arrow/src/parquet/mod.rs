@@ -0,0 +1,102 @@
+//! Provides `connector_arrow` traits for Parquet files, read with [parquet crate](https://docs.rs/parquet).
+//!
+//! [ParquetConnection] is read-only. Its "tables" are Parquet files in a directory.
+//!
+//! ```ignore
+//! let mut conn = ParquetConnection::new("./data");
+//!
+//! let batches = connector_arrow::query(&mut conn, "SELECT a, b FROM 'file.parquet'")?;
+//! let batches = connector_arrow::query(&mut conn, "file.parquet")?;
+//! ```
+
+mod query;
+mod schema;
+
+#[doc(hidden)]
+pub use query::{ParquetReader, ParquetStatement};
+
+use std::path::{Path, PathBuf};
+
+use arrow::datatypes::{DataType, SchemaRef};
+
+use crate::api::{unimplemented, Connector, ResultReader};
+use crate::ConnectorError;
+
+pub struct ParquetConnection {
+    path: PathBuf,
+}
+
+impl ParquetConnection {
+    /// Creates a connection to a directory of Parquet files or to a single Parquet file.
+    ///
+    /// Relative paths in queries are resolved against the directory
+    /// (or the parent directory of the file).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        ParquetConnection { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn dir(&self) -> &Path {
+        if self.path.is_file() {
+            self.path.parent().unwrap_or_else(|| Path::new(""))
+        } else {
+            &self.path
+        }
+    }
+
+    fn resolve(&self, file: &str) -> PathBuf {
+        self.dir().join(file)
+    }
+}
+
+impl Connector for ParquetConnection {
+    type Stmt<'conn>
+        = ParquetStatement
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = unimplemented::Appender
+    where
+        Self: 'conn;
+
+    /// Accepts either `SELECT <columns> FROM '<file>'`, where columns are `*` or
+    /// a comma-separated list of column names, or just a path to the file.
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        let (file, columns) = query::parse_query(query);
+        Ok(ParquetStatement {
+            path: self.resolve(&file),
+            columns,
+        })
+    }
+
+    fn execute(&mut self, _query: &str) -> Result<u64, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::parquet",
+            feature: "execute",
+        })
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        let stmt = self.query(query)?;
+        stmt.open()?.get_schema()
+    }
+
+    fn append<'a>(&'a mut self, _table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::parquet",
+            feature: "append",
+        })
+    }
+
+    fn type_db_into_arrow(_database_ty: &str) -> Option<DataType> {
+        None
+    }
+
+    fn type_arrow_into_db(_ty: &DataType) -> Option<String> {
+        None
+    }
+}
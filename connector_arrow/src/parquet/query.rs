@@ -0,0 +1,151 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use once_cell::sync::Lazy;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+use regex::Regex;
+
+use crate::api::{ResultReader, Statement};
+use crate::ConnectorError;
+
+static SELECT_FROM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)^\s*SELECT\s+(.+?)\s+FROM\s+'([^']*)'\s*;?\s*$").unwrap());
+
+/// Splits the query into the file path and names of selected columns.
+/// Columns are None when all columns are selected.
+pub(super) fn parse_query(query: &str) -> (String, Option<Vec<String>>) {
+    let Some(captures) = SELECT_FROM.captures(query) else {
+        return (query.trim().to_string(), None);
+    };
+
+    let projection = captures[1].trim();
+    let columns = if projection == "*" {
+        None
+    } else {
+        let columns = projection.split(',').map(|c| {
+            let c = c.trim();
+            match c.strip_prefix('"').and_then(|c| c.strip_suffix('"')) {
+                Some(quoted) => quoted.replace("\"\"", "\""),
+                None => c.to_string(),
+            }
+        });
+        Some(columns.collect())
+    };
+    (captures[2].to_string(), columns)
+}
+
+pub struct ParquetStatement {
+    pub(super) path: PathBuf,
+    pub(super) columns: Option<Vec<String>>,
+}
+
+impl ParquetStatement {
+    pub(super) fn open(&self) -> Result<ParquetReader, ConnectorError> {
+        let file = File::open(&self.path)?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+
+        // read one row group per batch
+        let row_group_size = builder.metadata().row_groups().iter().map(|g| g.num_rows());
+        let batch_size = row_group_size.max().unwrap_or_default().max(1) as usize;
+        let builder = builder.with_batch_size(batch_size);
+
+        let Some(columns) = &self.columns else {
+            let schema = builder.schema().clone();
+            return Ok(ParquetReader {
+                schema,
+                inner: builder.build()?,
+                projection: None,
+            });
+        };
+
+        // find indices of selected columns
+        let file_schema = builder.schema().clone();
+        let mut indices = Vec::with_capacity(columns.len());
+        for column in columns {
+            let Some((index, _)) = file_schema.column_with_name(column) else {
+                return Err(ConnectorError::ColumnNotFound {
+                    table_name: self.path.display().to_string(),
+                    column: column.clone(),
+                });
+            };
+            indices.push(index);
+        }
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices.iter().copied());
+
+        // reader produces columns in the order of the file, so they have to be reordered
+        let mut sorted = indices.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        let projection = indices
+            .iter()
+            .map(|i| sorted.binary_search(i).unwrap())
+            .collect::<Vec<_>>();
+
+        let fields = indices.iter().map(|i| file_schema.field(*i).clone());
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields.collect::<Vec<_>>(),
+            file_schema.metadata().clone(),
+        ));
+        Ok(ParquetReader {
+            schema,
+            inner: builder.with_projection(mask).build()?,
+            projection: Some(projection),
+        })
+    }
+}
+
+impl<'conn> Statement<'conn> for ParquetStatement {
+    type Reader<'stmt>
+        = ParquetReader
+    where
+        Self: 'stmt;
+
+    fn start_batch(
+        &mut self,
+        args: (&RecordBatch, usize),
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        if args.0.num_columns() > 0 {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::parquet",
+                feature: "query parameters",
+            });
+        }
+        self.open()
+    }
+}
+
+pub struct ParquetReader {
+    schema: SchemaRef,
+    inner: ParquetRecordBatchReader,
+    projection: Option<Vec<usize>>,
+}
+
+impl ResultReader<'_> for ParquetReader {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        Ok(self.schema.clone())
+    }
+}
+
+impl Iterator for ParquetReader {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = match self.inner.next()? {
+            Ok(batch) => batch,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let batch = match &self.projection {
+            Some(projection) => batch.project(projection),
+            None => Ok(batch),
+        };
+        Some(
+            batch
+                .and_then(|b| b.with_schema(self.schema.clone()))
+                .map_err(ConnectorError::from),
+        )
+    }
+}
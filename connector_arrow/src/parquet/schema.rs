@@ -0,0 +1,36 @@
+use arrow::datatypes::SchemaRef;
+
+use crate::api::{ResultReader, SchemaGet};
+use crate::ConnectorError;
+
+use super::query::ParquetStatement;
+use super::ParquetConnection;
+
+impl SchemaGet for ParquetConnection {
+    /// Lists Parquet files in the directory (or the single file this connection was created for).
+    fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
+        if self.path.is_file() {
+            let name = self.path.file_name().unwrap_or_default();
+            return Ok(vec![name.to_string_lossy().into_owned()]);
+        }
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|e| e == "parquet") {
+                let name = path.file_name().unwrap_or_default();
+                files.push(name.to_string_lossy().into_owned());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn table_get(&mut self, name: &str) -> Result<SchemaRef, ConnectorError> {
+        let stmt = ParquetStatement {
+            path: self.resolve(name),
+            columns: None,
+        };
+        stmt.open()?.get_schema()
+    }
+}
@@ -0,0 +1,355 @@
+//! Connection pool, backed by [r2d2](https://docs.rs/r2d2).
+//!
+//! ```ignore
+//! let manager = r2d2_postgres::PostgresConnectionManager::new(config, postgres::NoTls);
+//! let pool = PooledConnector::new(manager)?;
+//!
+//! // pool can be cloned and shared across threads
+//! let mut conn = pool.checkout()?;
+//! let batches = connector_arrow::query(&mut conn, "SELECT 1")?;
+//! ```
+
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::api::{
+    AppenderOptions, CancelToken, Connector, QueryCost, ReadOptions, StatementCacheStats,
+};
+use crate::ConnectorError;
+
+pub use r2d2;
+
+/// A connection of a data store client crate, that can be wrapped into a [Connector].
+pub trait IntoConnector: Sized + Send + 'static {
+    type Connector: Connector + Send + 'static;
+
+    fn into_connector(self) -> Self::Connector;
+
+    fn from_connector_mut(connector: &mut Self::Connector) -> &mut Self;
+}
+
+/// Wraps a connection manager of a data store client crate (i.e. from `r2d2_postgres`),
+/// so it produces [Connector]s.
+#[derive(Debug)]
+pub struct ConnectorManager<M> {
+    inner: M,
+}
+
+impl<M> r2d2::ManageConnection for ConnectorManager<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    type Connection = <M::Connection as IntoConnector>::Connector;
+    type Error = M::Error;
+
+    fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        self.inner.connect().map(IntoConnector::into_connector)
+    }
+
+    fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        self.inner.is_valid(IntoConnector::from_connector_mut(conn))
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        self.inner
+            .has_broken(IntoConnector::from_connector_mut(conn))
+    }
+}
+
+/// A pool of connections. Cloning it is cheap and clones share the same pool.
+pub struct PooledConnector<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    pool: r2d2::Pool<ConnectorManager<M>>,
+}
+
+impl<M> Clone for PooledConnector<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    fn clone(&self) -> Self {
+        PooledConnector {
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+impl<M> PooledConnector<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    /// Creates a pool with default configuration.
+    /// Blocks until the pool has established its minimum number of connections.
+    pub fn new(manager: M) -> Result<Self, ConnectorError> {
+        Self::with_builder(r2d2::Pool::builder(), manager)
+    }
+
+    /// Creates a pool configured by the builder.
+    pub fn with_builder(
+        builder: r2d2::Builder<ConnectorManager<M>>,
+        manager: M,
+    ) -> Result<Self, ConnectorError> {
+        let pool = builder.build(ConnectorManager { inner: manager })?;
+        Ok(PooledConnector { pool })
+    }
+
+    /// Retrieves a connection from the pool, waiting for at most the connection timeout
+    /// of the pool. Connection is returned to the pool when the guard is dropped.
+    pub fn checkout(&self) -> Result<PooledConnection<M>, ConnectorError> {
+        Ok(PooledConnection {
+            inner: self.pool.get()?,
+        })
+    }
+
+    pub fn pool(&self) -> &r2d2::Pool<ConnectorManager<M>> {
+        &self.pool
+    }
+
+    /// Executes the query in `num_partitions` parts, each on its own connection and thread.
+    ///
+    /// Rows are split into ranges of `partition_column`, which must be numeric.
+    /// Ranges are computed from the minimum and maximum value of the column.
+    /// Rows with NULL in the column are included in the first partition.
+    ///
+    /// Returns batches of all partitions, in order of the ranges.
+    pub fn query_partitioned(
+        &self,
+        base_query: &str,
+        partition_column: &str,
+        num_partitions: usize,
+    ) -> Result<Vec<RecordBatch>, ConnectorError> {
+        let queries = {
+            let mut conn = self.checkout()?;
+            crate::dml::partition_queries(&mut conn, base_query, partition_column, num_partitions)?
+        };
+        log::debug!("partitioned queries: {queries:?}");
+
+        let results: Vec<Result<Vec<RecordBatch>, ConnectorError>> = std::thread::scope(|s| {
+            let threads: Vec<_> = (queries.iter())
+                .map(|query| {
+                    s.spawn(move || {
+                        let mut conn = self.checkout()?;
+                        crate::query(&mut conn, query)
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|t| t.join().expect("partition thread panicked"))
+                .collect()
+        });
+
+        let mut batches = Vec::new();
+        for result in results {
+            batches.extend(result?);
+        }
+        Ok(batches)
+    }
+}
+
+/// A connection that was checked out from [PooledConnector].
+///
+/// Statements and appenders borrow this guard, so they cannot outlive it.
+pub struct PooledConnection<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    inner: r2d2::PooledConnection<ConnectorManager<M>>,
+}
+
+impl<M> Deref for PooledConnection<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    type Target = <M::Connection as IntoConnector>::Connector;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<M> DerefMut for PooledConnection<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<M> Connector for PooledConnection<M>
+where
+    M: r2d2::ManageConnection,
+    M::Connection: IntoConnector,
+{
+    type Stmt<'conn>
+        = <<M::Connection as IntoConnector>::Connector as Connector>::Stmt<'conn>
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = <<M::Connection as IntoConnector>::Connector as Connector>::Append<'conn>
+    where
+        Self: 'conn;
+
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        self.inner.query(query)
+    }
+
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        self.inner.query_with(query, options)
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        self.inner.execute(query)
+    }
+
+    fn execute_batch(&mut self, statements: &[&str]) -> Result<Vec<u64>, ConnectorError> {
+        self.inner.execute_batch(statements)
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        self.inner.query_schema(query)
+    }
+
+    fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        self.inner.append(table_name)
+    }
+
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        self.inner.append_with(table_name, options)
+    }
+
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        self.inner.delete_by_keys(table_name, key_columns, keys)
+    }
+
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        self.inner.merge(table_name, key_columns, batch)
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError> {
+        self.inner.estimate_row_count(table_name)
+    }
+
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        self.inner.explain_cost(query)
+    }
+
+    fn load_parquet(&mut self, table_name: &str, path: &Path) -> Result<u64, ConnectorError> {
+        self.inner.load_parquet(table_name, path)
+    }
+
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        self.inner.set_statement_cache_size(size)
+    }
+
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        self.inner.statement_cache_stats()
+    }
+
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        self.inner.cancel_token()
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        self.inner.list_catalogs()
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.inner.begin_transaction()
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.inner.end_transaction(commit)
+    }
+
+    fn type_db_into_arrow(database_ty: &str) -> Option<DataType> {
+        <M::Connection as IntoConnector>::Connector::type_db_into_arrow(database_ty)
+    }
+
+    fn type_arrow_into_db(ty: &DataType) -> Option<String> {
+        <M::Connection as IntoConnector>::Connector::type_arrow_into_db(ty)
+    }
+}
+
+#[cfg(feature = "src_postgres")]
+impl IntoConnector for postgres::Client {
+    type Connector = crate::postgres::PostgresConnection;
+
+    fn into_connector(self) -> Self::Connector {
+        crate::postgres::PostgresConnection::new(self)
+    }
+
+    fn from_connector_mut(connector: &mut Self::Connector) -> &mut Self {
+        connector.inner_mut()
+    }
+}
+
+#[cfg(feature = "src_sqlite")]
+impl IntoConnector for rusqlite::Connection {
+    type Connector = crate::sqlite::SQLiteConnection;
+
+    fn into_connector(self) -> Self::Connector {
+        crate::sqlite::SQLiteConnection::new(self)
+    }
+
+    fn from_connector_mut(connector: &mut Self::Connector) -> &mut Self {
+        connector.inner_mut()
+    }
+}
+
+#[cfg(feature = "src_duckdb")]
+impl IntoConnector for duckdb::Connection {
+    type Connector = crate::duckdb::DuckDBConnection;
+
+    fn into_connector(self) -> Self::Connector {
+        crate::duckdb::DuckDBConnection::new(self)
+    }
+
+    fn from_connector_mut(connector: &mut Self::Connector) -> &mut Self {
+        connector.inner_mut()
+    }
+}
+
+#[cfg(feature = "src_mysql")]
+impl IntoConnector for mysql::Conn {
+    type Connector = crate::mysql::MySQLConnection<mysql::Conn>;
+
+    fn into_connector(self) -> Self::Connector {
+        crate::mysql::MySQLConnection::new(self)
+    }
+
+    fn from_connector_mut(connector: &mut Self::Connector) -> &mut Self {
+        connector.inner_mut()
+    }
+}
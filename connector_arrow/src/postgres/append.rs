@@ -1,38 +1,262 @@
 use std::sync::Arc;
 
+use arrow::array::{Array, ArrayRef, AsArray};
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
-use bytes::BytesMut;
+use bytes::{BufMut, BytesMut};
 use itertools::{zip_eq, Itertools};
 use postgres::binary_copy::BinaryCopyInWriter;
-use postgres::types::{to_sql_checked, IsNull, ToSql};
-use postgres::{Client, CopyInWriter};
+use postgres::types::{to_sql_checked, IsNull, ToSql, Type};
+use postgres::{Client, CopyInWriter, Statement, Transaction};
 use postgres_protocol::types as postgres_proto;
 
-use crate::api::Append;
-use crate::types::{FixedSizeBinaryType, NullType};
+use crate::api::{Append, ConflictMode};
+use crate::types::{FixedSizeBinaryType, ListType, NullType};
 use crate::util::escape::escaped_ident;
 use crate::util::transport::{Consume, ConsumeTy};
-use crate::util::ArrayCellRef;
+use crate::util::{coerce, ArrayCellRef};
 use crate::{impl_consume_unsupported, ConnectorError};
 
+use super::query::DUR_1970_TO_2000_SEC;
 use super::PostgresError;
 
 pub struct PostgresAppender<'c> {
-    writer: Writer<'c>,
+    inner: Inner<'c>,
 }
 
+enum Inner<'c> {
+    /// Rows are copied directly into the table.
+    Copy(Writer<'c>),
+
+    /// Rows are inserted one by one, for relations that do not support COPY.
+    Insert {
+        transaction: Scope<'c>,
+        statement: Statement,
+    },
+
+    /// Rows are copied into a staging table and then inserted into the table,
+    /// with an `ON CONFLICT` clause.
+    Upsert {
+        transaction: Scope<'c>,
+        types: Vec<Type>,
+        copy_query: String,
+        insert_query: String,
+        staging: String,
+    },
+}
+
+/// Transaction that rows are inserted in.
+enum Scope<'c> {
+    /// Transaction of the appender, committed by [Append::finish].
+    Own(Transaction<'c>),
+
+    /// Transaction started by [crate::api::Connector::transaction], which outlives the appender.
+    Outer(&'c mut Client),
+}
+
+impl<'c> Scope<'c> {
+    fn begin(client: &'c mut Client, in_transaction: bool) -> Result<Self, PostgresError> {
+        Ok(if in_transaction {
+            Scope::Outer(client)
+        } else {
+            Scope::Own(client.transaction()?)
+        })
+    }
+
+    fn prepare(&mut self, query: &str) -> Result<Statement, PostgresError> {
+        Ok(match self {
+            Scope::Own(t) => t.prepare(query)?,
+            Scope::Outer(c) => c.prepare(query)?,
+        })
+    }
+
+    fn execute(
+        &mut self,
+        statement: &Statement,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, PostgresError> {
+        Ok(match self {
+            Scope::Own(t) => t.execute(statement, params)?,
+            Scope::Outer(c) => c.execute(statement, params)?,
+        })
+    }
+
+    fn batch_execute(&mut self, query: &str) -> Result<(), PostgresError> {
+        match self {
+            Scope::Own(t) => t.batch_execute(query)?,
+            Scope::Outer(c) => c.batch_execute(query)?,
+        }
+        Ok(())
+    }
+
+    fn copy_in(&mut self, query: &str) -> Result<CopyInWriter<'_>, PostgresError> {
+        Ok(match self {
+            Scope::Own(t) => t.copy_in(query)?,
+            Scope::Outer(c) => c.copy_in(query)?,
+        })
+    }
+
+    fn commit(self) -> Result<(), PostgresError> {
+        match self {
+            Scope::Own(t) => Ok(t.commit()?),
+            Scope::Outer(_) => Ok(()),
+        }
+    }
+}
+
+/// Name of the temporary table that rows are copied into before they are upserted.
+const STAGING_TABLE: &str = "_connector_arrow_staging";
+
 impl<'conn> PostgresAppender<'conn> {
-    pub fn new(client: &'conn mut Client, table_name: &str) -> Result<Self, ConnectorError> {
-        let query = format!("COPY BINARY {} FROM stdin", escaped_ident(table_name));
-        let writer = client.copy_in(&query).map_err(PostgresError::Postgres)?;
-        let writer = Writer::Uninitialized(writer);
-        Ok(Self { writer })
+    /// Creates an appender that uses `COPY BINARY`, or falls back to `INSERT`
+    /// when the server rejects COPY into the relation (i.e. views without an
+    /// `INSTEAD OF INSERT` trigger or tables with row-level security).
+    ///
+    /// COPY that fails after rows were sent (i.e. on a constraint violation) is not
+    /// retried, because the rows are not kept.
+    pub fn new(
+        client: &'conn mut Client,
+        table_name: &str,
+        in_transaction: bool,
+    ) -> Result<Self, ConnectorError> {
+        let types = column_types(client, table_name)?;
+        let table = escaped_ident(table_name);
+
+        let query = format!("COPY BINARY {table} FROM stdin");
+        if supports_copy(client, &query, in_transaction)? {
+            let writer = client.copy_in(&query).map_err(PostgresError::Postgres)?;
+            let writer = Writer::Uninitialized(writer, types);
+            return Ok(Self {
+                inner: Inner::Copy(writer),
+            });
+        }
+
+        let params = (1..=types.len()).map(|i| format!("${i}")).join(", ");
+        let mut transaction = Scope::begin(client, in_transaction)?;
+        let statement = transaction.prepare(&format!("INSERT INTO {table} VALUES ({params})"))?;
+        Ok(Self {
+            inner: Inner::Insert {
+                transaction,
+                statement,
+            },
+        })
+    }
+
+    /// Creates an appender that resolves conflicts on unique constraints.
+    /// `columns` are the names of all columns of the table.
+    pub fn new_upsert(
+        client: &'conn mut Client,
+        table_name: &str,
+        columns: &[&str],
+        conflict: &ConflictMode,
+        in_transaction: bool,
+    ) -> Result<Self, ConnectorError> {
+        let on_conflict = match conflict {
+            ConflictMode::Error => String::new(),
+            ConflictMode::Ignore => " ON CONFLICT DO NOTHING".to_string(),
+            ConflictMode::Replace(conflict_columns) => {
+                let target = conflict_columns.iter().map(|c| escaped_ident(c)).join(", ");
+                let updates = columns
+                    .iter()
+                    .filter(|c| !conflict_columns.iter().any(|k| k == *c))
+                    .map(|c| format!("{c} = EXCLUDED.{c}", c = escaped_ident(c)))
+                    .join(", ");
+                if updates.is_empty() {
+                    format!(" ON CONFLICT ({target}) DO NOTHING")
+                } else {
+                    format!(" ON CONFLICT ({target}) DO UPDATE SET {updates}")
+                }
+            }
+        };
+
+        let types = column_types(client, table_name)?;
+        let table = escaped_ident(table_name);
+        let staging = escaped_ident(STAGING_TABLE);
+
+        // staging table is dropped when the transaction ends
+        let mut transaction = Scope::begin(client, in_transaction)?;
+        let ddl = format!(
+            "DROP TABLE IF EXISTS pg_temp.{staging}; \
+            CREATE TEMPORARY TABLE {staging} (LIKE {table}) ON COMMIT DROP"
+        );
+        transaction.batch_execute(&ddl)?;
+
+        Ok(Self {
+            inner: Inner::Upsert {
+                transaction,
+                types,
+                copy_query: format!("COPY BINARY {staging} FROM stdin"),
+                insert_query: format!("INSERT INTO {table} SELECT * FROM {staging}{on_conflict}"),
+                staging: staging.to_string(),
+            },
+        })
+    }
+}
+
+/// Name of the savepoint that COPY is probed within, when a transaction is open.
+const COPY_PROBE: &str = "connector_arrow_copy_probe";
+
+/// Starts and aborts the COPY, to check that the server accepts it for the relation.
+///
+/// The probe is needed because the writer of a started COPY holds the client,
+/// so it cannot be used for the `INSERT` fallback when the COPY fails.
+fn supports_copy(
+    client: &mut Client,
+    copy_query: &str,
+    in_transaction: bool,
+) -> Result<bool, ConnectorError> {
+    // failed COPY aborts the open transaction, unless it is rolled back to a savepoint
+    if in_transaction {
+        client
+            .batch_execute(&format!("SAVEPOINT {COPY_PROBE}"))
+            .map_err(PostgresError::Postgres)?;
+    }
+
+    // writer that is dropped before it is finished aborts the COPY
+    let res = client.copy_in(copy_query).map(drop);
+
+    if in_transaction {
+        client
+            .batch_execute(&format!(
+                "ROLLBACK TO SAVEPOINT {COPY_PROBE}; RELEASE SAVEPOINT {COPY_PROBE}"
+            ))
+            .map_err(PostgresError::Postgres)?;
+    }
+
+    match res {
+        Ok(()) => Ok(true),
+        Err(e) if e.as_db_error().is_some() => {
+            log::debug!("COPY rejected, falling back to INSERT: {e}");
+            Ok(false)
+        }
+        Err(e) => Err(PostgresError::Postgres(e).into()),
+    }
+}
+
+/// Types of the columns of the table, which are needed to encode values
+/// whose binary format depends on the column type (i.e. `jsonb`).
+fn column_types(client: &mut Client, table_name: &str) -> Result<Vec<Type>, ConnectorError> {
+    let query = format!("SELECT * FROM {}", escaped_ident(table_name));
+    let stmt = client.prepare(&query).map_err(PostgresError::Postgres)?;
+    Ok(stmt.columns().iter().map(|c| c.type_().clone()).collect())
+}
+
+/// Types of columns passed to [BinaryCopyInWriter].
+///
+/// Types are used for client-side checking of match between the
+/// declared type and passed value, which our ToSql::accepts skips,
+/// and for encoding of types with special binary format.
+/// If the batch does not match the table, COPY will fail on the server.
+pub(super) fn copy_types(types: Vec<Type>, num_columns: usize) -> Vec<Type> {
+    if types.len() == num_columns {
+        types
+    } else {
+        vec![Type::VOID; num_columns]
     }
 }
 
 enum Writer<'c> {
-    Uninitialized(CopyInWriter<'c>),
+    Uninitialized(CopyInWriter<'c>, Vec<Type>),
     Invalid,
     Initialized { writer: BinaryCopyInWriter<'c> },
 }
@@ -42,17 +266,12 @@ impl<'c> Writer<'c> {
         &mut self,
         schema: SchemaRef,
     ) -> Result<&mut BinaryCopyInWriter<'c>, ConnectorError> {
-        if let Writer::Uninitialized(_) = self {
+        if let Writer::Uninitialized(..) = self {
             // replace plain writer with a new binary one
-            let Writer::Uninitialized(w) = std::mem::replace(self, Writer::Invalid) else {
+            let Writer::Uninitialized(w, types) = std::mem::replace(self, Writer::Invalid) else {
                 unreachable!();
             };
-
-            // types don't really matter
-            // they are used only for client-side checking of match between the
-            // declared type and passed value.
-            // Because our ToSql::accepts returns true
-            let types = vec![postgres::types::Type::VOID; schema.fields().len()];
+            let types = copy_types(types, schema.fields().len());
 
             *self = Writer::Initialized {
                 writer: BinaryCopyInWriter::new(w, &types),
@@ -73,44 +292,97 @@ impl<'c> Writer<'c> {
             Writer::Initialized { writer: w, .. } => {
                 Ok(w.finish().map_err(PostgresError::Postgres)?)
             }
-            Writer::Uninitialized(_) | Writer::Invalid => unreachable!(),
+            Writer::Uninitialized(..) | Writer::Invalid => unreachable!(),
         }
     }
 }
 
 impl<'conn> Append<'conn> for PostgresAppender<'conn> {
     fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError> {
-        let writer = self.writer.as_binary(batch.schema())?;
-
-        let schema = batch.schema();
-        let mut row = zip_eq(batch.columns(), schema.fields())
-            .map(|(array, field)| ArrayCellRef {
-                array,
-                field,
-                row_number: 0,
-            })
-            .collect_vec();
-
-        for row_number in 0..batch.num_rows() {
-            for cell in &mut row {
-                cell.row_number = row_number;
+        let batch = coerce::decode_dictionaries(batch)?;
+        match &mut self.inner {
+            Inner::Copy(writer) => {
+                let writer = writer.as_binary(batch.schema())?;
+                write_rows(writer, &batch)
+            }
+            Inner::Insert {
+                transaction,
+                statement,
+            } => insert_rows(transaction, statement, &batch),
+            Inner::Upsert {
+                transaction,
+                types,
+                copy_query,
+                insert_query,
+                staging,
+            } => {
+                let writer = transaction.copy_in(copy_query.as_str())?;
+                let mut writer = Writer::Uninitialized(writer, types.clone());
+                write_rows(writer.as_binary(batch.schema())?, &batch)?;
+                writer.finish()?;
+
+                transaction.batch_execute(&format!("{insert_query}; TRUNCATE {staging}"))?;
+                Ok(())
             }
-
-            writer.write_raw(&row).map_err(PostgresError::Postgres)?;
         }
-        Ok(())
     }
 
     fn finish(self) -> Result<(), ConnectorError> {
-        self.writer.finish()?;
+        match self.inner {
+            Inner::Copy(writer) => {
+                writer.finish()?;
+            }
+            Inner::Insert { transaction, .. } | Inner::Upsert { transaction, .. } => {
+                transaction.commit()?;
+            }
+        }
         Ok(())
     }
 }
 
+fn write_rows(writer: &mut BinaryCopyInWriter, batch: &RecordBatch) -> Result<(), ConnectorError> {
+    let schema = batch.schema();
+    let mut row = zip_eq(batch.columns(), schema.fields())
+        .map(|(array, field)| ArrayCellRef {
+            array,
+            field,
+            row_number: 0,
+        })
+        .collect_vec();
+
+    for row_number in 0..batch.num_rows() {
+        for cell in &mut row {
+            cell.row_number = row_number;
+        }
+
+        writer.write_raw(&row).map_err(PostgresError::Postgres)?;
+    }
+    Ok(())
+}
+
+fn insert_rows(
+    transaction: &mut Scope,
+    statement: &Statement,
+    batch: &RecordBatch,
+) -> Result<(), ConnectorError> {
+    let schema = batch.schema();
+    for row_number in 0..batch.num_rows() {
+        let row = zip_eq(batch.columns(), schema.fields()).map(|(array, field)| ArrayCellRef {
+            array,
+            field,
+            row_number,
+        });
+        let row = row.collect_vec();
+        let params = row.iter().map(|c| c as &(dyn ToSql + Sync)).collect_vec();
+        transaction.execute(statement, &params)?;
+    }
+    Ok(())
+}
+
 impl ToSql for ArrayCellRef<'_> {
     fn to_sql(
         &self,
-        _ty: &postgres::types::Type,
+        ty: &postgres::types::Type,
         out: &mut BytesMut,
     ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
     where
@@ -119,7 +391,27 @@ impl ToSql for ArrayCellRef<'_> {
         if self.array.is_null(self.row_number) || matches!(self.field.data_type(), DataType::Null) {
             return Ok(IsNull::Yes);
         }
-        crate::util::transport::transport(self.field, self, out)?;
+        match self.field.data_type() {
+            DataType::List(field) => {
+                let values = self.array.as_list::<i32>().value(self.row_number);
+                list_to_sql(&values, field, out)?;
+            }
+            DataType::LargeList(field) => {
+                let values = self.array.as_list::<i64>().value(self.row_number);
+                list_to_sql(&values, field, out)?;
+            }
+            DataType::Utf8 | DataType::LargeUtf8 if *ty == Type::JSONB => {
+                // binary format of jsonb is a version byte, followed by JSON text
+                out.put_u8(1);
+                crate::util::transport::transport(self.field, self, out)?;
+            }
+            DataType::Timestamp(TimeUnit::Microsecond, _) if *ty == Type::TIMESTAMPTZ => {
+                let micros = self.array.as_primitive::<TimestampMicrosecondType>();
+                let micros = timestamp_into_y2000(micros.value(self.row_number))?;
+                postgres_proto::timestamp_to_sql(micros, out);
+            }
+            _ => crate::util::transport::transport(self.field, self, out)?,
+        }
         Ok(IsNull::No)
     }
 
@@ -134,6 +426,69 @@ impl ToSql for ArrayCellRef<'_> {
     to_sql_checked!();
 }
 
+/// Converts microseconds since 1970-01-01 into microseconds since 2000-01-01,
+/// which is the binary format of `timestamptz`.
+/// Inverse of `TimestampY2000::into_microsecond`.
+fn timestamp_into_y2000(micros: i64) -> Result<i64, ConnectorError> {
+    micros
+        .checked_sub(DUR_1970_TO_2000_SEC * 1000 * 1000)
+        .ok_or(ConnectorError::DataOutOfRange)
+}
+
+/// Encodes values of a list as a one-dimensional Postgres array.
+fn list_to_sql(
+    values: &ArrayRef,
+    field: &Field,
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let element_type = array_element_type(field.data_type())
+        .ok_or_else(|| format!("cannot append list of {} into an array", field.data_type()))?;
+
+    // empty arrays have zero dimensions
+    let dimensions = (!values.is_empty()).then(|| postgres_proto::ArrayDimension {
+        len: values.len() as i32,
+        lower_bound: 1,
+    });
+
+    let cells = (0..values.len()).map(|row_number| ArrayCellRef {
+        array: values,
+        field,
+        row_number,
+    });
+    postgres_proto::array_to_sql(
+        dimensions,
+        element_type.oid(),
+        cells,
+        |cell, buf| match cell.to_sql(&element_type, buf)? {
+            IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+            IsNull::No => Ok(postgres_protocol::IsNull::No),
+        },
+        out,
+    )
+}
+
+/// Postgres type of array elements, matching [super::PostgresConnection::type_arrow_into_db].
+/// COPY BINARY checks that the element type of the array matches the column.
+fn array_element_type(ty: &DataType) -> Option<Type> {
+    Some(match ty {
+        DataType::Boolean => Type::BOOL,
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => Type::INT2,
+        DataType::Int32 | DataType::UInt16 => Type::INT4,
+        DataType::Int64 | DataType::UInt32 => Type::INT8,
+        DataType::UInt64 => Type::NUMERIC,
+        DataType::Float16 | DataType::Float32 => Type::FLOAT4,
+        DataType::Float64 => Type::FLOAT8,
+        DataType::Timestamp(_, _) => Type::INT8,
+        DataType::Date32 | DataType::Time32(_) => Type::INT4,
+        DataType::Date64 | DataType::Time64(_) | DataType::Duration(_) => Type::INT8,
+        DataType::Utf8 | DataType::LargeUtf8 => Type::TEXT,
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => Type::BYTEA,
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => Type::NUMERIC,
+        DataType::Interval(IntervalUnit::YearMonth | IntervalUnit::DayTime) => Type::INTERVAL,
+        _ => return None,
+    })
+}
+
 impl Consume for BytesMut {}
 
 macro_rules! impl_consume_ty {
@@ -204,9 +559,27 @@ impl_consume_ty!(DurationMillisecondType, postgres_proto::int8_to_sql);
 impl_consume_ty!(DurationMicrosecondType, postgres_proto::int8_to_sql);
 impl_consume_ty!(DurationNanosecondType, postgres_proto::int8_to_sql);
 
-// impl_consume_ty!(IntervalYearMonthType,  );
-// impl_consume_ty!(IntervalDayTimeType,  );
-// impl_consume_ty!(IntervalMonthDayNanoType,  );
+impl_consume_ty!(
+    IntervalYearMonthType,
+    interval_to_sql,
+    year_month_to_interval
+);
+impl_consume_ty!(IntervalDayTimeType, interval_to_sql, day_time_to_interval);
+
+fn year_month_to_interval(months: i32) -> (i64, i32, i32) {
+    (0, 0, months)
+}
+
+fn day_time_to_interval(value: IntervalDayTime) -> (i64, i32, i32) {
+    (value.milliseconds as i64 * 1000, value.days, 0)
+}
+
+/// Binary format of `interval` is microseconds (int8), days (int4) and months (int4).
+fn interval_to_sql((micros, days, months): (i64, i32, i32), out: &mut BytesMut) {
+    out.put_i64(micros);
+    out.put_i32(days);
+    out.put_i32(months);
+}
 
 impl_consume_ref_ty!(BinaryType, postgres_proto::bytea_to_sql);
 impl_consume_ref_ty!(LargeBinaryType, postgres_proto::bytea_to_sql);
@@ -250,8 +623,9 @@ impl ConsumeTy<Decimal256Type> for BytesMut {
 impl_consume_unsupported!(
     BytesMut,
     (
-        IntervalYearMonthType,
-        IntervalDayTimeType,
         IntervalMonthDayNanoType,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
@@ -0,0 +1,122 @@
+use std::pin::pin;
+
+use tokio_postgres::binary_copy::BinaryCopyInWriter;
+use tokio_postgres::Client;
+
+use arrow::record_batch::RecordBatch;
+
+use crate::api::AsyncConnector;
+use crate::util::escape::escaped_ident;
+use crate::util::{ArrayCellRef, ArrowReader};
+use crate::ConnectorError;
+
+use super::{append, query, types, PostgresError};
+
+/// Connection to PostgreSQL that implements [AsyncConnector], over a [tokio_postgres::Client].
+///
+/// The connection object returned by [tokio_postgres::connect] must be spawned onto
+/// the runtime by the caller.
+pub struct PostgresAsyncConnection {
+    client: Client,
+    options: types::TypeOptions,
+}
+
+impl PostgresAsyncConnection {
+    pub fn new(client: Client) -> Self {
+        PostgresAsyncConnection {
+            client,
+            options: types::TypeOptions::default(),
+        }
+    }
+
+    /// See [super::PostgresConnection::with_timestamptz_naive].
+    pub fn with_timestamptz_naive(mut self, naive: bool) -> Self {
+        self.options.timestamptz_naive = naive;
+        self
+    }
+
+    /// See [super::PostgresConnection::with_numeric_decimal].
+    pub fn with_numeric_decimal(mut self, enabled: bool) -> Self {
+        self.options.numeric_decimal = enabled;
+        self
+    }
+
+    pub fn unwrap(self) -> Client {
+        self.client
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Client {
+        &mut self.client
+    }
+}
+
+impl AsyncConnector for PostgresAsyncConnection {
+    async fn query(&mut self, query: &str) -> Result<ArrowReader, ConnectorError> {
+        let stmt = self
+            .client
+            .prepare(query)
+            .await
+            .map_err(PostgresError::Postgres)?;
+        let rows = self
+            .client
+            .query(&stmt, &[])
+            .await
+            .map_err(PostgresError::Postgres)?;
+
+        query::rows_into_reader(&stmt, rows, &self.options)
+    }
+
+    async fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let affected = self
+            .client
+            .execute(query, &[])
+            .await
+            .map_err(PostgresError::Postgres)?;
+        Ok(affected)
+    }
+
+    /// Rows are written with `COPY BINARY`.
+    async fn append(
+        &mut self,
+        table_name: &str,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(), ConnectorError> {
+        let table = escaped_ident(table_name);
+
+        // see append::column_types
+        let query = format!("SELECT * FROM {table}");
+        let stmt = self
+            .client
+            .prepare(&query)
+            .await
+            .map_err(PostgresError::Postgres)?;
+        let mut types = stmt.columns().iter().map(|c| c.type_().clone()).collect();
+        if let Some(batch) = batches.first() {
+            types = append::copy_types(types, batch.num_columns());
+        }
+
+        let query = format!("COPY BINARY {table} FROM stdin");
+        let sink = self
+            .client
+            .copy_in(&query)
+            .await
+            .map_err(PostgresError::Postgres)?;
+        let mut writer = pin!(BinaryCopyInWriter::new(sink, &types));
+
+        for batch in &batches {
+            let mut row = ArrayCellRef::vec_from_batch(batch, 0);
+            for row_number in 0..batch.num_rows() {
+                for cell in &mut row {
+                    cell.row_number = row_number;
+                }
+                writer
+                    .as_mut()
+                    .write_raw(&row)
+                    .await
+                    .map_err(PostgresError::Postgres)?;
+            }
+        }
+        writer.finish().await.map_err(PostgresError::Postgres)?;
+        Ok(())
+    }
+}
@@ -2,7 +2,6 @@
 
 use arrow::{array::ArrowNativeTypeOp, datatypes::i256};
 use bytes::{BufMut, BytesMut};
-use itertools::Itertools;
 use std::io::{Cursor, Read};
 
 fn read_two_bytes(cursor: &mut Cursor<&[u8]>) -> std::io::Result<[u8; 2]> {
@@ -35,6 +34,8 @@ fn read_two_bytes(cursor: &mut Cursor<&[u8]>) -> std::io::Result<[u8; 2]> {
 // 15 E0 = 5600
 //   result = result + 5600 * 0.00000001;
 
+/// Decodes a numeric into its text representation, which is the same as Postgres's
+/// text output: it has exactly `dscale` fractional digits, including trailing zeros.
 pub fn from_sql(raw: &[u8]) -> std::io::Result<String> {
     let mut raw = Cursor::new(raw);
     let num_groups = u16::from_be_bytes(read_two_bytes(&mut raw)?);
@@ -51,47 +52,170 @@ pub fn from_sql(raw: &[u8]) -> std::io::Result<String> {
         0xD000 => return Ok("Infinity".into()),
         0xF000 => return Ok("-Infinity".into()),
         0xC000 => return Ok("NaN".into()),
-        _ => panic!("invalid sign value: {:x}", sign),
+        _ => return Err(invalid_sign(sign)),
     };
 
-    // read all of the groups
-    let mut buf = String::new();
-    let mut prev_remainder = 0;
-    for _ in 0..num_groups as usize {
-        let group = u16::from_be_bytes(read_two_bytes(&mut raw)?);
-        let number = group as u32 + prev_remainder * 1_0000;
-
-        buf += &(format!("{:0>4}", (number / 10)));
-        prev_remainder = number % 10;
+    // Groups that are zero might be omitted at both ends, so each group is placed
+    // by its weight and the gaps are filled with zeros.
+    let mut int_part = String::new();
+    let mut int_groups = 0;
+    let mut frac_part = String::new();
+    for index in 0..num_groups as i32 {
+        let group = read_group(&mut raw)?;
+        let weight = weight_first as i32 - index;
+
+        if weight >= 0 {
+            int_groups += 1;
+            if int_part.is_empty() {
+                if group != 0 {
+                    int_part += &group.to_string();
+                }
+            } else {
+                int_part += &format!("{group:0>4}");
+            }
+        } else {
+            let frac_start = (-weight - 1) as usize * 4;
+            if frac_part.len() < frac_start {
+                frac_part += &"0".repeat(frac_start - frac_part.len());
+            }
+            frac_part += &format!("{group:0>4}");
+        }
     }
-    buf += &prev_remainder.to_string();
-
-    // find relevant slices
-    let dot = (weight_first * 4 + 5) as usize;
-    let frac_end = dot + scale as usize;
-    if frac_end > buf.len() {
-        buf += &"0".repeat(frac_end - buf.len());
+    if !int_part.is_empty() {
+        let omitted_groups = (weight_first as i32 + 1 - int_groups).max(0) as usize;
+        int_part += &"0000".repeat(omitted_groups);
     }
-    let (first_non_zero, _) = buf
-        .chars()
-        .find_position(|x| *x != '0')
-        .unwrap_or((dot - 1, '0'));
-    let int_start = first_non_zero.min(dot - 1);
 
     // compose result
     let mut res = String::new();
     if negate {
         res += "-";
     }
-    res += &buf[int_start..dot];
+    if int_part.is_empty() {
+        res += "0";
+    } else {
+        res += &int_part;
+    }
 
     if scale > 0 {
+        let scale = scale as usize;
+        if frac_part.len() < scale {
+            frac_part += &"0".repeat(scale - frac_part.len());
+        }
         res += ".";
-        res += &buf[dot..frac_end];
+        res += &frac_part[..scale];
     }
     Ok(res)
 }
 
+/// Reads a base-10000 digit, which must be in range `0..10000`.
+fn read_group(cursor: &mut Cursor<&[u8]>) -> std::io::Result<u16> {
+    let group = u16::from_be_bytes(read_two_bytes(cursor)?);
+    if group >= 10000 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid numeric digit: {group}"),
+        ));
+    }
+    Ok(group)
+}
+
+fn invalid_sign(sign: u16) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid numeric sign: {sign:#x}"),
+    )
+}
+
+/// Decimals cannot represent special values, so these are errors.
+fn decimal_sign(sign: u16) -> std::io::Result<bool> {
+    let msg = match sign {
+        0x0000 => return Ok(false),
+        0x4000 => return Ok(true),
+        0xC000 => "cannot convert NaN to decimal",
+        0xD000 | 0xF000 => "cannot convert Infinity to decimal",
+        _ => return Err(invalid_sign(sign)),
+    };
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, msg))
+}
+
+/// Decodes a numeric into an integer, scaled by `10^scale`.
+/// Digits beyond the scale are truncated.
+pub fn i128_from_sql(raw: &[u8], scale: i8) -> std::io::Result<i128> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let mut raw = Cursor::new(raw);
+    let num_groups = u16::from_be_bytes(read_two_bytes(&mut raw)?);
+    let weight_first = i16::from_be_bytes(read_two_bytes(&mut raw)?);
+    let sign = u16::from_be_bytes(read_two_bytes(&mut raw)?);
+    let _dscale = i16::from_be_bytes(read_two_bytes(&mut raw)?);
+
+    let negate = decimal_sign(sign)?;
+
+    let mut res: i128 = 0;
+    for index in 0..num_groups as i32 {
+        let group = read_group(&mut raw)? as i128;
+        if group == 0 {
+            // zero groups don't contribute, even if their exponent is out of range
+            continue;
+        }
+
+        // exponent of this group, in base 10
+        let exp = (weight_first as i32 - index) * 4 + scale as i32;
+        let value = if exp >= 0 {
+            10i128
+                .checked_pow(exp as u32)
+                .and_then(|m| group.checked_mul(m))
+        } else {
+            Some(10i128.checked_pow((-exp) as u32).map_or(0, |d| group / d))
+        };
+        res = value
+            .and_then(|v| res.checked_add(v))
+            .ok_or_else(|| invalid("numeric out of range of decimal"))?;
+    }
+
+    Ok(if negate { -res } else { res })
+}
+
+/// Decodes a numeric into a 256-bit integer, scaled by `10^scale`.
+/// Digits beyond the scale are truncated.
+pub fn i256_from_sql(raw: &[u8], scale: i8) -> std::io::Result<i256> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let mut raw = Cursor::new(raw);
+    let num_groups = u16::from_be_bytes(read_two_bytes(&mut raw)?);
+    let weight_first = i16::from_be_bytes(read_two_bytes(&mut raw)?);
+    let sign = u16::from_be_bytes(read_two_bytes(&mut raw)?);
+    let _dscale = i16::from_be_bytes(read_two_bytes(&mut raw)?);
+
+    let negate = decimal_sign(sign)?;
+
+    let ten = i256::from_i128(10);
+    let mut res = i256::ZERO;
+    for index in 0..num_groups as i32 {
+        let group = read_group(&mut raw)? as i128;
+        if group == 0 {
+            // zero groups don't contribute, even if their exponent is out of range
+            continue;
+        }
+
+        // exponent of this group, in base 10
+        let exp = (weight_first as i32 - index) * 4 + scale as i32;
+        let value = if exp >= 0 {
+            ten.checked_pow(exp as u32)
+                .and_then(|m| i256::from_i128(group).checked_mul(m))
+        } else {
+            let d = 10i128.checked_pow((-exp) as u32);
+            Some(i256::from_i128(d.map_or(0, |d| group / d)))
+        };
+        res = value
+            .and_then(|v| res.checked_add(v))
+            .ok_or_else(|| invalid("numeric out of range of decimal"))?;
+    }
+
+    Ok(if negate { res.wrapping_neg() } else { res })
+}
+
 pub fn i128_to_sql(data: i128, scale: i8, out: &mut BytesMut) {
     let neg = data < 0;
 
@@ -232,3 +356,132 @@ fn test_from_sql_03() {
     i128_to_sql(res.0, res.1 as i8, &mut bytes);
     assert_eq!(&raw, &bytes[..]);
 }
+
+#[test]
+fn test_i128_from_sql_01() {
+    // 3950.123456
+    let raw = [
+        0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x0F, 0x6E, 0x04, 0xD2, 0x15, 0xE0,
+    ];
+    assert_eq!(i128_from_sql(&raw, 6).unwrap(), 3950123456);
+    assert_eq!(i128_from_sql(&raw, 8).unwrap(), 395012345600);
+    assert_eq!(i128_from_sql(&raw, 2).unwrap(), 395012);
+}
+
+#[test]
+fn test_i128_from_sql_02() {
+    // -3950.123456
+    let raw = [
+        0x00, 0x03, 0x00, 0x00, 0x40, 0x00, 0x00, 0x06, 0x0F, 0x6E, 0x04, 0xD2, 0x15, 0xE0,
+    ];
+    assert_eq!(i128_from_sql(&raw, 6).unwrap(), -3950123456);
+
+    let mut bytes = BytesMut::new();
+    i128_to_sql(-12345678901234567890, 3, &mut bytes);
+    assert_eq!(i128_from_sql(&bytes, 3).unwrap(), -12345678901234567890);
+}
+
+#[test]
+fn test_i256_from_sql_01() {
+    // -3950.123456
+    let raw = [
+        0x00, 0x03, 0x00, 0x00, 0x40, 0x00, 0x00, 0x06, 0x0F, 0x6E, 0x04, 0xD2, 0x15, 0xE0,
+    ];
+    assert_eq!(
+        i256_from_sql(&raw, 6).unwrap(),
+        i256::from_i128(-3950123456)
+    );
+    assert_eq!(i256_from_sql(&raw, 2).unwrap(), i256::from_i128(-395012));
+
+    let value = i256::from_string("123456789012345678901234567890123456789012345678901").unwrap();
+    let mut bytes = BytesMut::new();
+    i256_to_sql(value, 10, &mut bytes);
+    assert_eq!(i256_from_sql(&bytes, 10).unwrap(), value);
+}
+
+/// Encodes a numeric in binary format, from its header and base-10000 digits.
+#[cfg(test)]
+fn numeric_raw(weight: i16, sign: u16, dscale: i16, groups: &[u16]) -> Vec<u8> {
+    let mut raw = Vec::new();
+    raw.extend((groups.len() as u16).to_be_bytes());
+    raw.extend(weight.to_be_bytes());
+    raw.extend(sign.to_be_bytes());
+    raw.extend(dscale.to_be_bytes());
+    raw.extend(groups.iter().flat_map(|g| g.to_be_bytes()));
+    raw
+}
+
+#[test]
+fn test_numeric_zero() {
+    // 0 has no groups
+    let raw = numeric_raw(0, 0x0000, 0, &[]);
+    assert_eq!(&from_sql(&raw).unwrap(), "0");
+    assert_eq!(i128_from_sql(&raw, 4).unwrap(), 0);
+    assert_eq!(i256_from_sql(&raw, 4).unwrap(), i256::ZERO);
+
+    // 0.00
+    let raw = numeric_raw(0, 0x0000, 2, &[]);
+    assert_eq!(&from_sql(&raw).unwrap(), "0.00");
+    assert_eq!(i128_from_sql(&raw, 2).unwrap(), 0);
+}
+
+#[test]
+fn test_numeric_small() {
+    // 0.00000001, the first fractional group is omitted
+    let raw = numeric_raw(-2, 0x0000, 8, &[1]);
+    assert_eq!(&from_sql(&raw).unwrap(), "0.00000001");
+    assert_eq!(i128_from_sql(&raw, 8).unwrap(), 1);
+    assert_eq!(i128_from_sql(&raw, 4).unwrap(), 0);
+    assert_eq!(i256_from_sql(&raw, 10).unwrap(), i256::from_i128(100));
+
+    // -0.05
+    let raw = numeric_raw(-1, 0x4000, 2, &[500]);
+    assert_eq!(&from_sql(&raw).unwrap(), "-0.05");
+    assert_eq!(i128_from_sql(&raw, 2).unwrap(), -5);
+}
+
+#[test]
+fn test_numeric_large() {
+    // 100000000000000000000, trailing integer groups are omitted
+    let raw = numeric_raw(5, 0x0000, 0, &[1]);
+    assert_eq!(&from_sql(&raw).unwrap(), "100000000000000000000");
+    assert_eq!(i128_from_sql(&raw, 2).unwrap(), 10i128.pow(22));
+    assert_eq!(
+        i256_from_sql(&raw, 0).unwrap(),
+        i256::from_i128(10i128.pow(20))
+    );
+
+    // -12340000.5, with explicit zero groups in the middle and at both ends
+    let raw = numeric_raw(2, 0x4000, 1, &[0, 1234, 0, 5000, 0]);
+    assert_eq!(&from_sql(&raw).unwrap(), "-12340000.5");
+    assert_eq!(i128_from_sql(&raw, 1).unwrap(), -123400005);
+    assert_eq!(
+        i256_from_sql(&raw, 3).unwrap(),
+        i256::from_i128(-12340000500)
+    );
+
+    // out of range of i128, but fits into i256
+    let raw = numeric_raw(10, 0x0000, 0, &[1]);
+    assert!(i128_from_sql(&raw, 0).is_err());
+    assert_eq!(
+        i256_from_sql(&raw, 0).unwrap(),
+        i256::from_string(&format!("1{}", "0".repeat(40))).unwrap()
+    );
+}
+
+#[test]
+fn test_numeric_special() {
+    let nan = numeric_raw(0, 0xC000, 0, &[]);
+    assert_eq!(&from_sql(&nan).unwrap(), "NaN");
+    assert!(i128_from_sql(&nan, 2).is_err());
+    assert!(i256_from_sql(&nan, 2).is_err());
+
+    let infinity = numeric_raw(0, 0xD000, 0, &[]);
+    assert_eq!(&from_sql(&infinity).unwrap(), "Infinity");
+    assert!(i128_from_sql(&infinity, 2).is_err());
+
+    // invalid sign and digit
+    assert!(from_sql(&numeric_raw(0, 0x1234, 0, &[])).is_err());
+    assert!(from_sql(&numeric_raw(0, 0x0000, 0, &[10000])).is_err());
+    assert!(i128_from_sql(&numeric_raw(0, 0x0000, 0, &[10000]), 0).is_err());
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, StructArray};
+use arrow::buffer::NullBuffer;
+use arrow::datatypes::{DataType, Fields};
+use arrow::error::ArrowError;
+use serde_json::Value;
+
+/// Parses JSON text into a struct array of the given fields.
+///
+/// Each field is taken from the top-level key of the same name and then cast
+/// to the type of the field. Missing keys, JSON nulls and values that
+/// cannot be cast are null. Values that are not JSON objects are null structs.
+pub fn json_into_struct(array: &ArrayRef, fields: &Fields) -> Result<ArrayRef, ArrowError> {
+    let array = arrow::compute::cast(array, &DataType::Utf8)?;
+    let texts = array.as_string::<i32>();
+
+    let mut objects = Vec::with_capacity(texts.len());
+    for text in texts.iter() {
+        let object = match text.map(serde_json::from_str::<Value>).transpose() {
+            Ok(Some(Value::Object(object))) => Some(object),
+            Ok(_) => None,
+            Err(e) => return Err(ArrowError::JsonError(e.to_string())),
+        };
+        objects.push(object);
+    }
+
+    let mut columns = Vec::with_capacity(fields.len());
+    for field in fields {
+        let values = objects.iter().map(|object| {
+            let value = object.as_ref()?.get(field.name())?;
+            match value {
+                Value::Null => None,
+                Value::String(s) => Some(s.clone()),
+                v => Some(v.to_string()),
+            }
+        });
+        let values: ArrayRef = Arc::new(values.collect::<StringArray>());
+
+        let column = match field.data_type() {
+            DataType::Struct(fields) => json_into_struct(&values, fields)?,
+            ty => arrow::compute::cast(&values, ty)?,
+        };
+        columns.push(column);
+    }
+
+    let nulls = NullBuffer::from(objects.iter().map(Option::is_some).collect::<Vec<_>>());
+    let array = StructArray::try_new(fields.clone(), columns, Some(nulls))?;
+    Ok(Arc::new(array))
+}
@@ -13,16 +13,31 @@
 //! ````
 
 mod append;
+mod async_client;
 mod decimal;
+mod json;
+mod params;
 mod query;
 mod schema;
+mod stmt_cache;
 mod types;
+mod typmod;
+
+pub use async_client::PostgresAsyncConnection;
+pub use types::{DatetimeEncoding, TypeMapOverride};
+
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 use arrow::datatypes::{DataType, IntervalUnit, TimeUnit};
-use postgres::Client;
+use postgres::tls::MakeTlsConnect;
+use postgres::{Client, Socket};
 use thiserror::Error;
 
-use crate::api::Connector;
+use crate::api::{
+    AppenderOptions, CancelToken, ConflictMode, Connector, QueryCost, ReadOptions, SchemaGet,
+    StatementCacheStats,
+};
 use crate::errors::ConnectorError;
 
 /// Connection to PostgreSQL that implements [Connection], [crate::api::SchemaGet] and [crate::api::SchemaEdit].
@@ -30,13 +45,150 @@ use crate::errors::ConnectorError;
 /// Requires generic argument `Protocol`, which can be one of the following types:
 /// - [ProtocolExtended]
 /// - [ProtocolSimple]
+///
+/// `money` is read as `Decimal128(19, 2)`. Its binary format is an amount in the smallest
+/// unit of the currency, which does not depend on `lc_monetary`, but it is assumed that
+/// the currency has 2 fractional digits.
+///
+/// `time(p)` is read as `Time32(Second)` when `p` is 0, as `Time32(Millisecond)` when `p`
+/// is at most 3 and as `Time64(Microsecond)` otherwise.
+///
+/// `macaddr` and `macaddr8` are read as `FixedSizeBinary(6)` and `FixedSizeBinary(8)`.
+/// `inet` and `cidr` are read as `Binary` in their binary format: address family
+/// (2 for IPv4, 3 for IPv6), netmask bits, is-cidr flag, address length and the address.
+/// See [PostgresConnection::with_network_addr_text] for reading them as text.
+///
+/// `bit(1)` is read as `Boolean`, other `bit(n)` and `varbit` as `Binary` with bits packed
+/// into bytes, which loses the number of bits.
+/// See [PostgresConnection::with_bit_list] for reading them as lists of booleans.
 pub struct PostgresConnection {
     client: Client,
+    options: types::TypeOptions,
+    statements: stmt_cache::StatementCache,
+
+    /// Set while a transaction started by [Connector::transaction] is open.
+    in_transaction: bool,
+
+    /// Determined by the first query that returns date-time values.
+    datetime_encoding: Option<DatetimeEncoding>,
+
+    /// Sends cancel requests of [Connector::cancel_token], with the TLS of [Self::with_cancel_tls].
+    cancel_query: Option<Arc<CancelQuery>>,
 }
 
+type CancelQuery = dyn Fn(&postgres::CancelToken) -> Result<(), postgres::Error> + Send + Sync;
+
 impl PostgresConnection {
     pub fn new(client: Client) -> Self {
-        PostgresConnection { client }
+        PostgresConnection {
+            client,
+            options: types::TypeOptions::default(),
+            statements: stmt_cache::StatementCache::default(),
+            in_transaction: false,
+            datetime_encoding: None,
+            cancel_query: None,
+        }
+    }
+
+    /// Read `timestamptz` as `Timestamp(Microsecond, None)` instead of
+    /// `Timestamp(Microsecond, Some("+00:00"))`.
+    /// Values are still UTC instants, only the timezone annotation is omitted.
+    pub fn with_timestamptz_naive(mut self, naive: bool) -> Self {
+        self.options.timestamptz_naive = naive;
+        self
+    }
+
+    /// Read `numeric` columns with declared precision and scale as `Decimal128(p, s)`,
+    /// when they fit into 38 digits, or as `Decimal256(p, s)` when they fit into 76 digits.
+    /// Other `numeric` columns are still read as `Utf8`.
+    pub fn with_numeric_decimal(mut self, enabled: bool) -> Self {
+        self.options.numeric_decimal = enabled;
+        self
+    }
+
+    /// Read text types (`text`, `varchar`, ...) as `Utf8View` and `bytea` as `BinaryView`,
+    /// instead of `Utf8` and `Binary`.
+    pub fn with_view_types(mut self, enabled: bool) -> Self {
+        self.options.view_types = enabled;
+        self
+    }
+
+    /// Read `inet`, `cidr`, `macaddr` and `macaddr8` as `Utf8`, formatted the same
+    /// as PostgreSQL formats them (i.e. `192.168.0.1/24` or `08:00:2b:01:02:03`),
+    /// instead of binary.
+    pub fn with_network_addr_text(mut self, enabled: bool) -> Self {
+        self.options.network_addr_text = enabled;
+        self
+    }
+
+    /// Read `bit(n)` and `varbit` as `List(Boolean)`, with one element for each bit,
+    /// instead of packed into `Binary`. `bit(1)` is still read as `Boolean`.
+    pub fn with_bit_list(mut self, enabled: bool) -> Self {
+        self.options.bit_list = enabled;
+        self
+    }
+
+    /// Send cancel requests of [Connector::cancel_token] over TLS.
+    ///
+    /// Cancel requests are sent over a new connection, which does not use TLS by default,
+    /// so servers that accept only TLS connections reject them.
+    pub fn with_cancel_tls<T>(mut self, tls: T) -> Self
+    where
+        T: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    {
+        self.cancel_query = Some(Arc::new(move |token| token.cancel_query(tls.clone())));
+        self
+    }
+
+    /// Create columns of type `Timestamp(Microsecond, Some(_))` as `timestamptz`,
+    /// instead of `bigint`. Values are UTC instants, so they keep their meaning
+    /// regardless of the time zone annotation, which is not stored.
+    ///
+    /// Appending into a `timestamptz` column converts microseconds since 1970
+    /// into microseconds since 2000, which PostgreSQL uses.
+    pub fn with_timestamptz_append(mut self, enabled: bool) -> Self {
+        self.options.timestamptz_append = enabled;
+        self
+    }
+
+    /// Skip UTF-8 validation when reading text types (`text`, `varchar`, `bpchar`, ...).
+    ///
+    /// This avoids re-validating text that the server has already encoded as UTF-8,
+    /// which can be measurable for text-heavy queries.
+    ///
+    /// # Safety
+    ///
+    /// The server must send only valid UTF-8 text. This holds when the database
+    /// encoding is `UTF8` or when the server performs the conversion to client
+    /// encoding (which the `postgres` crate sets to `UTF8`).
+    /// Invalid UTF-8 in a [String] is undefined behavior.
+    pub unsafe fn with_utf8_unchecked(mut self, unchecked: bool) -> Self {
+        self.options.utf8_unchecked = unchecked;
+        self
+    }
+
+    /// Override the mapping between PostgreSQL and Arrow types for this connection.
+    ///
+    /// The override is consulted before the default mapping when reading query results
+    /// and table schemas, and when creating tables.
+    pub fn set_type_override(&mut self, type_override: impl TypeMapOverride + 'static) {
+        self.options.type_override = Some(std::sync::Arc::new(type_override));
+    }
+
+    /// Binary format of date-time values, which is determined with `SHOW integer_datetimes`.
+    /// The setting is read once and then cached for the lifetime of the connection.
+    pub fn datetime_encoding(&mut self) -> Result<DatetimeEncoding, ConnectorError> {
+        if let Some(encoding) = self.datetime_encoding {
+            return Ok(encoding);
+        }
+        let row = self
+            .client
+            .query_one("SHOW integer_datetimes", &[])
+            .map_err(PostgresError::Postgres)?;
+        let setting: String = row.try_get(0).map_err(PostgresError::Postgres)?;
+        let encoding = DatetimeEncoding::from_setting(&setting);
+        self.datetime_encoding = Some(encoding);
+        Ok(encoding)
     }
 
     pub fn unwrap(self) -> Client {
@@ -67,6 +219,9 @@ pub enum PostgresError {
 
     #[error(transparent)]
     IO(#[from] std::io::Error),
+
+    #[error(transparent)]
+    FromSql(Box<dyn std::error::Error + Sync + Send>),
 }
 
 impl Connector for PostgresConnection {
@@ -81,18 +236,183 @@ impl Connector for PostgresConnection {
         Self: 'conn;
 
     fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
-        let stmt = self
+        if let Some((stmt, param_names)) = self.statements.get(query) {
+            return Ok(query::PostgresStatement {
+                client: &mut self.client,
+                options: &self.options,
+                stmt,
+                param_names,
+                batch_size: 1024,
+                portal_fetch_size: None,
+                in_transaction: self.in_transaction,
+            });
+        }
+        let original_query = query;
+
+        let (query, param_names) = params::rewrite_named_params(query);
+        let query = query.as_str();
+
+        let mut stmt = self
             .client
             .prepare(query)
             .map_err(PostgresError::Postgres)?;
+
+        // some types cannot be received in binary format, so we cast them to text
+        if let Some(query) = types::cast_text_only_columns(query, &stmt) {
+            stmt = self
+                .client
+                .prepare(&query)
+                .map_err(|_| ConnectorError::NotSupported {
+                    connector_name: "connector_arrow::postgres",
+                    feature: "querying types that are received as text (aclitem, gtsvector, reg*) outside of SELECT",
+                })?;
+        }
+        if types::pg_stmt_uses_datetimes(&stmt)
+            && self.datetime_encoding()? == DatetimeEncoding::Float
+        {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::postgres",
+                feature: "date-time values of servers with integer_datetimes = off",
+            });
+        }
+        self.statements.insert(original_query, &stmt, &param_names);
         Ok(query::PostgresStatement {
             client: &mut self.client,
+            options: &self.options,
             stmt,
+            param_names,
+            batch_size: 1024,
+            portal_fetch_size: None,
+            in_transaction: self.in_transaction,
         })
     }
 
+    /// `prefetch_rows` sets [query::PostgresStatement::with_portal_fetch_size].
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        let mut stmt = self.query(query)?;
+        if let Some(batch_size) = options.batch_size {
+            stmt = stmt.with_batch_size(batch_size)?;
+        }
+        if let Some(prefetch_rows) = options.prefetch_rows {
+            let fetch_size =
+                u32::try_from(prefetch_rows).map_err(|_| ConnectorError::DataOutOfRange)?;
+            stmt = stmt.with_portal_fetch_size(fetch_size)?;
+        }
+        Ok(stmt)
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let affected = self
+            .client
+            .execute(query, &[])
+            .map_err(PostgresError::Postgres)?;
+        Ok(affected)
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<arrow::datatypes::SchemaRef, ConnectorError> {
+        // prepared statement contains description of the result
+        let stmt = self.query(query)?;
+        let schema = types::pg_stmt_to_arrow(&stmt.stmt, stmt.options)?;
+        Ok(types::pg_stmt_override(&stmt.stmt, &schema, stmt.options).unwrap_or(schema))
+    }
+
     fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
-        append::PostgresAppender::new(&mut self.client, table_name)
+        append::PostgresAppender::new(&mut self.client, table_name, self.in_transaction)
+    }
+
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        if options.null_as_default {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::postgres",
+                feature: "null_as_default",
+            });
+        }
+        if options.conflict == ConflictMode::Error {
+            return self.append(table_name);
+        }
+
+        let schema = self.table_get(table_name)?;
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        options.conflict.validate(table_name, &columns)?;
+
+        append::PostgresAppender::new_upsert(
+            &mut self.client,
+            table_name,
+            &columns,
+            &options.conflict,
+            self.in_transaction,
+        )
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.client
+            .batch_execute("BEGIN")
+            .map_err(PostgresError::Postgres)?;
+        self.in_transaction = true;
+        Ok(())
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.in_transaction = false;
+        self.client
+            .batch_execute(if commit { "COMMIT" } else { "ROLLBACK" })
+            .map_err(PostgresError::Postgres)?;
+        Ok(())
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError> {
+        schema::estimate_row_count(self, table_name)
+    }
+
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        schema::explain_cost(self, query)
+    }
+
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &arrow::record_batch::RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        crate::dml::merge(self, table_name, key_columns, batch, |i| format!("${i}"))
+    }
+
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        self.statements.set_capacity(size);
+        Ok(())
+    }
+
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        Some(self.statements.stats())
+    }
+
+    /// Cancellation request is sent over a new connection, without TLS.
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        let token = self.client.cancel_token();
+        let cancel_query = self.cancel_query.clone();
+        Ok(CancelToken::new(move || {
+            let res = match &cancel_query {
+                Some(cancel_query) => cancel_query(&token),
+                None => token.cancel_query(postgres::NoTls),
+            };
+            res.map_err(|e| PostgresError::Postgres(e).into())
+        }))
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        schema::list_catalogs(self)
     }
 
     fn type_db_into_arrow(ty: &str) -> Option<DataType> {
@@ -104,6 +424,7 @@ impl Connector for PostgresConnection {
             "real" | "float4" => DataType::Float32,
             "double precision" | "float8" => DataType::Float64,
             "numeric" | "decimal" => DataType::Utf8,
+            "money" => DataType::Decimal128(19, 2),
 
             "timestamp" | "timestamp without time zone" => {
                 DataType::Timestamp(TimeUnit::Microsecond, None)
@@ -115,10 +436,18 @@ impl Connector for PostgresConnection {
             "time" | "time without time zone" => DataType::Time64(TimeUnit::Microsecond),
             "interval" => DataType::Interval(IntervalUnit::MonthDayNano),
 
+            "json" | "jsonb" => DataType::Utf8,
+
             "bytea" => DataType::Binary,
+            "bit(1)" => DataType::Boolean,
             "bit" | "bit varying" | "varbit" => DataType::Binary,
+            "uuid" => DataType::FixedSizeBinary(16),
+            "macaddr" => DataType::FixedSizeBinary(6),
+            "macaddr8" => DataType::FixedSizeBinary(8),
+            "inet" | "cidr" => DataType::Binary,
 
             "text" | "varchar" | "char" | "bpchar" => DataType::Utf8,
+            _ if types::REG_TYPES.contains(&ty) => DataType::Utf8,
 
             _ if ty.starts_with("bit") => DataType::Binary,
             _ if ty.starts_with("varchar") | ty.starts_with("char") | ty.starts_with("bpchar") => {
@@ -126,6 +455,14 @@ impl Connector for PostgresConnection {
             }
             _ if ty.starts_with("decimal") | ty.starts_with("numeric") => DataType::Utf8,
 
+            // only one-dimensional arrays
+            _ if ty.ends_with("[]") && !ty.ends_with("[][]") => {
+                let item = Self::type_db_into_arrow(&ty[..ty.len() - 2])?;
+                DataType::List(std::sync::Arc::new(
+                    arrow::datatypes::Field::new_list_field(item, true),
+                ))
+            }
+
             _ => return None,
         })
     }
@@ -162,7 +499,8 @@ impl Connector for PostgresConnection {
                 DataType::Time32(_) => "integer",
                 DataType::Time64(_) => "bigint",
                 DataType::Duration(_) => "bigint",
-                DataType::Interval(_) => return None,
+                DataType::Interval(IntervalUnit::YearMonth | IntervalUnit::DayTime) => "interval",
+                DataType::Interval(IntervalUnit::MonthDayNano) => return None,
 
                 DataType::Utf8 | DataType::LargeUtf8 => "text",
 
@@ -172,9 +510,19 @@ impl Connector for PostgresConnection {
                     return Some(format!("decimal({precision}, {scale})"))
                 }
 
-                DataType::List(_)
-                | DataType::FixedSizeList(_, _)
-                | DataType::LargeList(_)
+                DataType::List(field) | DataType::LargeList(field) => {
+                    // only one-dimensional arrays
+                    if matches!(
+                        field.data_type(),
+                        DataType::List(_) | DataType::LargeList(_)
+                    ) {
+                        return None;
+                    }
+                    let element = Self::type_arrow_into_db(field.data_type())?;
+                    return Some(format!("{element}[]"));
+                }
+
+                DataType::FixedSizeList(_, _)
                 | DataType::Struct(_)
                 | DataType::Union(_, _)
                 | DataType::Dictionary(_, _)
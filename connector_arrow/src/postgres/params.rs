@@ -0,0 +1,91 @@
+/// Rewrites named placeholders (`:name`) into positional placeholders (`$1`).
+///
+/// Repeated names are mapped to the same position.
+/// String literals, quoted identifiers, comments and type casts (`::`) are left unchanged.
+///
+/// Returns the rewritten query and the names of the parameters, ordered by position.
+pub fn rewrite_named_params(query: &str) -> (String, Vec<String>) {
+    let bytes = query.as_bytes();
+    let mut res = String::with_capacity(query.len());
+    let mut names: Vec<String> = Vec::new();
+
+    let is_ident_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+
+    let mut start = 0; // start of the segment that has not been copied yet
+    let mut i = 0;
+    while i < bytes.len() {
+        let skip_to = match bytes[i] {
+            b'\'' | b'"' => find(query, i + 1, &query[i..i + 1]),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => find(query, i + 2, "\n"),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => find(query, i + 2, "*/"),
+            b'$' => {
+                // dollar-quoted string: $tag$ ... $tag$
+                let tag_len = query[i + 1..]
+                    .bytes()
+                    .take_while(|b| is_ident_char(*b))
+                    .count();
+                let tag_end = i + 1 + tag_len;
+                let starts_with_digit = bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+                if !starts_with_digit && bytes.get(tag_end) == Some(&b'$') {
+                    find(query, tag_end + 1, &query[i..=tag_end])
+                } else {
+                    i + 1
+                }
+            }
+            b':' if bytes.get(i + 1) == Some(&b':') => i + 2,
+            b':' if i > 0 && (is_ident_char(bytes[i - 1]) || bytes[i - 1] == b':') => i + 1,
+            b':' if bytes
+                .get(i + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_') =>
+            {
+                let name_len = query[i + 1..]
+                    .bytes()
+                    .take_while(|b| is_ident_char(*b))
+                    .count();
+                let name = &query[i + 1..i + 1 + name_len];
+
+                let position = match names.iter().position(|n| n == name) {
+                    Some(p) => p,
+                    None => {
+                        names.push(name.to_string());
+                        names.len() - 1
+                    }
+                };
+
+                res += &query[start..i];
+                res += &format!("${}", position + 1);
+                start = i + 1 + name_len;
+                start
+            }
+            _ => i + 1,
+        };
+        i = skip_to;
+    }
+    res += &query[start..];
+
+    (res, names)
+}
+
+/// Returns position after the first occurrence of `needle`, starting at `from`.
+/// If there is no occurrence, returns length of the haystack.
+fn find(haystack: &str, from: usize, needle: &str) -> usize {
+    haystack[from..]
+        .find(needle)
+        .map_or(haystack.len(), |p| from + p + needle.len())
+}
+
+#[test]
+fn test_rewrite_named_params_01() {
+    let (query, names) = rewrite_named_params("SELECT :a + :b, :a::int, CAST(:c AS text)");
+    assert_eq!(query, "SELECT $1 + $2, $1::int, CAST($3 AS text)");
+    assert_eq!(names, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_rewrite_named_params_02() {
+    let query = r#"SELECT ':a', ":a", $$ :a $$, $t$ :a $t$, $1, x::text, arr[1:2] -- :a
+        /* :a */"#;
+    let (res, names) = rewrite_named_params(query);
+    assert_eq!(res, query);
+    assert!(names.is_empty());
+}
@@ -1,21 +1,61 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, StructArray};
+use arrow::buffer::NullBuffer;
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 
 use postgres::fallible_iterator::FallibleIterator;
-use postgres::types::{FromSql, Type};
-use postgres::{Client, Row, RowIter};
+use postgres::types::{FromSql, Kind, Type};
+use postgres::{Client, Portal, Row, RowIter, Transaction};
 
-use crate::api::{ResultReader, Statement};
-use crate::types::{ArrowType, FixedSizeBinaryType};
-use crate::util::CellReader;
-use crate::util::{transport, ArrayCellRef};
+use crate::api::{ArrowValue, ResultReader, Statement};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType};
+use crate::util::{collect_rows_to_arrow, CellReader};
+use crate::util::{transport, ArrayCellRef, ArrowReader, ArrowRowWriter};
 use crate::{errors::ConnectorError, util::RowsReader};
 
+use super::typmod::parse_typmod;
 use super::{types, PostgresError};
 
 pub struct PostgresStatement<'conn> {
     pub(super) client: &'conn mut Client,
+    pub(super) options: &'conn types::TypeOptions,
     pub(super) stmt: postgres::Statement,
+    pub(super) param_names: Vec<String>,
+    pub(super) batch_size: usize,
+    pub(super) portal_fetch_size: Option<i32>,
+    pub(super) in_transaction: bool,
+}
+
+impl PostgresStatement<'_> {
+    /// Set the number of rows in each of the produced record batches.
+    /// The last batch may contain fewer rows. Defaults to 1024.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Result<Self, ConnectorError> {
+        if batch_size == 0 {
+            return Err(ConnectorError::InvalidBatchSize);
+        }
+        self.batch_size = batch_size;
+        Ok(self)
+    }
+
+    /// Fetch results from the server in chunks of `fetch_size` rows,
+    /// independently of the size of produced record batches.
+    ///
+    /// Results are read from a portal, which requires a transaction.
+    /// It is committed after all rows are read, or rolled back when the reader
+    /// is dropped before that. By default, all rows are requested at once.
+    ///
+    /// Not supported within [crate::api::Connector::transaction].
+    pub fn with_portal_fetch_size(mut self, fetch_size: u32) -> Result<Self, ConnectorError> {
+        let fetch_size = i32::try_from(fetch_size).map_err(|_| ConnectorError::DataOutOfRange)?;
+        if fetch_size == 0 {
+            return Err(ConnectorError::InvalidBatchSize);
+        }
+        self.portal_fetch_size = Some(fetch_size);
+        Ok(self)
+    }
 }
 
 impl<'conn> Statement<'conn> for PostgresStatement<'conn> {
@@ -24,30 +64,76 @@ impl<'conn> Statement<'conn> for PostgresStatement<'conn> {
     where
         Self: 'stmt;
 
+    fn start_named(
+        &mut self,
+        params: &[(&str, &dyn ArrowValue)],
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        let batch = crate::params::named_to_record_batch(&self.param_names, params)?;
+        self.start_batch((&batch, 0))
+    }
+
     fn start_batch<'p>(
         &mut self,
         args: (&RecordBatch, usize),
     ) -> Result<Self::Reader<'_>, ConnectorError> {
         let stmt = &self.stmt;
-        let schema = types::pg_stmt_to_arrow(stmt)?;
+        let row_schema = types::pg_stmt_to_arrow(stmt, self.options)?;
+        let schema = types::pg_stmt_override(stmt, &row_schema, self.options);
 
         let arg_row = ArrayCellRef::vec_from_batch(args.0, args.1);
 
         // query
-        let rows = self
-            .client
-            .query_raw::<_, _, _>(stmt, &arg_row)
-            .map_err(PostgresError::from)?;
+        let rows = if let Some(fetch_size) = self.portal_fetch_size {
+            if self.in_transaction {
+                return Err(ConnectorError::NotSupported {
+                    connector_name: "connector_arrow::postgres",
+                    feature: "portal_fetch_size within a transaction",
+                });
+            }
+            let mut transaction = self.client.transaction().map_err(PostgresError::from)?;
+            let params = arg_row.iter().map(|c| c as _).collect::<Vec<_>>();
+            let portal = transaction
+                .bind(stmt, &params)
+                .map_err(PostgresError::from)?;
+            RowSource::Portal {
+                transaction: Some(transaction),
+                portal,
+                fetch_size,
+                fetched: Vec::new().into_iter(),
+            }
+        } else {
+            let rows = self
+                .client
+                .query_raw::<_, _, _>(stmt, &arg_row)
+                .map_err(PostgresError::from)?;
+            RowSource::Iter(rows.fuse())
+        };
 
         // create the row reader
-        let row_reader = PostgresRowStream::new(rows);
-        Ok(PostgresBatchStream { schema, row_reader })
+        let row_reader = PostgresRowStream {
+            rows,
+            options: self.options,
+        };
+        Ok(PostgresBatchStream {
+            schema: schema.unwrap_or_else(|| row_schema.clone()),
+            row_schema,
+            row_reader,
+            batch_size: self.batch_size,
+            rows_read: 0,
+        })
     }
 }
 
 pub struct PostgresBatchStream<'a> {
     schema: SchemaRef,
+
+    /// Schema that rows are read into, before applying [types::TypeMapOverride].
+    row_schema: SchemaRef,
     row_reader: PostgresRowStream<'a>,
+    batch_size: usize,
+
+    /// Number of rows in previous batches.
+    rows_read: usize,
 }
 
 impl<'a> ResultReader<'a> for PostgresBatchStream<'a> {
@@ -60,39 +146,146 @@ impl Iterator for PostgresBatchStream<'_> {
     type Item = Result<RecordBatch, ConnectorError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        crate::util::next_batch_from_rows(&self.schema, &mut self.row_reader, 1024).transpose()
+        let batch = crate::util::next_batch_from_rows(
+            &self.row_schema,
+            &mut self.row_reader,
+            self.batch_size,
+        )
+        .transpose()?;
+        let batch = match batch {
+            Ok(batch) => {
+                self.rows_read += batch.num_rows();
+                Ok(batch)
+            }
+            Err(ConnectorError::DataOutOfRangeAt { column, row }) => {
+                Err(ConnectorError::DataOutOfRangeAt {
+                    column,
+                    row: self.rows_read + row,
+                })
+            }
+            Err(e) => Err(e),
+        };
+        if std::sync::Arc::ptr_eq(&self.schema, &self.row_schema) {
+            return Some(batch);
+        }
+        Some(batch.and_then(|b| types::cast_to_schema(b, &self.schema)))
     }
 }
 
 struct PostgresRowStream<'a> {
-    iter: postgres_fallible_iterator::Fuse<postgres::RowIter<'a>>,
+    rows: RowSource<'a>,
+    options: &'a types::TypeOptions,
+}
+
+enum RowSource<'a> {
+    Iter(postgres_fallible_iterator::Fuse<RowIter<'a>>),
+
+    /// Rows of a portal, fetched in chunks of `fetch_size`.
+    /// Transaction is committed and taken when the portal is exhausted.
+    Portal {
+        transaction: Option<Transaction<'a>>,
+        portal: Portal,
+        fetch_size: i32,
+        fetched: std::vec::IntoIter<Row>,
+    },
 }
 
-impl<'a> PostgresRowStream<'a> {
-    pub fn new(iter: RowIter<'a>) -> Self {
-        Self { iter: iter.fuse() }
+impl RowSource<'_> {
+    fn next(&mut self) -> Result<Option<Row>, postgres::Error> {
+        match self {
+            RowSource::Iter(iter) => iter.next(),
+            RowSource::Portal {
+                transaction,
+                portal,
+                fetch_size,
+                fetched,
+            } => loop {
+                if let Some(row) = fetched.next() {
+                    return Ok(Some(row));
+                }
+                let Some(t) = transaction else {
+                    return Ok(None);
+                };
+
+                let rows = t.query_portal(portal, *fetch_size)?;
+                if rows.len() < *fetch_size as usize {
+                    transaction.take().unwrap().commit()?;
+                }
+                *fetched = rows.into_iter();
+            },
+        }
     }
 }
 
 impl<'stmt> RowsReader<'stmt> for PostgresRowStream<'stmt> {
     type CellReader<'row>
-        = PostgresCellReader
+        = PostgresCellReader<'row>
     where
         Self: 'row;
 
     fn next_row(&mut self) -> Result<Option<Self::CellReader<'_>>, ConnectorError> {
-        let row = self.iter.next().map_err(PostgresError::from)?;
+        let row = self.rows.next().map_err(PostgresError::from)?;
+
+        Ok(row.map(|row| PostgresCellReader {
+            row,
+            next_col: 0,
+            options: self.options,
+        }))
+    }
+}
 
-        Ok(row.map(|row| PostgresCellReader { row, next_col: 0 }))
+/// Converts rows that were already fetched, i.e. by [super::PostgresAsyncConnection].
+pub(super) fn rows_into_reader(
+    stmt: &postgres::Statement,
+    rows: Vec<Row>,
+    options: &types::TypeOptions,
+) -> Result<ArrowReader, ConnectorError> {
+    let row_schema = types::pg_stmt_to_arrow(stmt, options)?;
+    let schema = types::pg_stmt_override(stmt, &row_schema, options);
+
+    let mut row_reader = PostgresRowVec {
+        iter: rows.into_iter(),
+        options,
+    };
+    let batches = collect_rows_to_arrow(row_schema.clone(), &mut row_reader, 1024)?;
+
+    let Some(schema) = schema else {
+        return Ok(ArrowReader::new(row_schema, batches));
+    };
+    let batches = batches
+        .into_iter()
+        .map(|b| types::cast_to_schema(b, &schema))
+        .collect::<Result<_, _>>()?;
+    Ok(ArrowReader::new(schema, batches))
+}
+
+struct PostgresRowVec<'a> {
+    iter: std::vec::IntoIter<Row>,
+    options: &'a types::TypeOptions,
+}
+
+impl<'a> RowsReader<'a> for PostgresRowVec<'a> {
+    type CellReader<'row>
+        = PostgresCellReader<'row>
+    where
+        Self: 'row;
+
+    fn next_row(&mut self) -> Result<Option<Self::CellReader<'_>>, ConnectorError> {
+        Ok(self.iter.next().map(|row| PostgresCellReader {
+            row,
+            next_col: 0,
+            options: self.options,
+        }))
     }
 }
 
-struct PostgresCellReader {
+struct PostgresCellReader<'a> {
     row: Row,
     next_col: usize,
+    options: &'a types::TypeOptions,
 }
 
-impl CellReader<'_> for PostgresCellReader {
+impl CellReader<'_> for PostgresCellReader<'_> {
     type CellRef<'cell>
         = CellRef<'cell>
     where
@@ -104,31 +297,79 @@ impl CellReader<'_> for PostgresCellReader {
         }
         let col = self.next_col;
         self.next_col += 1;
-        Some((&self.row, col))
+        Some((&self.row, col, self.options))
     }
 }
 
-type CellRef<'a> = (&'a Row, usize);
+type CellRef<'a> = (&'a Row, usize, &'a types::TypeOptions);
+
+/// A value that can be decoded with [FromSql], either a cell of a row or an element of an array.
+trait PgValue<'c>: Copy {
+    fn get<T: FromSql<'c>>(self) -> Result<T, ConnectorError>;
+
+    fn typmod(self) -> i32;
+
+    fn options(self) -> &'c types::TypeOptions;
+}
+
+impl<'c> PgValue<'c> for CellRef<'c> {
+    fn get<T: FromSql<'c>>(self) -> Result<T, ConnectorError> {
+        Ok(self.0.try_get(self.1).map_err(PostgresError::from)?)
+    }
+
+    fn typmod(self) -> i32 {
+        self.0.columns()[self.1].type_modifier()
+    }
+
+    fn options(self) -> &'c types::TypeOptions {
+        self.2
+    }
+}
 
 impl<'c> transport::Produce<'c> for CellRef<'c> {}
 
+/// An element of a Postgres array.
+#[derive(Debug, Clone, Copy)]
+struct ElementRef<'a> {
+    ty: &'a Type,
+    typmod: i32,
+    raw: Option<&'a [u8]>,
+    options: &'a types::TypeOptions,
+}
+
+impl<'c> PgValue<'c> for ElementRef<'c> {
+    fn get<T: FromSql<'c>>(self) -> Result<T, ConnectorError> {
+        Ok(T::from_sql_nullable(self.ty, self.raw).map_err(PostgresError::FromSql)?)
+    }
+
+    fn typmod(self) -> i32 {
+        self.typmod
+    }
+
+    fn options(self) -> &'c types::TypeOptions {
+        self.options
+    }
+}
+
+impl<'c> transport::Produce<'c> for ElementRef<'c> {}
+
 macro_rules! impl_produce {
     ($t: ty, $native: ty, $conversion_fn: expr) => {
-        impl<'c> transport::ProduceTy<'c, $t> for CellRef<'c> {
+        impl<'c, V: PgValue<'c>> transport::ProduceTy<'c, $t> for V {
             fn produce(self) -> Result<<$t as ArrowType>::Native, ConnectorError> {
-                let value = self.0.get::<_, $native>(self.1);
+                let value = self.get::<$native>()?;
                 $conversion_fn(value)
             }
 
             fn produce_opt(self) -> Result<Option<<$t as ArrowType>::Native>, ConnectorError> {
-                let value = self.0.get::<_, Option<$native>>(self.1);
+                let value = self.get::<Option<$native>>()?;
                 value.map($conversion_fn).transpose()
             }
         }
     };
 }
 
-impl_produce!(BooleanType, bool, Result::Ok);
+impl_produce!(BooleanType, Bool, Bool::into_arrow);
 impl_produce!(Int8Type, i8, Result::Ok);
 impl_produce!(Int16Type, i16, Result::Ok);
 impl_produce!(Int32Type, i32, Result::Ok);
@@ -137,65 +378,374 @@ impl_produce!(Float32Type, f32, Result::Ok);
 impl_produce!(Float64Type, f64, Result::Ok);
 impl_produce!(BinaryType, Binary, Binary::into_arrow);
 impl_produce!(LargeBinaryType, Binary, Binary::into_arrow);
-impl_produce!(Utf8Type, StrOrNum, StrOrNum::into_arrow);
+impl_produce!(FixedSizeBinaryType, Binary, Binary::into_arrow);
+impl_produce!(BinaryViewType, Binary, Binary::into_arrow);
 impl_produce!(LargeUtf8Type, String, Result::Ok);
 impl_produce!(
     TimestampMicrosecondType,
     TimestampY2000,
     TimestampY2000::into_microsecond
 );
+impl_produce!(Time32SecondType, Time64, Time64::into_second);
+impl_produce!(Time32MillisecondType, Time64, Time64::into_millisecond);
 impl_produce!(Time64MicrosecondType, Time64, Time64::into_microsecond);
 impl_produce!(Date32Type, DaysSinceY2000, DaysSinceY2000::into_date32);
+impl_produce!(Date64Type, DaysSinceY2000, DaysSinceY2000::into_date64);
 impl_produce!(
     IntervalMonthDayNanoType,
     IntervalMonthDayMicros,
     IntervalMonthDayMicros::into_arrow
 );
-
-crate::impl_produce_unsupported!(
-    CellRef<'r>,
-    (
-        UInt8Type,
-        UInt16Type,
-        UInt32Type,
-        UInt64Type,
-        Float16Type,
-        TimestampSecondType,
-        TimestampMillisecondType,
-        TimestampNanosecondType,
-        Date64Type,
-        Time32SecondType,
-        Time32MillisecondType,
-        Time64NanosecondType,
-        IntervalYearMonthType,
-        IntervalDayTimeType,
-        DurationSecondType,
-        DurationMillisecondType,
-        DurationMicrosecondType,
-        DurationNanosecondType,
-        FixedSizeBinaryType,
-        Decimal128Type,
-        Decimal256Type,
-    )
+impl_produce!(
+    DurationSecondType,
+    IntervalMonthDayMicros,
+    IntervalMonthDayMicros::into_second
 );
+impl_produce!(
+    DurationMillisecondType,
+    IntervalMonthDayMicros,
+    IntervalMonthDayMicros::into_millisecond
+);
+impl_produce!(
+    DurationMicrosecondType,
+    IntervalMonthDayMicros,
+    IntervalMonthDayMicros::into_microsecond
+);
+impl_produce!(
+    DurationNanosecondType,
+    IntervalMonthDayMicros,
+    IntervalMonthDayMicros::into_nanosecond
+);
+
+macro_rules! impl_produce_unsupported {
+    ($($p: ty),+) => {
+        $(
+            crate::impl_produce_unsupported!(
+                $p,
+                (
+                    UInt8Type,
+                    UInt16Type,
+                    UInt32Type,
+                    UInt64Type,
+                    Float16Type,
+                    TimestampSecondType,
+                    TimestampMillisecondType,
+                    TimestampNanosecondType,
+                    Time64NanosecondType,
+                    IntervalYearMonthType,
+                    IntervalDayTimeType,
+                )
+            );
+        )+
+    };
+}
+impl_produce_unsupported!(CellRef<'r>, ElementRef<'r>);
+
+impl<'c, V: PgValue<'c>> transport::ProduceTy<'c, Decimal128Type> for V {
+    fn produce(self) -> Result<i128, ConnectorError> {
+        let value = self.get::<Numeric>()?;
+        value.into_decimal128(numeric_scale(self.typmod()))
+    }
+
+    fn produce_opt(self) -> Result<Option<i128>, ConnectorError> {
+        let value = self.get::<Option<Numeric>>()?;
+        value
+            .map(|v| v.into_decimal128(numeric_scale(self.typmod())))
+            .transpose()
+    }
+}
+
+impl<'c, V: PgValue<'c>> transport::ProduceTy<'c, Decimal256Type> for V {
+    fn produce(self) -> Result<i256, ConnectorError> {
+        let value = self.get::<Numeric>()?;
+        value.into_decimal256(numeric_scale(self.typmod()))
+    }
+
+    fn produce_opt(self) -> Result<Option<i256>, ConnectorError> {
+        let value = self.get::<Option<Numeric>>()?;
+        value
+            .map(|v| v.into_decimal256(numeric_scale(self.typmod())))
+            .transpose()
+    }
+}
+
+impl<'c, V: PgValue<'c>> transport::ProduceTy<'c, StringViewType> for V {
+    fn produce(self) -> Result<String, ConnectorError> {
+        transport::ProduceTy::<Utf8Type>::produce(self)
+    }
+
+    fn produce_opt(self) -> Result<Option<String>, ConnectorError> {
+        transport::ProduceTy::<Utf8Type>::produce_opt(self)
+    }
+}
+
+fn numeric_scale(typmod: i32) -> i8 {
+    // money has no typmod, so its scale is always 0
+    parse_typmod(&Type::NUMERIC, typmod).scale.unwrap_or(0)
+}
+
+impl<'c, V: PgValue<'c>> transport::ProduceTy<'c, Utf8Type> for V {
+    fn produce(self) -> Result<String, ConnectorError> {
+        if self.options().utf8_unchecked {
+            self.get::<StrOrNum<false>>()?.into_arrow()
+        } else {
+            self.get::<StrOrNum<true>>()?.into_arrow()
+        }
+    }
+
+    fn produce_opt(self) -> Result<Option<String>, ConnectorError> {
+        if self.options().utf8_unchecked {
+            let value = self.get::<Option<StrOrNum<false>>>()?;
+            value.map(StrOrNum::into_arrow).transpose()
+        } else {
+            let value = self.get::<Option<StrOrNum<true>>>()?;
+            value.map(StrOrNum::into_arrow).transpose()
+        }
+    }
+}
+
+impl<'c> transport::ProduceTy<'c, ListType> for CellRef<'c> {
+    fn produce(self) -> Result<ArrayRef, ConnectorError> {
+        if is_bit_string(self) {
+            return self.get::<Bits>()?.into_arrow();
+        }
+        let value = self.get::<PgArray>()?;
+        value.into_arrow(self)
+    }
+
+    fn produce_opt(self) -> Result<Option<ArrayRef>, ConnectorError> {
+        if is_bit_string(self) {
+            let value = self.get::<Option<Bits>>()?;
+            return value.map(Bits::into_arrow).transpose();
+        }
+        let value = self.get::<Option<PgArray>>()?;
+        value.map(|v| v.into_arrow(self)).transpose()
+    }
+}
+
+/// `bit` and `varbit` are read as lists when [types::TypeOptions::bit_list] is set.
+fn is_bit_string(cell: CellRef) -> bool {
+    matches!(cell.0.columns()[cell.1].type_(), &Type::BIT | &Type::VARBIT)
+}
+
+/// Bits of a `bit(n)` or `varbit`, most significant bit of each byte first.
+struct Bits(BooleanArray);
+
+impl<'a> FromSql<'a> for Bits {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let varbit = postgres_protocol::types::varbit_from_sql(raw)?;
+        let bytes = varbit.bytes();
+        let bits = (0..varbit.len()).map(|i| Some(bytes[i / 8] & (0x80 >> (i % 8)) != 0));
+        Ok(Bits(bits.collect::<BooleanArray>()))
+    }
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, &Type::BIT | &Type::VARBIT)
+    }
+}
+
+impl Bits {
+    fn into_arrow(self) -> Result<ArrayRef, ConnectorError> {
+        Ok(Arc::new(self.0))
+    }
+}
+
+// Postgres does not have arrays of arrays, only multidimensional arrays
+crate::impl_produce_unsupported!(ElementRef<'r>, (ListType,));
+
+struct PgArray<'a> {
+    element_ty: Type,
+    array: postgres_protocol::types::Array<'a>,
+}
+
+impl<'a> FromSql<'a> for PgArray<'a> {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let Kind::Array(element_ty) = ty.kind() else {
+            unreachable!()
+        };
+        Ok(PgArray {
+            element_ty: element_ty.clone(),
+            array: postgres_protocol::types::array_from_sql(raw)?,
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Array(_))
+    }
+}
+
+impl PgArray<'_> {
+    /// Decodes elements of the array, using the same type mapping as for columns.
+    fn into_arrow(self, cell: CellRef) -> Result<ArrayRef, ConnectorError> {
+        let dimensions: Vec<_> = self
+            .array
+            .dimensions()
+            .collect()
+            .map_err(PostgresError::FromSql)?;
+        if dimensions.len() > 1 {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::postgres",
+                feature: "multidimensional arrays",
+            });
+        }
+        let len = dimensions.first().map_or(0, |d| d.len as usize);
+
+        let typmod = cell.typmod();
+        let field = types::pg_array_item_field(&self.element_ty, typmod, cell.2);
+        if let DataType::Struct(fields) = field.data_type() {
+            return self.into_arrow_composites(fields, len, cell.2);
+        }
+        let schema = Arc::new(Schema::new(vec![field.clone()]));
+
+        let mut writer = ArrowRowWriter::new(schema, len);
+        let mut values = self.array.values();
+        while let Some(raw) = values.next().map_err(PostgresError::FromSql)? {
+            let element = ElementRef {
+                ty: &self.element_ty,
+                typmod,
+                raw,
+                options: cell.2,
+            };
+            writer.prepare_for_batch(1)?;
+            transport::transport(&field, element, &mut writer)?;
+        }
+
+        let batches = writer.finish()?;
+        Ok(match batches.into_iter().next() {
+            Some(batch) => batch.column(0).clone(),
+            None => arrow::array::new_empty_array(field.data_type()),
+        })
+    }
+
+    /// Decodes elements of an array of a composite type into a [StructArray].
+    fn into_arrow_composites(
+        self,
+        fields: &Fields,
+        len: usize,
+        options: &types::TypeOptions,
+    ) -> Result<ArrayRef, ConnectorError> {
+        let Kind::Composite(attributes) = self.element_ty.kind() else {
+            unreachable!()
+        };
+
+        let schema = Arc::new(Schema::new(fields.clone()));
+        let mut writer = ArrowRowWriter::new(schema, len);
+        let mut validity = Vec::with_capacity(len);
 
-struct StrOrNum(String);
+        let mut values = self.array.values();
+        while let Some(raw) = values.next().map_err(PostgresError::FromSql)? {
+            validity.push(raw.is_some());
+
+            let raw_attrs = match raw {
+                Some(raw) => composite_from_sql(raw, attributes.len())?,
+                None => vec![None; attributes.len()],
+            };
+
+            writer.prepare_for_batch(1)?;
+            for ((field, attribute), raw) in fields.iter().zip(attributes).zip(raw_attrs) {
+                let element = ElementRef {
+                    ty: attribute.type_(),
+                    typmod: -1,
+                    raw,
+                    options,
+                };
+                transport::transport(field, element, &mut writer)?;
+            }
+        }
+
+        let columns = match writer.finish()?.into_iter().next() {
+            Some(batch) => batch.columns().to_vec(),
+            None => {
+                return Ok(arrow::array::new_empty_array(&DataType::Struct(
+                    fields.clone(),
+                )))
+            }
+        };
+        let nulls = Some(NullBuffer::from(validity));
+        let array = StructArray::try_new(fields.clone(), columns, nulls)?;
+        Ok(Arc::new(array))
+    }
+}
+
+/// Splits binary representation of a composite value (a record) into raw attribute values.
+fn composite_from_sql(
+    mut raw: &[u8],
+    attribute_count: usize,
+) -> Result<Vec<Option<&[u8]>>, ConnectorError> {
+    fn read_i32(raw: &mut &[u8]) -> Result<i32, ConnectorError> {
+        let Some((bytes, rest)) = raw.split_first_chunk::<4>() else {
+            return Err(invalid_composite());
+        };
+        *raw = rest;
+        Ok(i32::from_be_bytes(*bytes))
+    }
+    fn invalid_composite() -> ConnectorError {
+        ConnectorError::from(PostgresError::FromSql("invalid composite value".into()))
+    }
 
-impl StrOrNum {
+    let count = read_i32(&mut raw)?;
+    if count as usize != attribute_count {
+        return Err(invalid_composite());
+    }
+
+    let mut attrs = Vec::with_capacity(attribute_count);
+    for _ in 0..attribute_count {
+        let _oid = read_i32(&mut raw)?;
+        let len = read_i32(&mut raw)?;
+        if len < 0 {
+            attrs.push(None);
+            continue;
+        }
+        let len = len as usize;
+        if raw.len() < len {
+            return Err(invalid_composite());
+        }
+        let (value, rest) = raw.split_at(len);
+        attrs.push(Some(value));
+        raw = rest;
+    }
+    Ok(attrs)
+}
+
+/// Text of text types, or numeric and network address types formatted as text.
+///
+/// `CHECKED` is false only when enabled with [super::PostgresConnection::with_utf8_unchecked],
+/// which skips UTF-8 validation of text.
+struct StrOrNum<const CHECKED: bool>(String);
+
+impl<const CHECKED: bool> StrOrNum<CHECKED> {
     fn into_arrow(self) -> Result<String, ConnectorError> {
         Ok(self.0)
     }
 }
 
-impl<'a> FromSql<'a> for StrOrNum {
+impl<'a, const CHECKED: bool> FromSql<'a> for StrOrNum<CHECKED> {
     fn from_sql(
         ty: &Type,
         raw: &'a [u8],
     ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
         if matches!(ty, &Type::NUMERIC) {
             Ok(super::decimal::from_sql(raw).map(StrOrNum)?)
+        } else if let Some(text) = network_addr_text_from_sql(ty, raw)? {
+            Ok(StrOrNum(text))
         } else {
-            let slice = postgres_protocol::types::text_from_sql(raw)?;
+            let raw = if matches!(ty, &Type::JSONB) {
+                jsonb_text_from_sql(raw)?
+            } else {
+                raw
+            };
+            let slice = if CHECKED {
+                postgres_protocol::types::text_from_sql(raw)?
+            } else {
+                // SAFETY: caller of PostgresConnection::with_utf8_unchecked guarantees
+                // that the server sends valid UTF-8
+                unsafe { std::str::from_utf8_unchecked(raw) }
+            };
             Ok(StrOrNum(slice.to_string()))
         }
     }
@@ -205,9 +755,97 @@ impl<'a> FromSql<'a> for StrOrNum {
     }
 }
 
+/// Formats `inet`, `cidr`, `macaddr` and `macaddr8` values the same as PostgreSQL does.
+/// Returns `None` for other types.
+fn network_addr_text_from_sql(
+    ty: &Type,
+    raw: &[u8],
+) -> Result<Option<String>, Box<dyn std::error::Error + Sync + Send>> {
+    if matches!(ty, &Type::INET | &Type::CIDR) {
+        let inet = postgres_protocol::types::inet_from_sql(raw)?;
+        let max_netmask = if inet.addr().is_ipv4() { 32 } else { 128 };
+
+        // inet omits the netmask when it covers the whole address
+        Ok(Some(
+            if matches!(ty, &Type::INET) && inet.netmask() == max_netmask {
+                inet.addr().to_string()
+            } else {
+                format!("{}/{}", inet.addr(), inet.netmask())
+            },
+        ))
+    } else if matches!(ty, &Type::MACADDR | &Type::MACADDR8) {
+        if raw.len() != if matches!(ty, &Type::MACADDR) { 6 } else { 8 } {
+            return Err("invalid message length: macaddr size mismatch".into());
+        }
+        let octets: Vec<_> = raw.iter().map(|b| format!("{b:02x}")).collect();
+        Ok(Some(octets.join(":")))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Binary format of `jsonb` is a version byte, followed by JSON text.
+fn jsonb_text_from_sql(raw: &[u8]) -> Result<&[u8], Box<dyn std::error::Error + Sync + Send>> {
+    match raw.split_first() {
+        Some((1, text)) => Ok(text),
+        Some((version, _)) => Err(format!("unsupported jsonb version {version}").into()),
+        None => Err("invalid jsonb: empty".into()),
+    }
+}
+
+/// A value that is read as [DataType::Decimal128].
+enum Numeric<'a> {
+    Numeric(&'a [u8]),
+
+    /// Binary format of `money` is an int8 amount in the smallest currency unit.
+    /// It is read as `Decimal128(19, 2)`, which assumes 2 fractional digits.
+    Money(i64),
+}
+
+impl<'a> FromSql<'a> for Numeric<'a> {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(if *ty == Type::MONEY {
+            Numeric::Money(postgres_protocol::types::int8_from_sql(raw)?)
+        } else {
+            Numeric::Numeric(raw)
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, &Type::NUMERIC | &Type::MONEY)
+    }
+}
+
+impl Numeric<'_> {
+    /// Scale is used only for `numeric`, `money` has a fixed scale of 2.
+    fn into_decimal128(self, scale: i8) -> Result<i128, ConnectorError> {
+        match self {
+            Numeric::Numeric(raw) => super::decimal::i128_from_sql(raw, scale)
+                .map_err(|_| ConnectorError::DataOutOfRange),
+            Numeric::Money(cents) => Ok(cents as i128),
+        }
+    }
+
+    fn into_decimal256(self, scale: i8) -> Result<i256, ConnectorError> {
+        match self {
+            Numeric::Numeric(raw) => super::decimal::i256_from_sql(raw, scale)
+                .map_err(|_| ConnectorError::DataOutOfRange),
+            Numeric::Money(cents) => Ok(i256::from_i128(cents as i128)),
+        }
+    }
+}
+
 const DUR_1970_TO_2000_DAYS: i32 = 10957;
-const DUR_1970_TO_2000_SEC: i64 = DUR_1970_TO_2000_DAYS as i64 * 24 * 60 * 60;
+pub(super) const DUR_1970_TO_2000_SEC: i64 = DUR_1970_TO_2000_DAYS as i64 * 24 * 60 * 60;
 
+/// Microseconds since 2000-01-01 00:00:00.
+///
+/// Both `timestamp` and `timestamptz` use this representation in the binary format.
+/// For `timestamptz` the value is always in UTC, regardless of session `TimeZone`,
+/// which is why it is read as `Timestamp(Microsecond, Some("+00:00"))`.
 struct TimestampY2000(i64);
 
 impl<'a> FromSql<'a> for TimestampY2000 {
@@ -252,6 +890,16 @@ impl DaysSinceY2000 {
             .checked_add(DUR_1970_TO_2000_DAYS)
             .ok_or(ConnectorError::DataOutOfRange)
     }
+
+    fn into_date64(self) -> Result<i64, ConnectorError> {
+        // `infinity` and `-infinity` are the extremes of i32
+        if self.0 == i32::MAX || self.0 == i32::MIN {
+            return Err(ConnectorError::DataOutOfRange);
+        }
+        (self.0 as i64 + DUR_1970_TO_2000_DAYS as i64)
+            .checked_mul(24 * 60 * 60 * 1000)
+            .ok_or(ConnectorError::DataOutOfRange)
+    }
 }
 
 struct Time64(i64);
@@ -269,6 +917,15 @@ impl<'a> FromSql<'a> for Time64 {
 }
 
 impl Time64 {
+    // time of day always fits into i32 seconds or milliseconds
+    fn into_second(self) -> Result<i32, ConnectorError> {
+        Ok((self.0 / 1_000_000) as i32)
+    }
+
+    fn into_millisecond(self) -> Result<i32, ConnectorError> {
+        Ok((self.0 / 1_000) as i32)
+    }
+
     fn into_microsecond(self) -> Result<i64, ConnectorError> {
         Ok(self.0)
     }
@@ -308,6 +965,28 @@ impl IntervalMonthDayMicros {
             nanoseconds,
         })
     }
+
+    /// Microseconds of an interval without months and days.
+    fn into_microsecond(self) -> Result<i64, ConnectorError> {
+        if self.months != 0 || self.days != 0 {
+            return Err(ConnectorError::DataOutOfRange);
+        }
+        Ok(self.micros)
+    }
+
+    fn into_second(self) -> Result<i64, ConnectorError> {
+        Ok(self.into_microsecond()? / 1_000_000)
+    }
+
+    fn into_millisecond(self) -> Result<i64, ConnectorError> {
+        Ok(self.into_microsecond()? / 1_000)
+    }
+
+    fn into_nanosecond(self) -> Result<i64, ConnectorError> {
+        (self.into_microsecond()?)
+            .checked_mul(1000)
+            .ok_or(ConnectorError::DataOutOfRange)
+    }
 }
 
 struct Binary<'a>(&'a [u8]);
@@ -335,3 +1014,29 @@ impl Binary<'_> {
         Ok(self.0.to_vec())
     }
 }
+
+/// A `bool` or the first bit of a `bit(1)`.
+struct Bool(bool);
+
+impl<'a> FromSql<'a> for Bool {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(if matches!(ty, &Type::BIT | &Type::VARBIT) {
+            let varbit = postgres_protocol::types::varbit_from_sql(raw)?;
+            Bool(varbit.bytes().first().is_some_and(|b| b & 0x80 != 0))
+        } else {
+            Bool(bool::from_sql(ty, raw)?)
+        })
+    }
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty, &Type::BIT | &Type::VARBIT) || <bool as FromSql>::accepts(ty)
+    }
+}
+
+impl Bool {
+    fn into_arrow(self) -> Result<bool, ConnectorError> {
+        Ok(self.0)
+    }
+}
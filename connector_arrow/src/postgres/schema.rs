@@ -3,9 +3,9 @@ use std::sync::Arc;
 use arrow::datatypes::{DataType, Schema, SchemaRef};
 use itertools::Itertools;
 use postgres::error::SqlState;
-use postgres::types::Type;
+use postgres::types::{Kind, Type};
 
-use crate::api::{Connector, SchemaEdit, SchemaGet};
+use crate::api::{ForeignKey, QueryCost, SchemaEdit, SchemaGet};
 use crate::postgres::PostgresConnection;
 use crate::util::escape::escaped_ident;
 use crate::{ConnectorError, TableCreateError, TableDropError};
@@ -18,7 +18,7 @@ impl SchemaGet for super::PostgresConnection {
             SELECT relname
             FROM pg_class
             JOIN pg_namespace ON (relnamespace = pg_namespace.oid)
-            WHERE nspname = current_schema AND relkind = 'r'
+            WHERE nspname = current_schema AND relkind IN ('r', 'p')
         ";
         let rows = self.client.query(query, &[]).map_err(PostgresError::from)?;
 
@@ -31,10 +31,13 @@ impl SchemaGet for super::PostgresConnection {
         table_name: &str,
     ) -> Result<arrow::datatypes::SchemaRef, ConnectorError> {
         let query = "
-            SELECT attname, atttypid, attnotnull
+            SELECT attname, atttypid, atttypmod, attnotnull,
+                t.typname::text, t.typtype = 'e', e.oid, e.typname::text, e.typtype = 'e'
             FROM pg_attribute
             JOIN pg_class ON (attrelid = pg_class.oid)
             JOIN pg_namespace ON (relnamespace = pg_namespace.oid)
+            JOIN pg_type t ON (atttypid = t.oid)
+            LEFT JOIN pg_type e ON (t.typelem = e.oid AND t.typcategory = 'A')
             WHERE nspname = current_schema AND relname = $1 AND attnum > 0 AND atttypid > 0
             ORDER BY attnum;
         ";
@@ -46,78 +49,287 @@ impl SchemaGet for super::PostgresConnection {
             .map(|row| -> Result<_, ConnectorError> {
                 let name: String = row.get(0);
                 let typid: u32 = row.get(1);
-                let not_null: bool = row.get(2);
+                let typmod: i32 = row.get(2);
+                let not_null: bool = row.get(3);
 
-                let ty = Type::from_oid(typid).ok_or(ConnectorError::NotSupported {
-                    connector_name: "connector_arrow::postgres table_get",
-                    feature: "custom types",
-                })?;
+                let ty = Type::from_oid(typid).or_else(|| enum_type(&row)).ok_or(
+                    ConnectorError::NotSupported {
+                        connector_name: "connector_arrow::postgres table_get",
+                        feature: "custom types",
+                    },
+                )?;
 
-                Ok(super::types::pg_field_to_arrow(name, &ty, !not_null))
+                if let Some(data_type) = self.options.type_db_override(&ty) {
+                    return Ok(arrow::datatypes::Field::new(name, data_type, !not_null));
+                }
+                Ok(super::types::pg_field_to_arrow(
+                    name,
+                    &ty,
+                    typmod,
+                    !not_null,
+                    &self.options,
+                ))
             })
             .try_collect()?;
 
         Ok(Arc::new(Schema::new(fields)))
     }
+
+    fn table_primary_key(&mut self, table_name: &str) -> Result<Vec<String>, ConnectorError> {
+        let query = "
+            SELECT kcu.column_name::text
+            FROM information_schema.table_constraints tc
+            JOIN information_schema.key_column_usage kcu USING (
+                constraint_catalog, constraint_schema, constraint_name, table_name
+            )
+            WHERE tc.constraint_type = 'PRIMARY KEY'
+                AND tc.table_schema = current_schema AND tc.table_name = $1
+            ORDER BY kcu.ordinal_position
+        ";
+        let res = self.client.query(query, &[&table_name.to_string()]);
+        let rows = res.map_err(PostgresError::Postgres)?;
+
+        Ok(rows.into_iter().map(|r| r.get(0)).collect_vec())
+    }
+
+    fn table_foreign_keys(&mut self, table_name: &str) -> Result<Vec<ForeignKey>, ConnectorError> {
+        // columns of information_schema.key_column_usage cannot be matched with referenced
+        // columns of multi-column keys, so pg_constraint is used instead
+        let query = "
+            SELECT
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                ),
+                r.relname::text,
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(c.confkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                )
+            FROM pg_constraint c
+            JOIN pg_class t ON t.oid = c.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            JOIN pg_class r ON r.oid = c.confrelid
+            WHERE c.contype = 'f' AND n.nspname = current_schema AND t.relname = $1
+            ORDER BY c.conname
+        ";
+        let res = self.client.query(query, &[&table_name.to_string()]);
+        let rows = res.map_err(PostgresError::Postgres)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| ForeignKey {
+                columns: r.get(0),
+                referenced_table: r.get(1),
+                referenced_columns: r.get(2),
+            })
+            .collect_vec())
+    }
+}
+
+/// Type of an enum or an array of enums column, from a row of the `table_get` query.
+/// Labels are not read, since they are not needed for the conversion.
+fn enum_type(row: &postgres::Row) -> Option<Type> {
+    let new_enum = |oid, name| Type::new(name, oid, Kind::Enum(Vec::new()), String::new());
+
+    if row.get::<_, bool>(5) {
+        return Some(new_enum(row.get(1), row.get(4)));
+    }
+    if row.get::<_, Option<bool>>(8)? {
+        let element = new_enum(row.get(6), row.get(7));
+        return Some(Type::new(
+            row.get(4),
+            row.get(1),
+            Kind::Array(element),
+            String::new(),
+        ));
+    }
+    None
+}
+
+/// Reads the estimate from `pg_class.reltuples`, which is maintained by `VACUUM` and `ANALYZE`.
+/// Tables that were never analyzed are counted instead.
+pub(super) fn estimate_row_count(
+    conn: &mut PostgresConnection,
+    table_name: &str,
+) -> Result<u64, ConnectorError> {
+    let query = "
+        SELECT reltuples::float8
+        FROM pg_class
+        JOIN pg_namespace ON (relnamespace = pg_namespace.oid)
+        WHERE nspname = current_schema AND relname = $1
+    ";
+    let res = conn.client.query_opt(query, &[&table_name.to_string()]);
+    let row = res.map_err(PostgresError::Postgres)?;
+
+    match row.map(|r| r.get::<_, f64>(0)) {
+        // -1 means "unknown" since PostgreSQL 14, older versions report 0
+        Some(reltuples) if reltuples >= 0.0 => Ok(reltuples.round() as u64),
+        _ => crate::dml::count_rows(conn, table_name),
+    }
+}
+
+pub(super) fn explain_cost(
+    conn: &mut PostgresConnection,
+    query: &str,
+) -> Result<QueryCost, ConnectorError> {
+    let query = format!("EXPLAIN {query}");
+    let rows = conn
+        .client
+        .query(&query, &[])
+        .map_err(PostgresError::from)?;
+
+    // first line describes the root node, i.e. `Seq Scan on t  (cost=0.00..35.50 rows=2550 width=4)`
+    let root: String = rows.first().ok_or(ConnectorError::NoResultSets)?.get(0);
+    parse_plan_cost(&root).ok_or_else(|| {
+        let msg = format!("cannot parse estimates from query plan: {root}");
+        std::io::Error::new(std::io::ErrorKind::InvalidData, msg).into()
+    })
+}
+
+fn parse_plan_cost(line: &str) -> Option<QueryCost> {
+    let (_, estimates) = line.rsplit_once("(cost=")?;
+    let mut estimates = estimates.split_whitespace();
+
+    // startup and total cost
+    let (_, total_cost) = estimates.next()?.split_once("..")?;
+    let rows = estimates.next()?.strip_prefix("rows=")?;
+    Some(QueryCost {
+        estimated_rows: rows.parse().ok()?,
+        estimated_cost: total_cost.parse().ok()?,
+    })
+}
+
+#[test]
+fn test_parse_plan_cost() {
+    let res = parse_plan_cost("Seq Scan on t  (cost=0.00..35.50 rows=2550 width=4)").unwrap();
+    assert_eq!(res.estimated_rows, 2550);
+    assert_eq!(res.estimated_cost, 35.5);
+
+    let res = parse_plan_cost("Delete on \"t (cost=1)\"  (cost=0.00..15.00 rows=0 width=0)");
+    assert_eq!(res.unwrap().estimated_rows, 0);
+
+    assert_eq!(parse_plan_cost("Result"), None);
 }
 
 impl SchemaEdit for super::PostgresConnection {
     fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
-        let column_defs = schema
-            .fields()
-            .iter()
-            .map(|field| {
-                let ty = PostgresConnection::type_arrow_into_db(field.data_type()).unwrap_or_else(
-                    || {
-                        unimplemented!("cannot store type {} in PostgreSQL", field.data_type());
-                    },
-                );
+        table_create(self, name, schema, false)
+    }
 
-                let is_nullable =
-                    field.is_nullable() || matches!(field.data_type(), DataType::Null);
-                let not_null = if is_nullable { "" } else { " NOT NULL" };
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        table_create(self, name, schema, true)
+    }
 
-                let name = escaped_ident(field.name());
-                format!("{name} {ty}{not_null}",)
-            })
-            .join(",");
-
-        let ddl = format!("CREATE TABLE {} ({column_defs});", escaped_ident(name));
-
-        let res = self.client.execute(&ddl, &[]);
-        match res {
-            Ok(_) => Ok(()),
-            Err(e) if matches!(e.code(), Some(&SqlState::DUPLICATE_TABLE)) => {
-                Err(TableCreateError::TableExists)
-            }
-            Err(e) => Err(TableCreateError::Connector(ConnectorError::Postgres(
-                PostgresError::Postgres(e),
-            ))),
+    fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
+        table_drop(self, name)
+    }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "ALTER TABLE {} RENAME TO {}",
+            escaped_ident(from),
+            escaped_ident(to)
+        );
+        self.client
+            .execute(&ddl, &[])
+            .map_err(PostgresError::Postgres)?;
+        Ok(())
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {}",
+            escaped_ident(table),
+            escaped_ident(from),
+            escaped_ident(to)
+        );
+        self.client
+            .execute(&ddl, &[])
+            .map_err(PostgresError::Postgres)?;
+        Ok(())
+    }
+}
+
+fn table_create(
+    conn: &mut PostgresConnection,
+    name: &str,
+    schema: SchemaRef,
+    temporary: bool,
+) -> Result<(), TableCreateError> {
+    let column_defs = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let ty = conn
+                .options
+                .type_arrow_into_db(field.data_type())
+                .unwrap_or_else(|| {
+                    unimplemented!("cannot store type {} in PostgreSQL", field.data_type());
+                });
+
+            let is_nullable = field.is_nullable() || matches!(field.data_type(), DataType::Null);
+            let not_null = if is_nullable { "" } else { " NOT NULL" };
+
+            let name = escaped_ident(field.name());
+            format!("{name} {ty}{not_null}",)
+        })
+        .join(",");
+
+    let temp = if temporary { "TEMPORARY " } else { "" };
+    let ddl = format!(
+        "CREATE {temp}TABLE {} ({column_defs});",
+        escaped_ident(name)
+    );
+
+    let res = conn.client.execute(&ddl, &[]);
+    match res {
+        Ok(_) => Ok(()),
+        Err(e) if matches!(e.code(), Some(&SqlState::DUPLICATE_TABLE)) => {
+            Err(TableCreateError::TableExists)
         }
+        Err(e) => Err(TableCreateError::Connector(ConnectorError::Postgres(
+            PostgresError::Postgres(e),
+        ))),
     }
+}
 
-    fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
-        let ddl = format!("DROP TABLE {}", escaped_ident(name));
-        let res = self.client.execute(&ddl, &[]);
+fn table_drop(conn: &mut PostgresConnection, name: &str) -> Result<(), TableDropError> {
+    let ddl = format!("DROP TABLE {}", escaped_ident(name));
+    let res = conn.client.execute(&ddl, &[]);
 
-        match res {
-            Ok(_) => Ok(()),
-            Err(e)
-                if matches!(e.code(), Some(&SqlState::UNDEFINED_TABLE)) ||
+    match res {
+        Ok(_) => Ok(()),
+        Err(e)
+            if matches!(e.code(), Some(&SqlState::UNDEFINED_TABLE)) ||
                     // GlareDB will return such errors
                     e.as_db_error().map_or(false, |e| {
                         e.message().starts_with("Error during planning: Table ")
                             && e.message().ends_with(" does not exist")
                     }) =>
-            {
-                Err(TableDropError::TableNonexistent)
-            }
-            Err(e) if matches!(e.code(), Some(&SqlState::UNDEFINED_TABLE)) => {
-                Err(TableDropError::TableNonexistent)
-            }
-            Err(err) => Err(TableDropError::Connector(ConnectorError::Postgres(
-                PostgresError::Postgres(err),
-            ))),
+        {
+            Err(TableDropError::TableNonexistent)
         }
+        Err(e) if matches!(e.code(), Some(&SqlState::UNDEFINED_TABLE)) => {
+            Err(TableDropError::TableNonexistent)
+        }
+        Err(err) => Err(TableDropError::Connector(ConnectorError::Postgres(
+            PostgresError::Postgres(err),
+        ))),
     }
 }
+
+/// Lists databases that accept connections, excluding templates.
+pub(super) fn list_catalogs(conn: &mut PostgresConnection) -> Result<Vec<String>, ConnectorError> {
+    let query = "
+        SELECT datname FROM pg_database
+        WHERE NOT datistemplate AND datallowconn
+        ORDER BY datname
+    ";
+    let rows = conn.client.query(query, &[]).map_err(PostgresError::from)?;
+    Ok(rows.into_iter().map(|r| r.get(0)).collect_vec())
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::api::StatementCacheStats;
+
+/// Least-recently-used cache of prepared statements, keyed by query text.
+#[derive(Default)]
+pub(super) struct StatementCache {
+    capacity: usize,
+    entries: HashMap<String, Entry>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+struct Entry {
+    stmt: postgres::Statement,
+    param_names: Vec<String>,
+    last_used: u64,
+}
+
+impl StatementCache {
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict();
+        }
+    }
+
+    pub fn get(&mut self, query: &str) -> Option<(postgres::Statement, Vec<String>)> {
+        if self.capacity == 0 {
+            return None;
+        }
+        self.clock += 1;
+        match self.entries.get_mut(query) {
+            Some(entry) => {
+                self.hits += 1;
+                entry.last_used = self.clock;
+                Some((entry.stmt.clone(), entry.param_names.clone()))
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, query: &str, stmt: &postgres::Statement, param_names: &[String]) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        let entry = Entry {
+            stmt: stmt.clone(),
+            param_names: param_names.to_vec(),
+            last_used: self.clock,
+        };
+        self.entries.insert(query.to_string(), entry);
+    }
+
+    pub fn stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.entries.len(),
+        }
+    }
+
+    fn evict(&mut self) {
+        let oldest = self.entries.iter().min_by_key(|(_, e)| e.last_used);
+        if let Some(key) = oldest.map(|(k, _)| k.clone()) {
+            self.entries.remove(&key);
+        }
+    }
+}
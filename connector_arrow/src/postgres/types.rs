@@ -1,27 +1,395 @@
 use std::{collections::HashMap, sync::Arc};
 
-use arrow::datatypes::{DataType, Field, Schema};
-use postgres::types::Type;
+use arrow::compute::CastOptions;
+use arrow::datatypes::{
+    DataType, Field, FieldRef, Fields, IntervalUnit, Schema, SchemaRef, TimeUnit,
+    DECIMAL128_MAX_PRECISION, DECIMAL256_MAX_PRECISION,
+};
+use arrow::record_batch::RecordBatch;
+use itertools::Itertools;
+use postgres::types::{Kind, Type};
 
+use crate::util::escape::escaped_ident;
 use crate::{api::Connector, errors::ConnectorError};
 
+use super::typmod::parse_typmod;
 use super::PostgresConnection;
 
+/// Overrides the mapping between PostgreSQL and Arrow types of a single connection.
+///
+/// Implemented for closures `Fn(&str) -> Option<DataType>`, which override only
+/// [TypeMapOverride::db_into_arrow].
+pub trait TypeMapOverride: Send + Sync {
+    /// Arrow type that columns of the PostgreSQL type are read as.
+    /// Type is identified by its name (i.e. `numeric`, or `_numeric` for arrays).
+    ///
+    /// Values are read using the default mapping and then converted
+    /// with [arrow::compute::cast]. None keeps the default mapping.
+    ///
+    /// `json` and `jsonb` can be read as [DataType::Struct], in which case
+    /// top-level keys of JSON objects are projected into fields of the struct.
+    ///
+    /// `interval` can be read as [DataType::Duration], when it has no months and days.
+    /// Otherwise, [crate::ConnectorError::DataOutOfRange] is returned.
+    /// Microseconds are truncated into seconds or milliseconds.
+    ///
+    /// `date` can be read as [DataType::Date64], in milliseconds since the epoch.
+    fn db_into_arrow(&self, db_ty: &str) -> Option<DataType> {
+        let _ = db_ty;
+        None
+    }
+
+    /// PostgreSQL type of columns that are created for the Arrow type.
+    /// None keeps the default mapping.
+    fn arrow_into_db(&self, ty: &DataType) -> Option<String> {
+        let _ = ty;
+        None
+    }
+}
+
+impl<F> TypeMapOverride for F
+where
+    F: Fn(&str) -> Option<DataType> + Send + Sync,
+{
+    fn db_into_arrow(&self, db_ty: &str) -> Option<DataType> {
+        self(db_ty)
+    }
+}
+
+impl std::fmt::Debug for dyn TypeMapOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TypeMapOverride")
+    }
+}
+
+/// Options that change how PostgreSQL types are converted into Arrow types.
+#[derive(Debug, Clone, Default)]
+pub struct TypeOptions {
+    pub timestamptz_naive: bool,
+    pub utf8_unchecked: bool,
+    pub numeric_decimal: bool,
+    pub view_types: bool,
+    pub network_addr_text: bool,
+    pub bit_list: bool,
+    pub timestamptz_append: bool,
+    pub type_override: Option<Arc<dyn TypeMapOverride>>,
+}
+
+impl TypeOptions {
+    /// Arrow type from [TypeMapOverride::db_into_arrow], if there is an override.
+    pub fn type_db_override(&self, ty: &Type) -> Option<DataType> {
+        self.type_override.as_ref()?.db_into_arrow(ty.name())
+    }
+
+    /// PostgreSQL type from [TypeMapOverride::arrow_into_db] or the default mapping.
+    pub fn type_arrow_into_db(&self, ty: &DataType) -> Option<String> {
+        let overridden = self
+            .type_override
+            .as_ref()
+            .and_then(|o| o.arrow_into_db(ty));
+        overridden.or_else(|| match ty {
+            DataType::Timestamp(TimeUnit::Microsecond, Some(_)) if self.timestamptz_append => {
+                Some("timestamptz".to_string())
+            }
+            _ => PostgresConnection::type_arrow_into_db(ty),
+        })
+    }
+
+    /// Type of array elements, which might have their own mapping.
+    /// Composite types are mapped to [DataType::Struct], but only as elements of arrays.
+    fn type_array_item_into_arrow(&self, element: &Type, typmod: i32) -> Option<DataType> {
+        let Kind::Composite(attributes) = element.kind() else {
+            return self.type_element_into_arrow(element, typmod);
+        };
+        let fields = attributes.iter().map(|attribute| {
+            let ty = attribute.type_();
+            if matches!(ty.kind(), Kind::Array(_) | Kind::Composite(_)) {
+                return None;
+            }
+            // typmods of attributes are not known
+            let data_type = self.type_element_into_arrow(ty, -1)?;
+            Some(Field::new(attribute.name(), data_type, true))
+        });
+        Some(DataType::Struct(fields.collect::<Option<Fields>>()?))
+    }
+
+    /// Lists cannot be nested, so bit strings within arrays are read as `Binary`,
+    /// even when [TypeOptions::bit_list] is set.
+    fn type_element_into_arrow(&self, ty: &Type, typmod: i32) -> Option<DataType> {
+        match self.type_db_into_arrow(ty, typmod)? {
+            DataType::List(_) => Some(DataType::Binary),
+            data_type => Some(data_type),
+        }
+    }
+
+    fn type_db_into_arrow(&self, ty: &Type, typmod: i32) -> Option<DataType> {
+        match ty.kind() {
+            Kind::Array(element) => {
+                let item = self.type_array_item_into_arrow(element, typmod)?;
+                return Some(DataType::List(Arc::new(Field::new_list_field(item, true))));
+            }
+            // binary format of enums is the text of the label
+            Kind::Enum(_) if self.view_types => return Some(DataType::Utf8View),
+            Kind::Enum(_) => return Some(DataType::Utf8),
+            _ => {}
+        }
+
+        match ty.name() {
+            "timestamptz" | "timestamp with time zone" if self.timestamptz_naive => {
+                Some(DataType::Timestamp(TimeUnit::Microsecond, None))
+            }
+            "bit" if parse_typmod(ty, typmod).length == Some(1) => Some(DataType::Boolean),
+            "bit" | "varbit" if self.bit_list => Some(DataType::new_list(DataType::Boolean, true)),
+            // the coarsest unit that can represent the declared precision
+            "time" => Some(match parse_typmod(ty, typmod).precision {
+                Some(0) => DataType::Time32(TimeUnit::Second),
+                Some(1..=3) => DataType::Time32(TimeUnit::Millisecond),
+                _ => DataType::Time64(TimeUnit::Microsecond),
+            }),
+            "inet" | "cidr" | "macaddr" | "macaddr8" if self.network_addr_text => {
+                Some(if self.view_types {
+                    DataType::Utf8View
+                } else {
+                    DataType::Utf8
+                })
+            }
+            // read directly, instead of casting from Date32 after reading
+            "date" => match self.type_db_override(ty) {
+                Some(DataType::Date64) => Some(DataType::Date64),
+                _ => Some(DataType::Date32),
+            },
+            // intervals are converted while reading, because months and days
+            // cannot be represented as a duration
+            "interval" => match self.type_db_override(ty) {
+                Some(ty @ DataType::Duration(_)) => Some(ty),
+                _ => Some(DataType::Interval(IntervalUnit::MonthDayNano)),
+            },
+            "numeric" if self.numeric_decimal => Some(
+                numeric_precision_scale(ty, typmod)
+                    .filter(|(p, s)| *s >= 0 && *s as u8 <= *p)
+                    .and_then(|(p, s)| {
+                        if p <= DECIMAL128_MAX_PRECISION {
+                            Some(DataType::Decimal128(p, s))
+                        } else if p <= DECIMAL256_MAX_PRECISION {
+                            Some(DataType::Decimal256(p, s))
+                        } else {
+                            None
+                        }
+                    })
+                    .unwrap_or(DataType::Utf8),
+            ),
+            name => match PostgresConnection::type_db_into_arrow(name)? {
+                DataType::Utf8 if self.view_types => Some(DataType::Utf8View),
+                DataType::Binary if self.view_types => Some(DataType::BinaryView),
+                ty => Some(ty),
+            },
+        }
+    }
+}
+
+/// Extracts precision and scale from type modifier of `numeric` type.
+/// Returns None for unconstrained numerics (i.e. `numeric` without parameters).
+fn numeric_precision_scale(ty: &Type, typmod: i32) -> Option<(u8, i8)> {
+    let typmod = parse_typmod(ty, typmod);
+    Some((typmod.precision?, typmod.scale?))
+}
+
+/// Types that don't have a binary output function, so they cannot be received
+/// by the extended query protocol.
+const TEXT_ONLY_TYPES: &[&str] = &["aclitem", "_aclitem", "gtsvector", "_gtsvector"];
+
+/// OID-alias types, which reference catalog objects by OID. Their binary output is
+/// the plain OID, so they are received as text, which contains the name of the object.
+pub(super) const REG_TYPES: &[&str] = &[
+    "regproc",
+    "regprocedure",
+    "regoper",
+    "regoperator",
+    "regclass",
+    "regcollation",
+    "regtype",
+    "regrole",
+    "regnamespace",
+    "regconfig",
+    "regdictionary",
+];
+
+/// Returns the cast that has to be applied to a column of given type, for it to be
+/// received as text.
+fn text_cast(ty: &Type) -> Option<&'static str> {
+    if TEXT_ONLY_TYPES.contains(&ty.name()) {
+        return Some("::text");
+    }
+    match ty.kind() {
+        Kind::Array(element) if REG_TYPES.contains(&element.name()) => Some("::text[]"),
+        _ if REG_TYPES.contains(&ty.name()) => Some("::text"),
+        _ => None,
+    }
+}
+
+/// If the statement returns any of [TEXT_ONLY_TYPES] or [REG_TYPES], returns a query
+/// that wraps the original query and casts such columns to text.
+pub fn cast_text_only_columns(query: &str, stmt: &postgres::Statement) -> Option<String> {
+    let columns = stmt.columns();
+    if !columns.iter().any(|c| text_cast(c.type_()).is_some()) {
+        return None;
+    }
+
+    // columns are aliased by position, because names might not be unique
+    let aliases = (0..columns.len()).map(|i| format!("_c{i}")).join(", ");
+    let projection = columns
+        .iter()
+        .enumerate()
+        .map(|(index, col)| {
+            let cast = text_cast(col.type_()).unwrap_or_default();
+            format!("_c{index}{cast} AS {}", escaped_ident(col.name()))
+        })
+        .join(", ");
+
+    let query = query.trim().trim_end_matches(';');
+    Some(format!(
+        "SELECT {projection} FROM ({query}) AS _q({aliases})"
+    ))
+}
+
+/// Binary format of `timestamp`, `timestamptz`, `time`, `timetz` and `interval` values,
+/// which depends on the `integer_datetimes` setting of the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatetimeEncoding {
+    /// 64-bit integer of microseconds. Default since PostgreSQL 8.4 and the only option since 10.
+    Integer,
+
+    /// Double precision seconds, used by servers built with `--disable-integer-datetimes`.
+    /// Values in this format cannot be decoded.
+    Float,
+}
+
+impl DatetimeEncoding {
+    /// Encoding for a value of `integer_datetimes` setting, as returned by `SHOW`.
+    pub fn from_setting(integer_datetimes: &str) -> Self {
+        match integer_datetimes {
+            "off" => DatetimeEncoding::Float,
+            _ => DatetimeEncoding::Integer,
+        }
+    }
+}
+
+/// Returns true when any of the result columns (or their array elements)
+/// is encoded according to [DatetimeEncoding].
+pub fn pg_stmt_uses_datetimes(stmt: &postgres::Statement) -> bool {
+    fn is_datetime(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Array(element) => is_datetime(element),
+            _ => matches!(
+                *ty,
+                Type::TIMESTAMP | Type::TIMESTAMPTZ | Type::TIME | Type::TIMETZ | Type::INTERVAL
+            ),
+        }
+    }
+    stmt.columns().iter().any(|c| is_datetime(c.type_()))
+}
+
 pub fn pg_stmt_to_arrow(
     stmt: &postgres::Statement,
+    options: &TypeOptions,
 ) -> Result<Arc<arrow::datatypes::Schema>, ConnectorError> {
     let fields: Vec<_> = stmt
         .columns()
         .iter()
-        .map(|col| pg_field_to_arrow(col.name().to_string(), col.type_(), true))
-        .collect();
+        .map(|col| {
+            let name = col.name().to_string();
+            let (ty, typmod) = (col.type_(), col.type_modifier());
+
+            // arrays of composites that cannot be decoded are not read as binary
+            if let Kind::Array(element) = ty.kind() {
+                if matches!(element.kind(), Kind::Composite(_))
+                    && options.type_db_into_arrow(ty, typmod).is_none()
+                {
+                    return Err(ConnectorError::NotSupported {
+                        connector_name: "connector_arrow::postgres",
+                        feature: "arrays of composite types with attributes of arrays, composites or unsupported types",
+                    });
+                }
+            }
+            Ok(pg_field_to_arrow(name, ty, typmod, true, options))
+        })
+        .collect::<Result<_, _>>()?;
     Ok(Arc::new(Schema::new(fields)))
 }
 
-pub fn pg_field_to_arrow(name: String, db_ty: &Type, nullable: bool) -> Field {
+/// Schema of the statement with [TypeMapOverride] applied.
+/// Returns None when no column is overridden, so `row_schema` can be used as is.
+pub fn pg_stmt_override(
+    stmt: &postgres::Statement,
+    row_schema: &SchemaRef,
+    options: &TypeOptions,
+) -> Option<SchemaRef> {
+    let overrides = stmt
+        .columns()
+        .iter()
+        .map(|col| options.type_db_override(col.type_()))
+        .collect_vec();
+    if overrides.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let fields = (row_schema.fields().iter().zip(overrides))
+        .map(|(field, ty)| match ty {
+            Some(ty) => Field::new(field.name(), ty, field.is_nullable()),
+            None => field.as_ref().clone(),
+        })
+        .collect_vec();
+    Some(Arc::new(Schema::new(fields)))
+}
+
+/// Converts columns of a batch that was read with the default mapping into
+/// types of the overridden schema.
+///
+/// `bytea` values that are read as [DataType::FixedSizeBinary] must have the
+/// exact length, otherwise an error is returned.
+///
+/// `json` and `jsonb` values that are read as [DataType::Struct] are parsed
+/// and their top-level keys are projected into fields of the struct
+/// (see [super::json::json_into_struct]).
+pub fn cast_to_schema(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+) -> Result<RecordBatch, ConnectorError> {
+    let columns = (batch.columns().iter().zip(schema.fields()))
+        .map(|(array, field)| match field.data_type() {
+            ty if array.data_type() == ty => Ok(array.clone()),
+            ty @ DataType::FixedSizeBinary(_) => {
+                let options = CastOptions {
+                    safe: false,
+                    ..Default::default()
+                };
+                arrow::compute::cast_with_options(array, ty, &options)
+            }
+            DataType::Struct(fields) if !matches!(array.data_type(), DataType::Struct(_)) => {
+                super::json::json_into_struct(array, fields)
+            }
+            ty => arrow::compute::cast(array, ty),
+        })
+        .try_collect()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Field of elements of a Postgres array, matching [TypeOptions::type_db_into_arrow].
+pub fn pg_array_item_field(element: &Type, typmod: i32, options: &TypeOptions) -> FieldRef {
+    let data_type = options.type_array_item_into_arrow(element, typmod);
+    Arc::new(Field::new_list_field(data_type.unwrap(), true))
+}
+
+pub fn pg_field_to_arrow(
+    name: String,
+    db_ty: &Type,
+    typmod: i32,
+    nullable: bool,
+    options: &TypeOptions,
+) -> Field {
     let mut metadata = HashMap::new();
 
-    let data_type = PostgresConnection::type_db_into_arrow(db_ty.name());
+    let data_type = options.type_db_into_arrow(db_ty, typmod);
 
     // if we cannot map to an arrow type, map into a binary
     let data_type = data_type.unwrap_or_else(|| {
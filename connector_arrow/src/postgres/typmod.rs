@@ -0,0 +1,117 @@
+use std::convert::TryFrom;
+
+use postgres::types::{Kind, Type};
+
+/// Parameters of a PostgreSQL type, decoded from its type modifier (`atttypmod`).
+///
+/// Parameters that the type does not have, or that were not declared
+/// (i.e. `varchar` without length), are None.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeMod {
+    /// Maximum length of `char(n)`, `varchar(n)`, `bit(n)` and `varbit(n)`.
+    pub length: Option<u32>,
+
+    /// Precision of `numeric(p, s)` or number of fractional digits of seconds
+    /// of `timestamp(p)`, `timestamptz(p)`, `time(p)`, `timetz(p)` and `interval(p)`.
+    pub precision: Option<u8>,
+
+    /// Scale of `numeric(p, s)`, which may be negative.
+    pub scale: Option<i8>,
+}
+
+/// Length of the varlena header, which is added to the typmod of some types.
+const VARHDRSZ: i32 = 4;
+
+/// Decodes the type modifier of a type. Arrays have the typmod of their element type.
+pub fn parse_typmod(ty: &Type, typmod: i32) -> TypeMod {
+    if let Kind::Array(element) = ty.kind() {
+        return parse_typmod(element, typmod);
+    }
+    // -1 means that the type has no modifier
+    if typmod < 0 {
+        return TypeMod::default();
+    }
+
+    match *ty {
+        Type::BPCHAR | Type::VARCHAR => TypeMod {
+            length: u32::try_from(typmod - VARHDRSZ).ok(),
+            ..TypeMod::default()
+        },
+        Type::BIT | Type::VARBIT => TypeMod {
+            length: u32::try_from(typmod).ok(),
+            ..TypeMod::default()
+        },
+        Type::NUMERIC => {
+            let Some(typmod) = typmod.checked_sub(VARHDRSZ).filter(|t| *t >= 0) else {
+                return TypeMod::default();
+            };
+            let precision = (typmod >> 16) & 0xffff;
+            // scale is 11-bit signed integer
+            let scale = ((typmod & 0x7ff) ^ 1024) - 1024;
+            TypeMod {
+                precision: u8::try_from(precision).ok(),
+                scale: i8::try_from(scale).ok(),
+                ..TypeMod::default()
+            }
+        }
+        Type::TIMESTAMP | Type::TIMESTAMPTZ | Type::TIME | Type::TIMETZ => TypeMod {
+            precision: u8::try_from(typmod).ok(),
+            ..TypeMod::default()
+        },
+        Type::INTERVAL => {
+            // lower 16 bits are precision, upper are the fields (i.e. `DAY TO SECOND`)
+            // full precision is 0xffff, which does not fit into u8
+            let precision = typmod & 0xffff;
+            TypeMod {
+                precision: u8::try_from(precision).ok(),
+                ..TypeMod::default()
+            }
+        }
+        _ => TypeMod::default(),
+    }
+}
+
+#[test]
+fn test_parse_typmod_varchar() {
+    // varchar(10), char(3)
+    let res = parse_typmod(&Type::VARCHAR, 14);
+    assert_eq!(res.length, Some(10));
+    assert_eq!(parse_typmod(&Type::BPCHAR, 7).length, Some(3));
+    assert_eq!(parse_typmod(&Type::VARCHAR_ARRAY, 14).length, Some(10));
+    assert_eq!(parse_typmod(&Type::VARCHAR, -1), TypeMod::default());
+    assert_eq!(parse_typmod(&Type::BIT, 1).length, Some(1));
+}
+
+#[test]
+fn test_parse_typmod_numeric() {
+    // numeric(10, 2)
+    let res = parse_typmod(&Type::NUMERIC, (10 << 16) + 2 + 4);
+    assert_eq!(res.precision, Some(10));
+    assert_eq!(res.scale, Some(2));
+    assert_eq!(res.length, None);
+
+    // numeric(3, -2)
+    let res = parse_typmod(&Type::NUMERIC, 198658);
+    assert_eq!((res.precision, res.scale), (Some(3), Some(-2)));
+
+    assert_eq!(parse_typmod(&Type::NUMERIC, -1), TypeMod::default());
+}
+
+#[test]
+fn test_parse_typmod_timestamp() {
+    // timestamp(3), timestamptz(0)
+    assert_eq!(parse_typmod(&Type::TIMESTAMP, 3).precision, Some(3));
+    assert_eq!(parse_typmod(&Type::TIMESTAMPTZ, 0).precision, Some(0));
+    assert_eq!(parse_typmod(&Type::TIMESTAMP, -1).precision, None);
+
+    // interval DAY TO SECOND(2), interval DAY TO SECOND
+    let day_to_second = 0x1c08 << 16;
+    assert_eq!(
+        parse_typmod(&Type::INTERVAL, day_to_second | 2).precision,
+        Some(2)
+    );
+    assert_eq!(
+        parse_typmod(&Type::INTERVAL, day_to_second | 0xffff).precision,
+        None
+    );
+}
@@ -0,0 +1,126 @@
+//! A connector wrapper that can only execute pre-registered statements.
+
+use std::collections::HashMap;
+
+use arrow::datatypes::{DataType, SchemaRef};
+
+use crate::api::{CancelToken, Connector, QueryCost, ReadOptions, StatementCacheStats};
+use crate::ConnectorError;
+
+/// Wraps a [Connector] and restricts it to a fixed set of statements.
+///
+/// Statements are registered under a key with [PreparedOnlyConnector::register] and
+/// are then executed by passing that key to [Connector::query]. Values can only be
+/// provided via query parameters, so SQL strings that were not registered up-front
+/// can never reach the data store.
+///
+/// Appending is not permitted.
+pub struct PreparedOnlyConnector<C> {
+    inner: C,
+    statements: HashMap<String, String>,
+}
+
+impl<C: Connector> PreparedOnlyConnector<C> {
+    pub fn new(inner: C) -> Self {
+        PreparedOnlyConnector {
+            inner,
+            statements: HashMap::new(),
+        }
+    }
+
+    /// Register a parameterized statement under a key.
+    /// Registering a statement under an existing key replaces the old statement.
+    pub fn register(&mut self, key: impl Into<String>, query: impl Into<String>) -> &mut Self {
+        self.statements.insert(key.into(), query.into());
+        self
+    }
+
+    pub fn unwrap(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: Connector> Connector for PreparedOnlyConnector<C> {
+    type Stmt<'conn>
+        = C::Stmt<'conn>
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = C::Append<'conn>
+    where
+        Self: 'conn;
+
+    /// Prepare a registered statement. `query` is the key of the statement.
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        let Some(registered) = self.statements.get(query) else {
+            return Err(ConnectorError::StatementNotRegistered(query.to_string()));
+        };
+        self.inner.query(registered)
+    }
+
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        let Some(registered) = self.statements.get(query) else {
+            return Err(ConnectorError::StatementNotRegistered(query.to_string()));
+        };
+        self.inner.query_with(registered, options)
+    }
+
+    /// Describe the result of a registered statement. `query` is the key of the statement.
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        let Some(registered) = self.statements.get(query) else {
+            return Err(ConnectorError::StatementNotRegistered(query.to_string()));
+        };
+        self.inner.query_schema(registered)
+    }
+
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        let Some(registered) = self.statements.get(query) else {
+            return Err(ConnectorError::StatementNotRegistered(query.to_string()));
+        };
+        self.inner.explain_cost(registered)
+    }
+
+    fn append<'a>(&'a mut self, _table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::prepared_only",
+            feature: "append",
+        })
+    }
+
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        self.inner.set_statement_cache_size(size)
+    }
+
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        self.inner.statement_cache_stats()
+    }
+
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        self.inner.cancel_token()
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        self.inner.list_catalogs()
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.inner.begin_transaction()
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.inner.end_transaction(commit)
+    }
+
+    fn type_db_into_arrow(database_ty: &str) -> Option<DataType> {
+        C::type_db_into_arrow(database_ty)
+    }
+
+    fn type_arrow_into_db(ty: &DataType) -> Option<String> {
+        C::type_arrow_into_db(ty)
+    }
+}
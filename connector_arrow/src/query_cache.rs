@@ -0,0 +1,222 @@
+//! A connector wrapper that caches query results.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::api::{
+    AppenderOptions, CancelToken, Connector, ForeignKey, QueryCost, ReadOptions, SchemaEdit,
+    SchemaGet, StatementCacheStats,
+};
+use crate::errors::{ConnectorError, TableCreateError, TableDropError};
+
+/// Wraps a [Connector] and caches results of [CachedConnector::query_cached] by query text.
+///
+/// Cached results are never invalidated automatically: use [CachedConnector::invalidate]
+/// or [CachedConnector::invalidate_all] after the underlying data has changed.
+///
+/// All other operations are passed through to the inner connector.
+pub struct CachedConnector<C> {
+    inner: C,
+    cache: HashMap<String, Arc<[RecordBatch]>>,
+}
+
+impl<C: Connector> CachedConnector<C> {
+    pub fn new(inner: C) -> Self {
+        CachedConnector {
+            inner,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Execute a query and return its results, or return results of a
+    /// previous execution of the same query.
+    pub fn query_cached(&mut self, query: &str) -> Result<Arc<[RecordBatch]>, ConnectorError> {
+        if let Some(batches) = self.cache.get(query) {
+            return Ok(batches.clone());
+        }
+
+        let batches: Arc<[RecordBatch]> = crate::query(&mut self.inner, query)?.into();
+        self.cache.insert(query.to_string(), batches.clone());
+        Ok(batches)
+    }
+
+    /// Remove results of a query from the cache.
+    pub fn invalidate(&mut self, query: &str) {
+        self.cache.remove(query);
+    }
+
+    /// Remove all results from the cache.
+    pub fn invalidate_all(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn unwrap(self) -> C {
+        self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
+
+impl<C: Connector> Connector for CachedConnector<C> {
+    type Stmt<'conn>
+        = C::Stmt<'conn>
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = C::Append<'conn>
+    where
+        Self: 'conn;
+
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        self.inner.query(query)
+    }
+
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        self.inner.query_with(query, options)
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        self.inner.execute(query)
+    }
+
+    fn execute_batch(&mut self, statements: &[&str]) -> Result<Vec<u64>, ConnectorError> {
+        self.inner.execute_batch(statements)
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError> {
+        self.inner.estimate_row_count(table_name)
+    }
+
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        self.inner.explain_cost(query)
+    }
+
+    fn load_parquet(&mut self, table_name: &str, path: &Path) -> Result<u64, ConnectorError> {
+        self.inner.load_parquet(table_name, path)
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        self.inner.query_schema(query)
+    }
+
+    fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        self.inner.append(table_name)
+    }
+
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        self.inner.append_with(table_name, options)
+    }
+
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        self.inner.delete_by_keys(table_name, key_columns, keys)
+    }
+
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        self.inner.merge(table_name, key_columns, batch)
+    }
+
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        self.inner.set_statement_cache_size(size)
+    }
+
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        self.inner.statement_cache_stats()
+    }
+
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        self.inner.cancel_token()
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        self.inner.list_catalogs()
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.inner.begin_transaction()
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.inner.end_transaction(commit)
+    }
+
+    fn type_db_into_arrow(database_ty: &str) -> Option<DataType> {
+        C::type_db_into_arrow(database_ty)
+    }
+
+    fn type_arrow_into_db(ty: &DataType) -> Option<String> {
+        C::type_arrow_into_db(ty)
+    }
+}
+
+impl<C: Connector + SchemaGet> SchemaGet for CachedConnector<C> {
+    fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
+        self.inner.table_list()
+    }
+
+    fn table_get(&mut self, name: &str) -> Result<SchemaRef, ConnectorError> {
+        self.inner.table_get(name)
+    }
+
+    fn table_exists(&mut self, name: &str) -> Result<bool, ConnectorError> {
+        self.inner.table_exists(name)
+    }
+
+    fn table_primary_key(&mut self, name: &str) -> Result<Vec<String>, ConnectorError> {
+        self.inner.table_primary_key(name)
+    }
+
+    fn table_foreign_keys(&mut self, name: &str) -> Result<Vec<ForeignKey>, ConnectorError> {
+        self.inner.table_foreign_keys(name)
+    }
+}
+
+impl<C: Connector + SchemaEdit> SchemaEdit for CachedConnector<C> {
+    fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        self.inner.table_create(name, schema)
+    }
+
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        self.inner.table_create_temp(name, schema)
+    }
+
+    fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
+        self.inner.table_drop(name)
+    }
+
+    fn table_drop_if_exists(&mut self, name: &str) -> Result<(), ConnectorError> {
+        self.inner.table_drop_if_exists(name)
+    }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        self.inner.rename_table(from, to)
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        self.inner.rename_column(table, from, to)
+    }
+}
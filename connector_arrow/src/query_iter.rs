@@ -0,0 +1,47 @@
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use pac_cell::PacCell;
+
+use crate::api::{Connector, ResultReader, Statement};
+use crate::ConnectorError;
+
+type Reader<'conn, C> = <<C as Connector>::Stmt<'conn> as Statement<'conn>>::Reader<'conn>;
+
+/// Execute a single query and return an iterator over batches of its results.
+///
+/// Unlike [crate::query], batches are not collected, but read as the iterator advances.
+/// The iterator owns the prepared statement, so it can be returned from functions.
+pub fn query_iter<'conn, C: Connector>(
+    conn: &'conn mut C,
+    query: &str,
+) -> Result<QueryIter<'conn, C>, ConnectorError> {
+    log::debug!("query: {query}");
+
+    let stmt = conn.query(query)?;
+
+    // PacCell is needed so we can return the statement and the reader that mutably borrows it.
+    let pac = PacCell::try_new(stmt, |stmt| stmt.start([]))?;
+    Ok(QueryIter(pac))
+}
+
+/// Results of [query_iter]: a reader together with the statement it reads from.
+pub struct QueryIter<'conn, C: Connector + 'conn>(
+    PacCell<
+        C::Stmt<'conn>,   // parent
+        Reader<'conn, C>, // child
+    >,
+);
+
+impl<'conn, C: Connector + 'conn> Iterator for QueryIter<'conn, C> {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.with_mut(|reader| reader.next())
+    }
+}
+
+impl<'conn, C: Connector + 'conn> ResultReader<'conn> for QueryIter<'conn, C> {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        self.0.with_mut(|reader| reader.get_schema())
+    }
+}
@@ -0,0 +1,68 @@
+//! Writing of query results into other formats, using the `arrow` version of this crate.
+//!
+//! ```ignore
+//! let batches = connector_arrow::query(&mut conn, "SELECT * FROM my_table")?;
+//! let schema = batches[0].schema();
+//!
+//! let file = std::fs::File::create("my_table.csv")?;
+//! connector_arrow::sink::write_csv(&schema, &batches, file, &CsvOptions::default())?;
+//! ```
+
+use std::io::Write;
+
+use arrow::csv::WriterBuilder;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+
+use crate::ConnectorError;
+
+/// Options for [write_csv].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Separator of values. Defaults to `,`.
+    pub delimiter: u8,
+
+    /// Write names of the columns as the first line. Defaults to true.
+    pub header: bool,
+
+    /// Text written for null values. Defaults to an empty string.
+    pub null: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            header: true,
+            null: String::new(),
+        }
+    }
+}
+
+/// Writes batches into CSV.
+///
+/// Temporal values are formatted as RFC 3339 (i.e. `2024-02-23T16:18:36.000001Z`)
+/// and decimals with all digits of their scale, so output does not depend on the locale.
+/// Nested types (lists, structs) are not supported.
+///
+/// Header is written even when there are no batches. Returns the inner writer.
+pub fn write_csv<W: Write>(
+    schema: &SchemaRef,
+    batches: &[RecordBatch],
+    writer: W,
+    options: &CsvOptions,
+) -> Result<W, ConnectorError> {
+    let mut writer = WriterBuilder::new()
+        .with_delimiter(options.delimiter)
+        .with_header(options.header)
+        .with_null(options.null.clone())
+        .build(writer);
+
+    if batches.is_empty() {
+        writer.write(&RecordBatch::new_empty(schema.clone()))?;
+    }
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    Ok(writer.into_inner())
+}
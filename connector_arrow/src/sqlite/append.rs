@@ -1,55 +1,212 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 use itertools::zip_eq;
 use itertools::Itertools;
 use rusqlite::types::Value;
-use rusqlite::{params_from_iter, Transaction};
+use rusqlite::{params_from_iter, Connection, Savepoint};
 
+use crate::api::{Append, AppenderOptions, ConflictMode, StatementCacheStats};
 use crate::impl_consume_unsupported;
-use crate::types::{FixedSizeBinaryType, NullType};
+use crate::types::{FixedSizeBinaryType, ListType, NullType};
 use crate::util::escape::escaped_ident;
 use crate::util::transport;
 use crate::util::transport::{Consume, ConsumeTy};
-use crate::util::ArrayCellRef;
-use crate::{api::Append, ConnectorError};
+use crate::util::{coerce, ArrayCellRef};
+use crate::ConnectorError;
+
+use super::json;
 
 pub struct SQLiteAppender<'conn> {
     table: String,
-    transaction: Transaction<'conn>,
+    transaction: Savepoint<'conn>,
+
+    /// Set when nulls should be replaced by column defaults.
+    columns: Option<Vec<TableColumn>>,
+
+    /// `INSERT` or `INSERT OR IGNORE`
+    insert: &'static str,
+
+    /// `ON CONFLICT` clause appended to the `INSERT` statement.
+    on_conflict: String,
+
+    /// Multi-row `INSERT` queries, keyed by number of columns and rows.
+    /// Their prepared statements are kept in the statement cache of the connection.
+    insert_queries: HashMap<(usize, usize), String>,
+    hits: u64,
+
+    /// Encode nested columns as JSON text.
+    json_nested: bool,
+}
+
+struct TableColumn {
+    name: String,
+    not_null: bool,
+    has_default: bool,
 }
 
 impl<'conn> SQLiteAppender<'conn> {
-    pub fn new(table: String, transaction: Transaction<'conn>) -> Result<Self, ConnectorError> {
-        Ok(Self { table, transaction })
+    pub fn new(
+        table: String,
+        transaction: Savepoint<'conn>,
+        options: AppenderOptions,
+        json_nested: bool,
+    ) -> Result<Self, ConnectorError> {
+        let table_columns = if options.null_as_default || options.conflict != ConflictMode::Error {
+            Some(table_columns(&transaction, &table)?)
+        } else {
+            None
+        };
+
+        let (insert, on_conflict) = match &options.conflict {
+            ConflictMode::Error => ("INSERT", String::new()),
+            ConflictMode::Ignore => ("INSERT OR IGNORE", String::new()),
+            ConflictMode::Replace(conflict_columns) => {
+                let columns = table_columns.iter().flatten();
+                let names = columns.clone().map(|c| c.name.as_str()).collect_vec();
+                options.conflict.validate(&table, &names)?;
+
+                let target = conflict_columns.iter().map(|c| escaped_ident(c)).join(", ");
+                let updates = names
+                    .iter()
+                    .filter(|c| !conflict_columns.iter().any(|k| k == *c))
+                    .map(|c| format!("{c} = excluded.{c}", c = escaped_ident(c)))
+                    .join(", ");
+                let action = if updates.is_empty() {
+                    "DO NOTHING".to_string()
+                } else {
+                    format!("DO UPDATE SET {updates}")
+                };
+                ("INSERT", format!(" ON CONFLICT ({target}) {action}"))
+            }
+        };
+
+        Ok(Self {
+            table,
+            transaction,
+            columns: table_columns.filter(|_| options.null_as_default),
+            insert,
+            on_conflict,
+            insert_queries: HashMap::new(),
+            hits: 0,
+            json_nested,
+        })
+    }
+
+    /// Counters of `INSERT` statements prepared by this appender.
+    /// Each distinct number of rows per statement is prepared once.
+    pub fn statement_cache_stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            hits: self.hits,
+            misses: self.insert_queries.len() as u64,
+            size: self.insert_queries.len(),
+        }
+    }
+
+    /// Inserts rows one by one, omitting columns that are null and have a default.
+    fn append_with_defaults(
+        &mut self,
+        batch: &RecordBatch,
+        columns: &[TableColumn],
+    ) -> Result<(), ConnectorError> {
+        if batch.num_columns() != columns.len() {
+            return Err(ConnectorError::DataSchemaMismatch(format!(
+                "table {} has {} columns, but batch has {}",
+                self.table,
+                columns.len(),
+                batch.num_columns()
+            )));
+        }
+        for (column, array) in zip_eq(columns, batch.columns()) {
+            if column.not_null && !column.has_default && array.null_count() > 0 {
+                return Err(ConnectorError::DataSchemaMismatch(format!(
+                    "column {} is NOT NULL and has no default, but batch contains nulls",
+                    column.name
+                )));
+            }
+        }
+
+        for row_number in 0..batch.num_rows() {
+            let included = zip_eq(columns, batch.columns())
+                .map(|(column, array)| {
+                    !(column.not_null && column.has_default && array.is_null(row_number))
+                })
+                .collect_vec();
+
+            let query = if included.iter().any(|i| *i) {
+                let names = zip_eq(columns, &included)
+                    .filter(|(_, i)| **i)
+                    .map(|(c, _)| escaped_ident(&c.name))
+                    .join(",");
+                let values = included.iter().filter(|i| **i).map(|_| "?").join(",");
+                format!(
+                    "{} INTO {} ({names}) VALUES ({values}){}",
+                    self.insert,
+                    escaped_ident(&self.table),
+                    self.on_conflict
+                )
+            } else {
+                // upsert clause is not allowed with DEFAULT VALUES
+                format!(
+                    "{} INTO {} DEFAULT VALUES",
+                    self.insert,
+                    escaped_ident(&self.table)
+                )
+            };
+
+            let params = zip_eq(collect_args(batch, row_number..row_number + 1), &included)
+                .filter(|(_, i)| **i)
+                .map(|(v, _)| v);
+            self.transaction.execute(&query, params_from_iter(params))?;
+        }
+        Ok(())
     }
 }
 
+fn table_columns(conn: &Connection, table_name: &str) -> Result<Vec<TableColumn>, ConnectorError> {
+    let query = format!("PRAGMA table_info({});", escaped_ident(table_name));
+    let mut statement = conn.prepare(&query)?;
+    let mut rows = statement.query(())?;
+    // contains columns: cid, name, type, notnull, dflt_value, pk
+
+    let mut columns = Vec::new();
+    while let Some(row) = rows.next()? {
+        let default: Option<String> = row.get(4)?;
+        columns.push(TableColumn {
+            name: row.get(1)?,
+            not_null: row.get(3)?,
+            has_default: default.is_some(),
+        });
+    }
+    Ok(columns)
+}
+
 impl<'conn> Append<'conn> for SQLiteAppender<'conn> {
     fn append(
         &mut self,
         batch: arrow::record_batch::RecordBatch,
     ) -> Result<(), crate::ConnectorError> {
-        // TODO: 30 is a guess, we need benchmarking to find the optimum value
-        const BATCH_SIZE: usize = 30;
+        let batch = coerce::decode_dictionaries(batch)?;
+        let batch = self.encode_nested(batch)?;
 
-        let last_batch_size = batch.num_rows() % BATCH_SIZE;
-
-        let batch_query = insert_query(&self.table, batch.num_columns(), BATCH_SIZE);
-        for batch_number in 0..(batch.num_rows() / BATCH_SIZE) {
-            let rows_range = (batch_number * BATCH_SIZE)..((batch_number + 1) * BATCH_SIZE);
-
-            let params: Vec<Value> = collect_args(&batch, rows_range);
-            self.transaction
-                .execute(&batch_query, params_from_iter(params))?;
+        if let Some(columns) = self.columns.take() {
+            let res = self.append_with_defaults(&batch, &columns);
+            self.columns = Some(columns);
+            return res;
         }
 
-        if last_batch_size > 0 {
-            let rows_range = (batch.num_rows() - last_batch_size)..batch.num_rows();
+        // TODO: 30 is a guess, we need benchmarking to find the optimum value
+        const BATCH_SIZE: usize = 30;
+
+        for chunk_start in (0..batch.num_rows()).step_by(BATCH_SIZE) {
+            let rows_range = chunk_start..(chunk_start + BATCH_SIZE).min(batch.num_rows());
 
-            let last_query = insert_query(&self.table, batch.num_columns(), last_batch_size);
-            let params: Vec<Value> = collect_args(&batch, rows_range);
-            self.transaction
-                .execute(&last_query, params_from_iter(params))?;
+            let params: Vec<Value> = collect_args(&batch, rows_range.clone());
+            let query = self.insert_query(batch.num_columns(), rows_range.len());
+            let mut stmt = self.transaction.prepare_cached(&query)?;
+            stmt.execute(params_from_iter(params))?;
         }
 
         Ok(())
@@ -60,15 +217,70 @@ impl<'conn> Append<'conn> for SQLiteAppender<'conn> {
     }
 }
 
-fn insert_query(table_name: &str, cols: usize, rows: usize) -> String {
-    let values = (0..rows)
-        .map(|_| {
-            let row = (0..cols).map(|_| "?").join(",");
-            format!("({row})")
-        })
-        .join(",");
+impl SQLiteAppender<'_> {
+    /// Replaces nested columns with their JSON encoding.
+    fn encode_nested(&self, batch: RecordBatch) -> Result<RecordBatch, ConnectorError> {
+        let schema = batch.schema();
+        if !schema.fields().iter().any(|f| is_nested(f.data_type())) {
+            return Ok(batch);
+        }
+        if !self.json_nested {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::sqlite",
+                feature: "nested types (see SQLiteConnection::with_json_nested)",
+            });
+        }
 
-    format!("INSERT INTO {} VALUES {values}", escaped_ident(table_name))
+        let mut fields = Vec::with_capacity(batch.num_columns());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, array) in zip_eq(schema.fields(), batch.columns()) {
+            if is_nested(field.data_type()) {
+                let field = Field::new(field.name(), DataType::Utf8, field.is_nullable());
+                fields.push(Arc::new(field));
+                columns.push(json::nested_into_json(array)?);
+            } else {
+                fields.push(field.clone());
+                columns.push(array.clone());
+            }
+        }
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    fn insert_query(&mut self, cols: usize, rows: usize) -> String {
+        if let Some(query) = self.insert_queries.get(&(cols, rows)) {
+            self.hits += 1;
+            return query.clone();
+        }
+
+        let values = (0..rows)
+            .map(|_| {
+                let row = (0..cols).map(|_| "?").join(",");
+                format!("({row})")
+            })
+            .join(",");
+
+        let query = format!(
+            "{} INTO {} VALUES {values}{}",
+            self.insert,
+            escaped_ident(&self.table),
+            self.on_conflict
+        );
+        self.insert_queries.insert((cols, rows), query.clone());
+        query
+    }
+}
+
+fn is_nested(ty: &DataType) -> bool {
+    matches!(
+        ty,
+        DataType::List(_)
+            | DataType::LargeList(_)
+            | DataType::FixedSizeList(_, _)
+            | DataType::Struct(_)
+    )
 }
 
 fn collect_args(batch: &RecordBatch, rows_range: std::ops::Range<usize>) -> Vec<Value> {
@@ -189,6 +401,9 @@ impl_consume_unsupported!(
         IntervalYearMonthType,
         IntervalDayTimeType,
         IntervalMonthDayNanoType,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
 
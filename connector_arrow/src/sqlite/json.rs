@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanBuilder, Float64Builder, Int64Builder, StringBuilder,
+};
+use arrow::datatypes::*;
+use arrow::error::ArrowError;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use itertools::Itertools;
+use rusqlite::types::Value;
+
+use crate::util::escape::escaped_ident;
+use crate::ConnectorError;
+
+use super::SQLiteConnection;
+
+impl SQLiteConnection {
+    /// Extracts values from a JSON column of a table into typed arrow columns.
+    ///
+    /// Each of the `paths` is a pair of the resulting column name and a
+    /// [JSON path](https://www.sqlite.org/json1.html#path_arguments) passed to `json_extract`.
+    ///
+    /// Type of each column is inferred from JSON types of all extracted values:
+    /// booleans are [DataType::Boolean], integers [DataType::Int64] and reals
+    /// [DataType::Float64]. Mixed integers and reals are read as [DataType::Float64]
+    /// and any other mix of types, as well as text, objects and arrays are read as
+    /// [DataType::Utf8]. Columns that contain only JSON nulls or missing values
+    /// are [DataType::Null].
+    pub fn query_json_extract(
+        &mut self,
+        table_name: &str,
+        json_column: &str,
+        paths: &[(&str, &str)],
+    ) -> Result<RecordBatch, ConnectorError> {
+        let column = escaped_ident(json_column);
+        let projection = (1..=paths.len())
+            .map(|i| format!("json_extract({column}, ?{i}), json_type({column}, ?{i})"))
+            .join(", ");
+        let projection = if paths.is_empty() {
+            "NULL".to_string()
+        } else {
+            projection
+        };
+        let query = format!("SELECT {projection} FROM {}", escaped_ident(table_name));
+
+        let mut stmt = self.inner.prepare(&query)?;
+        let args = paths.iter().map(|(_, path)| path).collect_vec();
+        let mut rows = stmt.query(rusqlite::params_from_iter(args))?;
+
+        let mut types: Vec<Option<JsonType>> = vec![None; paths.len()];
+        let mut columns: Vec<Vec<(Value, Option<JsonType>)>> = vec![Vec::new(); paths.len()];
+        let mut row_count = 0;
+        while let Some(row) = rows.next()? {
+            for (index, column) in columns.iter_mut().enumerate() {
+                let value: Value = row.get(index * 2)?;
+                let json_ty: Option<String> = row.get(index * 2 + 1)?;
+
+                let json_ty = json_ty.as_deref().and_then(JsonType::parse);
+                if let Some(ty) = json_ty {
+                    let current = &mut types[index];
+                    *current = Some(current.map_or(ty, |c| c.merge(ty)));
+                }
+                column.push((value, json_ty));
+            }
+            row_count += 1;
+        }
+
+        let mut fields = Vec::with_capacity(paths.len());
+        let mut arrays = Vec::with_capacity(paths.len());
+        for (((name, _), ty), values) in paths.iter().zip(types).zip(columns) {
+            let array = build_array(ty, values);
+            fields.push(Field::new(*name, array.data_type().clone(), true));
+            arrays.push(array);
+        }
+
+        let schema = Arc::new(Schema::new(fields));
+        let options = RecordBatchOptions::new().with_row_count(Some(row_count));
+        Ok(RecordBatch::try_new_with_options(schema, arrays, &options)?)
+    }
+}
+
+/// JSON types of extracted values.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum JsonType {
+    Boolean,
+    Integer,
+    Real,
+    Text,
+}
+
+impl JsonType {
+    fn parse(json_type: &str) -> Option<Self> {
+        Some(match json_type {
+            "null" => return None,
+            "true" | "false" => JsonType::Boolean,
+            "integer" => JsonType::Integer,
+            "real" => JsonType::Real,
+            _ => JsonType::Text,
+        })
+    }
+
+    /// Type that can hold values of both types.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (JsonType::Integer, JsonType::Real) | (JsonType::Real, JsonType::Integer) => {
+                JsonType::Real
+            }
+            _ => JsonType::Text,
+        }
+    }
+}
+
+fn build_array(ty: Option<JsonType>, values: Vec<(Value, Option<JsonType>)>) -> ArrayRef {
+    match ty {
+        None => arrow::array::new_null_array(&DataType::Null, values.len()),
+        Some(JsonType::Boolean) => {
+            let mut builder = BooleanBuilder::with_capacity(values.len());
+            for (value, _) in values {
+                builder.append_option(match value {
+                    Value::Integer(v) => Some(v != 0),
+                    _ => None,
+                });
+            }
+            Arc::new(builder.finish())
+        }
+        Some(JsonType::Integer) => {
+            let mut builder = Int64Builder::with_capacity(values.len());
+            for (value, _) in values {
+                builder.append_option(match value {
+                    Value::Integer(v) => Some(v),
+                    _ => None,
+                });
+            }
+            Arc::new(builder.finish())
+        }
+        Some(JsonType::Real) => {
+            let mut builder = Float64Builder::with_capacity(values.len());
+            for (value, _) in values {
+                builder.append_option(match value {
+                    Value::Integer(v) => Some(v as f64),
+                    Value::Real(v) => Some(v),
+                    _ => None,
+                });
+            }
+            Arc::new(builder.finish())
+        }
+        Some(JsonType::Text) => {
+            let mut builder = StringBuilder::with_capacity(values.len(), 0);
+            for (value, json_ty) in values {
+                match value {
+                    Value::Integer(v) if json_ty == Some(JsonType::Boolean) => {
+                        builder.append_value(if v != 0 { "true" } else { "false" })
+                    }
+                    Value::Integer(v) => builder.append_value(v.to_string()),
+                    Value::Real(v) => builder.append_value(v.to_string()),
+                    Value::Text(v) => builder.append_value(v),
+                    _ => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+    }
+}
+
+/// Encodes each value of a nested array as JSON text.
+pub(super) fn nested_into_json(array: &dyn Array) -> Result<ArrayRef, ArrowError> {
+    let mut builder = StringBuilder::with_capacity(array.len(), 0);
+    for index in 0..array.len() {
+        if array.is_null(index) {
+            builder.append_null();
+        } else {
+            builder.append_value(value_into_json(array, index)?.to_string());
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Converts a value of an array into JSON.
+///
+/// Numbers, booleans and text map to their JSON counterparts, lists to arrays and
+/// structs to objects. Non-finite floats are null and all other types are
+/// formatted as strings.
+fn value_into_json(array: &dyn Array, index: usize) -> Result<serde_json::Value, ArrowError> {
+    use serde_json::Value as Json;
+
+    if array.is_null(index) {
+        return Ok(Json::Null);
+    }
+    Ok(match array.data_type() {
+        DataType::Null => Json::Null,
+        DataType::Boolean => Json::Bool(array.as_boolean().value(index)),
+
+        DataType::Int8 => Json::from(array.as_primitive::<Int8Type>().value(index)),
+        DataType::Int16 => Json::from(array.as_primitive::<Int16Type>().value(index)),
+        DataType::Int32 => Json::from(array.as_primitive::<Int32Type>().value(index)),
+        DataType::Int64 => Json::from(array.as_primitive::<Int64Type>().value(index)),
+        DataType::UInt8 => Json::from(array.as_primitive::<UInt8Type>().value(index)),
+        DataType::UInt16 => Json::from(array.as_primitive::<UInt16Type>().value(index)),
+        DataType::UInt32 => Json::from(array.as_primitive::<UInt32Type>().value(index)),
+        DataType::UInt64 => Json::from(array.as_primitive::<UInt64Type>().value(index)),
+
+        DataType::Float16 => {
+            float_into_json(array.as_primitive::<Float16Type>().value(index).into())
+        }
+        DataType::Float32 => {
+            float_into_json(array.as_primitive::<Float32Type>().value(index).into())
+        }
+        DataType::Float64 => float_into_json(array.as_primitive::<Float64Type>().value(index)),
+
+        DataType::Utf8 => Json::from(array.as_string::<i32>().value(index)),
+        DataType::LargeUtf8 => Json::from(array.as_string::<i64>().value(index)),
+        DataType::Utf8View => Json::from(array.as_string_view().value(index)),
+
+        DataType::List(_) => list_into_json(array.as_list::<i32>().value(index).as_ref())?,
+        DataType::LargeList(_) => list_into_json(array.as_list::<i64>().value(index).as_ref())?,
+        DataType::FixedSizeList(_, _) => {
+            list_into_json(array.as_fixed_size_list().value(index).as_ref())?
+        }
+        DataType::Struct(fields) => {
+            let array = array.as_struct();
+            let mut object = serde_json::Map::with_capacity(fields.len());
+            for (field, column) in fields.iter().zip(array.columns()) {
+                let value = value_into_json(column.as_ref(), index)?;
+                object.insert(field.name().clone(), value);
+            }
+            Json::Object(object)
+        }
+
+        _ => Json::String(arrow::util::display::array_value_to_string(array, index)?),
+    })
+}
+
+fn list_into_json(values: &dyn Array) -> Result<serde_json::Value, ArrowError> {
+    let values = (0..values.len())
+        .map(|i| value_into_json(values, i))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(serde_json::Value::Array(values))
+}
+
+fn float_into_json(value: f64) -> serde_json::Value {
+    serde_json::Number::from_f64(value).map_or(serde_json::Value::Null, serde_json::Value::Number)
+}
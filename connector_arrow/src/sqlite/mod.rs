@@ -1,6 +1,7 @@
 //! Provides `connector_arrow` traits for [rusqlite crate](https://docs.rs/rusqlite).
 
 mod append;
+mod json;
 mod query;
 mod schema;
 mod types;
@@ -8,19 +9,33 @@ mod types;
 #[doc(hidden)]
 pub use append::SQLiteAppender;
 #[doc(hidden)]
-pub use query::SQLiteStatement;
+pub use query::{SQLiteBatchStream, SQLiteStatement};
 
-use crate::api::Connector;
+use crate::api::{AppenderOptions, CancelToken, Connector};
 use crate::errors::ConnectorError;
 use arrow::datatypes::DataType;
 
 pub struct SQLiteConnection {
     inner: rusqlite::Connection,
+    json_nested: bool,
 }
 
 impl SQLiteConnection {
     pub fn new(inner: rusqlite::Connection) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            json_nested: false,
+        }
+    }
+
+    /// Append nested columns (`List`, `LargeList`, `FixedSizeList` and `Struct`)
+    /// as JSON text, which can be processed with SQLite's JSON functions.
+    ///
+    /// Such columns are read back as `Utf8`. To extract typed values, use
+    /// [SQLiteConnection::query_json_extract].
+    pub fn with_json_nested(mut self, enabled: bool) -> Self {
+        self.json_nested = enabled;
+        self
     }
 
     pub fn unwrap(self) -> rusqlite::Connection {
@@ -48,10 +63,43 @@ impl Connector for SQLiteConnection {
         Ok(SQLiteStatement { stmt })
     }
 
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        Ok(self.inner.execute(query, ())? as u64)
+    }
+
+    /// Types of result columns are derived from their declared types. SQLite cannot tell
+    /// the type of expressions without computing them, so such columns are [DataType::Null].
+    fn query_schema(&mut self, query: &str) -> Result<arrow::datatypes::SchemaRef, ConnectorError> {
+        let stmt = self.inner.prepare(query)?;
+        Ok(query::stmt_decl_schema(&stmt))
+    }
+
     fn append<'a>(&'a mut self, table: &str) -> Result<Self::Append<'a>, ConnectorError> {
-        let transaction = self.inner.transaction()?;
+        self.append_with(table, AppenderOptions::default())
+    }
+
+    fn append_with<'a>(
+        &'a mut self,
+        table: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        // a savepoint starts a transaction, or nests within one started by [Connector::transaction]
+        let transaction = self.inner.savepoint()?;
+
+        SQLiteAppender::new(table.to_string(), transaction, options, self.json_nested)
+    }
 
-        SQLiteAppender::new(table.to_string(), transaction)
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        let handle = self.inner.get_interrupt_handle();
+        Ok(CancelToken::new(move || {
+            handle.interrupt();
+            Ok(())
+        }))
+    }
+
+    /// Lists the main database, the temp database (if it was used) and attached databases.
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        schema::list_catalogs(self)
     }
 
     fn type_db_into_arrow(database_ty: &str) -> Option<DataType> {
@@ -106,6 +154,12 @@ impl Connector for SQLiteConnection {
 
             DataType::Decimal128(_, _) => "TEXT",
             DataType::Decimal256(_, _) => "TEXT",
+
+            // nested types are encoded as JSON, see [SQLiteConnection::with_json_nested]
+            DataType::List(_) => "TEXT",
+            DataType::LargeList(_) => "TEXT",
+            DataType::FixedSizeList(_, _) => "TEXT",
+            DataType::Struct(_) => "TEXT",
             _ => return None,
         };
         Some(s.to_string())
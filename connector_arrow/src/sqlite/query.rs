@@ -5,8 +5,8 @@ use arrow::datatypes::*;
 use itertools::{zip_eq, Itertools};
 use rusqlite::types::{Type, Value};
 
-use crate::api::{Connector, Statement};
-use crate::types::FixedSizeBinaryType;
+use crate::api::{ArrowValue, Connector, ResultReader, Statement};
+use crate::types::{FixedSizeBinaryType, ListType};
 use crate::util::transport::{self, Produce, ProduceTy};
 use crate::util::{collect_rows_to_arrow, CellReader, RowsReader};
 use crate::util::{ArrayCellRef, ArrowReader};
@@ -18,12 +18,83 @@ pub struct SQLiteStatement<'conn> {
     pub stmt: rusqlite::Statement<'conn>,
 }
 
+impl SQLiteStatement<'_> {
+    /// Execute this statement once, producing a reader that pulls rows from SQLite lazily
+    /// and yields a [RecordBatch] every `batch_size` rows.
+    ///
+    /// Unlike [Statement::start_batch], this does not buffer the whole result set.
+    /// The schema is inferred from declared types of the result columns and from the values
+    /// in the first batch. Columns that are not table columns and contain only NULLs in the
+    /// first batch are read as [DataType::Null].
+    pub fn start_stream(
+        &mut self,
+        args: (&RecordBatch, usize),
+        batch_size: usize,
+    ) -> Result<SQLiteBatchStream<'_>, ConnectorError> {
+        if batch_size == 0 {
+            return Err(ConnectorError::InvalidBatchSize);
+        }
+        let column_count = self.stmt.column_count();
+
+        // types of table columns are declared
+        let decl_types = self
+            .stmt
+            .columns()
+            .iter()
+            .map(|c| c.decl_type().and_then(decl_ty_to_storage_class))
+            .collect_vec();
+        let names = self.stmt.column_names().into_iter().map(String::from);
+        let names = names.collect_vec();
+
+        // query
+        let args = bind_args(args)?;
+        let args = args.iter().map(|x| x as &dyn rusqlite::ToSql).collect_vec();
+        let mut rows = self.stmt.query(args.as_slice())?;
+
+        // read the first batch, for inferring types of remaining columns
+        let mut first_batch = Vec::with_capacity(batch_size);
+        while first_batch.len() < batch_size {
+            let Some(row) = rows.next()? else {
+                break;
+            };
+            first_batch.push(read_row(row, column_count)?);
+        }
+        let schema = infer_schema(names, decl_types, &first_batch)?;
+
+        Ok(SQLiteBatchStream {
+            schema,
+            rows: SQLiteRowsStream {
+                buffered: first_batch.into_iter(),
+                rows,
+                column_count,
+            },
+            batch_size,
+        })
+    }
+}
+
 impl<'conn> Statement<'conn> for SQLiteStatement<'conn> {
     type Reader<'task>
         = ArrowReader
     where
         Self: 'task;
 
+    fn start_named(
+        &mut self,
+        params: &[(&str, &dyn ArrowValue)],
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        // names include the prefix (`:`, `@` or `$`)
+        let names: Vec<String> = (1..=self.stmt.parameter_count())
+            .map(|index| {
+                let name = self.stmt.parameter_name(index).unwrap_or_default();
+                name.get(1..).unwrap_or_default().to_string()
+            })
+            .collect();
+
+        let batch = crate::params::named_to_record_batch(&names, params)?;
+        self.start_batch((&batch, 0))
+    }
+
     fn start_batch<'p>(
         &mut self,
         args: (&RecordBatch, usize),
@@ -31,11 +102,7 @@ impl<'conn> Statement<'conn> for SQLiteStatement<'conn> {
         let column_count = self.stmt.column_count();
 
         // args
-        let arg_cells = ArrayCellRef::vec_from_batch(args.0, args.1);
-        let mut args: Vec<Value> = Vec::with_capacity(arg_cells.len());
-        for cell in arg_cells {
-            transport::transport(cell.field, &cell, &mut args)?;
-        }
+        let args = bind_args(args)?;
         let args = args.iter().map(|x| x as &dyn rusqlite::ToSql).collect_vec();
 
         // query
@@ -45,18 +112,14 @@ impl<'conn> Statement<'conn> for SQLiteStatement<'conn> {
             // read all of the rows into a buffer
             let mut rows = Vec::with_capacity(1024);
             while let Some(row_ref) = rows_iter.next()? {
-                let mut row = Vec::with_capacity(column_count);
-                for col_index in 0..column_count {
-                    let value = row_ref.get::<_, Value>(col_index).unwrap();
-                    row.push(value);
-                }
-                rows.push(row);
+                rows.push(read_row(row_ref, column_count)?);
             }
             rows
         };
 
         // infer schema
-        let schema = infer_schema(&self.stmt, &rows, column_count)?;
+        let names = self.stmt.column_names().into_iter().map(String::from);
+        let schema = infer_schema(names.collect(), vec![None; column_count], &rows)?;
 
         // iterate over rows and convert into arrow
         let row_count = rows.len();
@@ -69,13 +132,50 @@ impl<'conn> Statement<'conn> for SQLiteStatement<'conn> {
     }
 }
 
+fn bind_args(args: (&RecordBatch, usize)) -> Result<Vec<Value>, ConnectorError> {
+    let arg_cells = ArrayCellRef::vec_from_batch(args.0, args.1);
+    let mut values: Vec<Value> = Vec::with_capacity(arg_cells.len());
+    for cell in arg_cells {
+        transport::transport(cell.field, &cell, &mut values)?;
+    }
+    Ok(values)
+}
+
+fn read_row(row_ref: &rusqlite::Row, column_count: usize) -> Result<Vec<Value>, ConnectorError> {
+    let mut row = Vec::with_capacity(column_count);
+    for col_index in 0..column_count {
+        row.push(row_ref.get::<_, Value>(col_index)?);
+    }
+    Ok(row)
+}
+
+/// Schema of the statement result, based on declared types of result columns.
+/// Columns without declared type (i.e. expressions) are [DataType::Null].
+pub(super) fn stmt_decl_schema(stmt: &rusqlite::Statement) -> SchemaRef {
+    let fields = stmt.columns().into_iter().map(|c| {
+        let ty = c.decl_type().and_then(decl_ty_to_storage_class);
+        Field::new(c.name(), ty.unwrap_or(DataType::Null), true)
+    });
+    Arc::new(Schema::new(fields.collect::<Vec<_>>()))
+}
+
+/// Maps a declared column type to the arrow type of its storage class.
+/// Returns None for `NUMERIC` affinity, which can be stored either as INTEGER or REAL.
+fn decl_ty_to_storage_class(decl_ty: &str) -> Option<DataType> {
+    let ty = super::types::decl_ty_to_arrow(decl_ty, "", "").ok()?;
+    Some(match ty {
+        DataType::LargeUtf8 => DataType::Utf8,
+        DataType::LargeBinary => DataType::Binary,
+        ty => ty,
+    })
+}
+
+/// Infers types of columns that are not known yet from the first non-null value.
 fn infer_schema(
-    stmt: &rusqlite::Statement,
-    rows: &Vec<Vec<Value>>,
-    column_count: usize,
+    names: Vec<String>,
+    mut types: Vec<Option<DataType>>,
+    rows: &[Vec<Value>],
 ) -> Result<Arc<arrow::datatypes::Schema>, ConnectorError> {
-    let mut types = vec![None; column_count];
-
     for row in rows {
         let mut all_known = true;
 
@@ -100,8 +200,8 @@ fn infer_schema(
         }
     }
 
-    let mut fields = Vec::with_capacity(column_count);
-    for (name, ty) in zip_eq(stmt.column_names(), types) {
+    let mut fields = Vec::with_capacity(names.len());
+    for (name, ty) in zip_eq(names, types) {
         let ty = ty.unwrap_or(DataType::Null);
 
         let nullable = true; // dynamic type system FTW
@@ -111,6 +211,53 @@ fn infer_schema(
     Ok(Arc::new(arrow::datatypes::Schema::new(fields)))
 }
 
+pub struct SQLiteBatchStream<'stmt> {
+    schema: SchemaRef,
+    rows: SQLiteRowsStream<'stmt>,
+    batch_size: usize,
+}
+
+impl<'stmt> ResultReader<'stmt> for SQLiteBatchStream<'stmt> {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        Ok(self.schema.clone())
+    }
+}
+
+impl Iterator for SQLiteBatchStream<'_> {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        crate::util::next_batch_from_rows(&self.schema, &mut self.rows, self.batch_size).transpose()
+    }
+}
+
+struct SQLiteRowsStream<'stmt> {
+    /// rows that were read for schema inference
+    buffered: std::vec::IntoIter<Vec<Value>>,
+    rows: rusqlite::Rows<'stmt>,
+    column_count: usize,
+}
+
+impl<'stmt> RowsReader<'stmt> for SQLiteRowsStream<'stmt> {
+    type CellReader<'rows>
+        = SQLiteCellReader
+    where
+        Self: 'rows;
+
+    fn next_row(&mut self) -> Result<Option<Self::CellReader<'_>>, ConnectorError> {
+        let row = match self.buffered.next() {
+            Some(row) => Some(row),
+            None => match self.rows.next()? {
+                Some(row_ref) => Some(read_row(row_ref, self.column_count)?),
+                None => None,
+            },
+        };
+        Ok(row.map(|row| SQLiteCellReader {
+            row: row.into_iter(),
+        }))
+    }
+}
+
 pub struct SQLiteRowsReader {
     rows: std::vec::IntoIter<Vec<Value>>,
 }
@@ -145,55 +292,72 @@ impl CellReader<'_> for SQLiteCellReader {
 
 impl Produce<'_> for Value {}
 
+/// The type of a column is inferred from its first non-null value,
+/// but SQLite allows values of any storage class in any column.
+fn mixed_storage_classes(value: &Value) -> ConnectorError {
+    ConnectorError::DataSchemaMismatch(format!(
+        "column contains values of multiple storage classes, including {}",
+        value.data_type()
+    ))
+}
+
 impl ProduceTy<'_, Int64Type> for Value {
     fn produce(self) -> Result<i64, ConnectorError> {
-        unimplemented!()
+        ProduceTy::<Int64Type>::produce_opt(self).and_then(|x| {
+            x.ok_or_else(|| ConnectorError::DataSchemaMismatch("unexpected NULL".into()))
+        })
     }
     fn produce_opt(self) -> Result<Option<i64>, ConnectorError> {
-        Ok(match self {
-            Self::Null => None,
-            Self::Integer(v) => Some(v),
-            _ => panic!("SQLite schema not inferred correctly"),
-        })
+        match self {
+            Self::Null => Ok(None),
+            Self::Integer(v) => Ok(Some(v)),
+            v => Err(mixed_storage_classes(&v)),
+        }
     }
 }
 
 impl ProduceTy<'_, Float64Type> for Value {
     fn produce(self) -> Result<f64, ConnectorError> {
-        unimplemented!()
+        ProduceTy::<Float64Type>::produce_opt(self).and_then(|x| {
+            x.ok_or_else(|| ConnectorError::DataSchemaMismatch("unexpected NULL".into()))
+        })
     }
     fn produce_opt(self) -> Result<Option<f64>, ConnectorError> {
-        Ok(match self {
-            Self::Null => None,
-            Self::Real(v) => Some(v),
-            _ => panic!("SQLite schema not inferred correctly"),
-        })
+        match self {
+            Self::Null => Ok(None),
+            Self::Real(v) => Ok(Some(v)),
+            v => Err(mixed_storage_classes(&v)),
+        }
     }
 }
 
 impl ProduceTy<'_, Utf8Type> for Value {
     fn produce(self) -> Result<String, ConnectorError> {
-        unimplemented!()
+        ProduceTy::<Utf8Type>::produce_opt(self).and_then(|x| {
+            x.ok_or_else(|| ConnectorError::DataSchemaMismatch("unexpected NULL".into()))
+        })
     }
     fn produce_opt(self) -> Result<Option<String>, ConnectorError> {
-        Ok(match self {
-            Self::Null => None,
-            Self::Text(v) => Some(v),
-            _ => panic!("SQLite schema not inferred correctly"),
-        })
+        match self {
+            Self::Null => Ok(None),
+            Self::Text(v) => Ok(Some(v)),
+            v => Err(mixed_storage_classes(&v)),
+        }
     }
 }
 
 impl ProduceTy<'_, BinaryType> for Value {
     fn produce(self) -> Result<Vec<u8>, ConnectorError> {
-        unimplemented!()
+        ProduceTy::<BinaryType>::produce_opt(self).and_then(|x| {
+            x.ok_or_else(|| ConnectorError::DataSchemaMismatch("unexpected NULL".into()))
+        })
     }
     fn produce_opt(self) -> Result<Option<Vec<u8>>, ConnectorError> {
-        Ok(match self {
-            Self::Null => None,
-            Self::Blob(v) => Some(v),
-            _ => panic!("SQLite schema not inferred correctly"),
-        })
+        match self {
+            Self::Null => Ok(None),
+            Self::Blob(v) => Ok(Some(v)),
+            v => Err(mixed_storage_classes(&v)),
+        }
     }
 }
 
@@ -232,5 +396,8 @@ crate::impl_produce_unsupported!(
         LargeUtf8Type,
         Decimal128Type,
         Decimal256Type,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
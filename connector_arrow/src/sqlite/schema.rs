@@ -2,7 +2,7 @@ use arrow::datatypes::{Field, Schema, SchemaRef};
 use itertools::Itertools;
 use std::sync::Arc;
 
-use crate::api::{Connector, SchemaEdit, SchemaGet};
+use crate::api::{Connector, ForeignKey, SchemaEdit, SchemaGet};
 use crate::errors::{ConnectorError, TableCreateError, TableDropError};
 use crate::util::escape::escaped_ident;
 
@@ -11,7 +11,11 @@ use super::SQLiteConnection;
 
 impl SchemaGet for SQLiteConnection {
     fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
-        let query_tables = "SELECT name FROM sqlite_master WHERE type = 'table';";
+        // tables prefixed with sqlite_ are reserved for internal use
+        let query_tables = r"
+            SELECT name FROM sqlite_master
+            WHERE type = 'table' AND name NOT LIKE 'sqlite\_%' ESCAPE '\'
+        ";
         let mut statement = self.inner.prepare(query_tables)?;
         let mut tables_res = statement.query(())?;
 
@@ -44,22 +48,122 @@ impl SchemaGet for SQLiteConnection {
 
         Ok(Arc::new(Schema::new(fields)))
     }
+
+    /// Identifiers are case-insensitive in SQLite.
+    fn table_exists(&mut self, name: &str) -> Result<bool, ConnectorError> {
+        let tables = self.table_list()?;
+        Ok(tables.iter().any(|t| t.eq_ignore_ascii_case(name)))
+    }
+
+    fn table_primary_key(&mut self, table_name: &str) -> Result<Vec<String>, ConnectorError> {
+        let query_columns = format!("PRAGMA table_info({});", escaped_ident(table_name));
+        let mut statement = self.inner.prepare(&query_columns)?;
+        let mut columns_res = statement.query(())?;
+
+        // pk is 1-based position of the column within the primary key, or 0
+        let mut key = Vec::new();
+        while let Some(row) = columns_res.next()? {
+            let name: String = row.get(1)?;
+            let pk: u32 = row.get(5)?;
+            if pk > 0 {
+                key.push((pk, name));
+            }
+        }
+        key.sort();
+        Ok(key.into_iter().map(|(_, name)| name).collect())
+    }
+
+    fn table_foreign_keys(&mut self, table_name: &str) -> Result<Vec<ForeignKey>, ConnectorError> {
+        let query = format!("PRAGMA foreign_key_list({});", escaped_ident(table_name));
+        let mut statement = self.inner.prepare(&query)?;
+        let mut rows = statement.query(())?;
+
+        // one row per column, grouped by id and ordered by seq within the key
+        let mut keys: Vec<(u32, ForeignKey, Vec<Option<String>>)> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id: u32 = row.get(0)?;
+            let referenced_table: String = row.get(2)?;
+            let column: String = row.get(3)?;
+            let referenced_column: Option<String> = row.get(4)?;
+
+            if keys.last().is_none_or(|(last_id, _, _)| *last_id != id) {
+                let key = ForeignKey {
+                    columns: Vec::new(),
+                    referenced_table,
+                    referenced_columns: Vec::new(),
+                };
+                keys.push((id, key, Vec::new()));
+            }
+            let (_, key, referenced_columns) = keys.last_mut().unwrap();
+            key.columns.push(column);
+            referenced_columns.push(referenced_column);
+        }
+        drop(rows);
+        drop(statement);
+
+        // ids are assigned in reverse order of declaration
+        keys.reverse();
+        keys.into_iter()
+            .map(|(_, mut key, referenced_columns)| {
+                // referenced columns are omitted when the key references the primary key
+                key.referenced_columns = match referenced_columns.into_iter().collect() {
+                    Some(columns) => columns,
+                    None => self.table_primary_key(&key.referenced_table)?,
+                };
+                Ok(key)
+            })
+            .collect()
+    }
 }
 
 impl SchemaEdit for SQLiteConnection {
     fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
-        table_create(self, name, schema)
+        table_create(self, name, schema, false)
+    }
+
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        table_create(self, name, schema, true)
     }
 
     fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
         table_drop(self, name)
     }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let ddl = format!(
+            "ALTER TABLE {} RENAME TO {};",
+            escaped_ident(from),
+            escaped_ident(to)
+        );
+        self.inner.execute(&ddl, ())?;
+        Ok(())
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        // RENAME COLUMN was added in SQLite 3.25.0
+        if rusqlite::version_number() < 3_025_000 {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::sqlite (SQLite older than 3.25.0)",
+                feature: "rename_column",
+            });
+        }
+
+        let ddl = format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {};",
+            escaped_ident(table),
+            escaped_ident(from),
+            escaped_ident(to)
+        );
+        self.inner.execute(&ddl, ())?;
+        Ok(())
+    }
 }
 
 pub(crate) fn table_create(
     conn: &mut SQLiteConnection,
     name: &str,
     schema: SchemaRef,
+    temporary: bool,
 ) -> Result<(), TableCreateError> {
     let column_defs = schema
         .fields()
@@ -74,7 +178,11 @@ pub(crate) fn table_create(
         })
         .join(",");
 
-    let ddl = format!("CREATE TABLE {} ({column_defs});", escaped_ident(name));
+    let temp = if temporary { "TEMPORARY " } else { "" };
+    let ddl = format!(
+        "CREATE {temp}TABLE {} ({column_defs});",
+        escaped_ident(name)
+    );
 
     let res = conn.inner.execute(&ddl, ());
     match res {
@@ -96,3 +204,16 @@ pub(crate) fn table_drop(conn: &mut SQLiteConnection, name: &str) -> Result<(),
         Err(e) => Err(TableDropError::Connector(ConnectorError::SQLite(e))),
     }
 }
+
+/// Lists the main database and attached databases.
+pub(super) fn list_catalogs(conn: &mut SQLiteConnection) -> Result<Vec<String>, ConnectorError> {
+    // contains columns: seq, name, file
+    let mut statement = conn.inner.prepare("PRAGMA database_list;")?;
+    let mut rows = statement.query(())?;
+
+    let mut names = Vec::new();
+    while let Some(row) = rows.next()? {
+        names.push(row.get(1)?);
+    }
+    Ok(names)
+}
@@ -2,6 +2,41 @@ use arrow::datatypes::DataType;
 
 use crate::ConnectorError;
 
+/// Type affinity of a SQLite column, which is the type recommended for values
+/// stored in that column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Affinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+impl Affinity {
+    /// Determines affinity from the declared type of a column.
+    ///
+    /// Rules are applied in order, matching substrings of the declared type. This
+    /// also covers type names of STRICT tables, except for `ANY`, which results in
+    /// `NUMERIC` affinity, like it does in ordinary tables.
+    ///
+    /// See: https://sqlite.org/datatype3.html#determination_of_column_affinity
+    pub fn from_decl_ty(decl_ty: &str) -> Self {
+        let ty = decl_ty.to_ascii_uppercase();
+        if ty.contains("INT") {
+            Affinity::Integer
+        } else if ty.contains("CHAR") || ty.contains("CLOB") || ty.contains("TEXT") {
+            Affinity::Text
+        } else if ty.contains("BLOB") || ty.trim().is_empty() {
+            Affinity::Blob
+        } else if ty.contains("REAL") || ty.contains("FLOA") || ty.contains("DOUB") {
+            Affinity::Real
+        } else {
+            Affinity::Numeric
+        }
+    }
+}
+
 pub fn decl_ty_to_arrow(decl_ty: &str, col: &str, table: &str) -> Result<DataType, ConnectorError> {
     // SQLite does not have a "required" column type, only "suggest" column type,
     // known a column type affinity. This function takes this affinity and tries to
@@ -9,28 +44,15 @@ pub fn decl_ty_to_arrow(decl_ty: &str, col: &str, table: &str) -> Result<DataTyp
     // a) one could make a column with affinity TEXT, but then store INTEGER in it,
     // b) one could declare a column with arbitrary data type that does not map into any affinity,
     // c) NUMERIC affinity can either be INT or REAL
-
-    // See: https://sqlite.org/datatype3.html#determination_of_column_affinity
-    let ty = decl_ty.to_ascii_uppercase();
-    if ty.contains("INT") {
-        return Ok(DataType::Int64);
+    match Affinity::from_decl_ty(decl_ty) {
+        Affinity::Integer => Ok(DataType::Int64),
+        Affinity::Text => Ok(DataType::LargeUtf8),
+        Affinity::Blob => Ok(DataType::LargeBinary),
+        Affinity::Real => Ok(DataType::Float64),
+        Affinity::Numeric => Err(ConnectorError::IncompatibleSchema {
+            table_name: table.to_string(),
+            message: format!("column `{col}` was declared with type `{decl_ty}`, which results in `NUMERIC` affinity, which is not supported."),
+            hint: Some("Supported types are INTEGER, REAL, TEXT and BLOB".to_string())
+        }),
     }
-
-    if ty.contains("CHAR") || ty.contains("CLOB") || ty.contains("TEXT") {
-        return Ok(DataType::LargeUtf8);
-    }
-
-    if ty.contains("BLOB") {
-        return Ok(DataType::LargeBinary);
-    }
-
-    if ty.contains("REAL") || ty.contains("FLOA") || ty.contains("DOUB") {
-        return Ok(DataType::Float64);
-    }
-
-    Err(ConnectorError::IncompatibleSchema {
-        table_name: table.to_string(),
-        message: format!("column `{col}` was declared with type `{ty}`, which results in `NUMERIC` affinity, which is not supported."),
-        hint: Some("Supported types are INTEGER, REAL, TEXT and BLOB".to_string())
-    })
 }
@@ -4,16 +4,15 @@ use std::sync::Arc;
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 use futures::{AsyncRead, AsyncWrite};
-use itertools::{zip_eq, Itertools};
 use tiberius::numeric::Numeric;
 use tiberius::{BulkLoadRequest, Client, ColumnData, TokenRow};
 use tokio::runtime::Runtime;
 
 use crate::api::Append;
-use crate::types::{FixedSizeBinaryType, NullType};
+use crate::types::{FixedSizeBinaryType, ListType, NullType};
 use crate::util::escape::escaped_ident;
 use crate::util::transport::{Consume, ConsumeTy};
-use crate::util::ArrayCellRef;
+use crate::util::{coerce, ArrayCellRef};
 use crate::{impl_consume_unsupported, ConnectorError};
 
 pub struct TiberiusAppender<'c, S: AsyncRead + AsyncWrite + Unpin + Send> {
@@ -42,24 +41,10 @@ impl<'conn, S: AsyncRead + AsyncWrite + Unpin + Send> TiberiusAppender<'conn, S>
 
 impl<'conn, S: AsyncRead + AsyncWrite + Unpin + Send> Append<'conn> for TiberiusAppender<'conn, S> {
     fn append(&mut self, batch: RecordBatch) -> Result<(), ConnectorError> {
-        let schema = batch.schema();
-        let mut row_ref = zip_eq(batch.columns(), schema.fields())
-            .map(|(array, field)| ArrayCellRef {
-                array,
-                field,
-                row_number: 0,
-            })
-            .collect_vec();
-
+        let batch = coerce::decode_dictionaries(batch)?;
+        let mut row_ref = ArrayCellRef::vec_from_batch(&batch, 0);
         for row_number in 0..batch.num_rows() {
-            let mut tb_row = TokenRow::with_capacity(row_ref.len());
-            let mut buffer = Vec::with_capacity(1);
-            for cell_ref in &mut row_ref {
-                cell_ref.row_number = row_number;
-
-                crate::util::transport::transport(cell_ref.field, &*cell_ref, &mut buffer)?;
-                tb_row.push(buffer.pop().unwrap());
-            }
+            let tb_row = token_row(&mut row_ref, row_number)?;
 
             let f = self.bulk_load.send(tb_row);
             self.rt.block_on(f)?;
@@ -74,6 +59,22 @@ impl<'conn, S: AsyncRead + AsyncWrite + Unpin + Send> Append<'conn> for Tiberius
     }
 }
 
+/// Converts a row of the batch into a row for bulk load.
+pub(super) fn token_row(
+    row_ref: &mut [ArrayCellRef],
+    row_number: usize,
+) -> Result<TokenRow<'static>, ConnectorError> {
+    let mut tb_row = TokenRow::with_capacity(row_ref.len());
+    let mut buffer = Vec::with_capacity(1);
+    for cell_ref in row_ref {
+        cell_ref.row_number = row_number;
+
+        crate::util::transport::transport(cell_ref.field, &*cell_ref, &mut buffer)?;
+        tb_row.push(buffer.pop().unwrap());
+    }
+    Ok(tb_row)
+}
+
 impl Consume for Vec<ColumnData<'static>> {}
 
 macro_rules! impl_consume_ty {
@@ -146,6 +147,9 @@ impl_consume_unsupported!(
         BinaryType,
         LargeBinaryType,
         FixedSizeBinaryType,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
 
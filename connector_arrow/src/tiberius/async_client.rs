@@ -0,0 +1,59 @@
+use futures::{AsyncRead, AsyncWrite, StreamExt};
+
+use arrow::record_batch::RecordBatch;
+
+use crate::api::AsyncConnector;
+use crate::util::escape::escaped_ident;
+use crate::util::{coerce, ArrayCellRef, ArrowReader};
+use crate::ConnectorError;
+
+use super::{append, query, TiberiusConnection};
+
+/// Uses the client directly, without blocking on the runtime of the connection.
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> AsyncConnector for TiberiusConnection<S> {
+    async fn query(&mut self, query: &str) -> Result<ArrowReader, ConnectorError> {
+        let mut stream = self.client.query(query, &[]).await?;
+
+        let columns = stream.columns().await?;
+        let schema = super::types::get_result_schema(columns)?;
+        stream.next().await;
+
+        let mut rows = Vec::new();
+        while let Some(item) = stream.next().await {
+            match item? {
+                tiberius::QueryItem::Row(row) => rows.push(row),
+                tiberius::QueryItem::Metadata(_) => {
+                    return Err(ConnectorError::MultipleResultSets);
+                }
+            }
+        }
+
+        query::rows_into_reader(schema, rows)
+    }
+
+    async fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let result = self.client.execute(query, &[]).await?;
+        Ok(result.total())
+    }
+
+    /// Rows are written with a bulk load.
+    async fn append(
+        &mut self,
+        table_name: &str,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(), ConnectorError> {
+        let table_name = escaped_ident(table_name).to_string();
+        let mut bulk_load = self.client.bulk_insert(&table_name).await?;
+
+        for batch in batches {
+            let batch = coerce::decode_dictionaries(batch)?;
+            let mut row_ref = ArrayCellRef::vec_from_batch(&batch, 0);
+            for row_number in 0..batch.num_rows() {
+                let tb_row = append::token_row(&mut row_ref, row_number)?;
+                bulk_load.send(tb_row).await?;
+            }
+        }
+        bulk_load.finalize().await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 mod append;
+mod async_client;
 mod query;
 mod schema;
 mod types;
@@ -9,7 +10,7 @@ use itertools::Itertools;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-use crate::api::Connector;
+use crate::api::{Connector, ResultReader, Statement};
 use crate::ConnectorError;
 
 pub struct TiberiusConnection<S: AsyncRead + AsyncWrite + Unpin + Send> {
@@ -49,10 +50,72 @@ impl<S: AsyncRead + AsyncWrite + Unpin + Send> Connector for TiberiusConnection<
         })
     }
 
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        // MSSQL does not support LIMIT
+        let query = query.trim().trim_end_matches(';');
+        let query = format!("SELECT TOP 0 * FROM ({query}) AS _q");
+
+        let mut stmt = self.query(&query)?;
+        let mut reader = stmt.start([])?;
+        reader.get_schema()
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let result = self.rt.block_on(self.client.execute(query, &[]))?;
+        Ok(result.total())
+    }
+
+    fn execute_batch(&mut self, statements: &[&str]) -> Result<Vec<u64>, ConnectorError> {
+        let tx = [
+            "BEGIN TRANSACTION",
+            "COMMIT TRANSACTION",
+            "ROLLBACK TRANSACTION",
+        ];
+        crate::dml::execute_batch(self, statements, tx)
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        self.execute("BEGIN TRANSACTION")?;
+        Ok(())
+    }
+
+    fn end_transaction(&mut self, commit: bool) -> Result<(), ConnectorError> {
+        self.execute(if commit {
+            "COMMIT TRANSACTION"
+        } else {
+            "ROLLBACK TRANSACTION"
+        })?;
+        Ok(())
+    }
+
     fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
         append::TiberiusAppender::new(self.rt.clone(), &mut self.client, table_name)
     }
 
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &arrow::record_batch::RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        let placeholder = |i| format!("@P{i}");
+        let ident = crate::util::escape::escaped_ident;
+        crate::dml::delete_by_keys(self, table_name, key_columns, keys, placeholder, ident)
+    }
+
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &arrow::record_batch::RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        crate::dml::merge(self, table_name, key_columns, batch, |i| format!("@P{i}"))
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        schema::list_catalogs(self)
+    }
+
     #[allow(clippy::get_first)]
     fn type_db_into_arrow(ty: &str) -> Option<DataType> {
         let ty = ty.to_lowercase();
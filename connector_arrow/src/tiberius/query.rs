@@ -7,7 +7,7 @@ use tokio::runtime::Runtime;
 
 use crate::api::{ResultReader, Statement};
 use crate::impl_produce_unsupported;
-use crate::types::{ArrowType, FixedSizeBinaryType, NullType};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType, NullType};
 use crate::util::transport::{self, ProduceTy};
 use crate::util::ArrayCellRef;
 use crate::util::{self, transport::Produce};
@@ -109,6 +109,36 @@ impl<'s> util::RowsReader<'s> for TiberiusStream<'s> {
     }
 }
 
+/// Converts rows that were already fetched, i.e. by [crate::api::AsyncConnector::query].
+pub(super) fn rows_into_reader(
+    schema: SchemaRef,
+    rows: Vec<tiberius::Row>,
+) -> Result<util::ArrowReader, ConnectorError> {
+    let mut rows = TiberiusRowVec {
+        rows: rows.into_iter(),
+    };
+    let batches = util::collect_rows_to_arrow(schema.clone(), &mut rows, 1024)?;
+    Ok(util::ArrowReader::new(schema, batches))
+}
+
+struct TiberiusRowVec {
+    rows: std::vec::IntoIter<tiberius::Row>,
+}
+
+impl util::RowsReader<'_> for TiberiusRowVec {
+    type CellReader<'row>
+        = TiberiusCellReader
+    where
+        Self: 'row;
+
+    fn next_row(&mut self) -> Result<Option<Self::CellReader<'_>>, ConnectorError> {
+        Ok(self
+            .rows
+            .next()
+            .map(|row| TiberiusCellReader { row, cell: 0 }))
+    }
+}
+
 struct TiberiusCellReader {
     row: tiberius::Row,
     cell: usize,
@@ -200,6 +230,9 @@ impl_produce_unsupported!(
         Decimal128Type,
         Decimal256Type,
         BinaryType,
+        BinaryViewType,
+        StringViewType,
+        ListType,
     )
 );
 
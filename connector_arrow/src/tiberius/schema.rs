@@ -122,4 +122,41 @@ impl<S: AsyncRead + AsyncWrite + Unpin + Send> SchemaEdit for super::TiberiusCon
             Err(e) => Err(TableDropError::Connector(e.into())),
         }
     }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        // new name is taken literally, so it must not be escaped
+        let from = escaped_ident(from).to_string();
+        let params: [&dyn tiberius::ToSql; 2] = [&from, &to];
+        let res = self.client.execute("EXEC sp_rename @P1, @P2", &params);
+        self.rt.block_on(res)?;
+        Ok(())
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        let from = format!("{}.{}", escaped_ident(table), escaped_ident(from));
+        let params: [&dyn tiberius::ToSql; 2] = [&from, &to];
+        let res = self
+            .client
+            .execute("EXEC sp_rename @P1, @P2, 'COLUMN'", &params);
+        self.rt.block_on(res)?;
+        Ok(())
+    }
+}
+
+pub(super) fn list_catalogs<S: AsyncRead + AsyncWrite + Unpin + Send>(
+    conn: &mut super::TiberiusConnection<S>,
+) -> Result<Vec<String>, ConnectorError> {
+    let res = conn
+        .client
+        .query("SELECT name FROM sys.databases ORDER BY name", &[]);
+    let res = conn.rt.block_on(res)?;
+
+    let res = res.into_first_result();
+    let res = conn.rt.block_on(res)?;
+
+    let names = res
+        .into_iter()
+        .map(|r| r.get::<&str, _>(0).unwrap().to_string())
+        .collect_vec();
+    Ok(names)
 }
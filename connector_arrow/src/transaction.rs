@@ -0,0 +1,215 @@
+//! Explicit transactions, started with [Connector::transaction].
+
+use std::path::Path;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::api::{
+    AppenderOptions, CancelToken, Connector, ForeignKey, QueryCost, ReadOptions, SchemaEdit,
+    SchemaGet, StatementCacheStats,
+};
+use crate::errors::{ConnectorError, TableCreateError, TableDropError};
+
+/// A guard of a transaction on a connection.
+///
+/// Queries, statements and appends of the guard are executed within the transaction.
+/// Changes are applied by [Transaction::commit] and discarded by [Transaction::rollback]
+/// or when the guard is dropped.
+///
+/// Appenders of the guard write into the transaction, so [crate::api::Append::finish]
+/// does not commit their rows.
+pub struct Transaction<'conn, C: Connector> {
+    conn: &'conn mut C,
+    done: bool,
+}
+
+impl<'conn, C: Connector> Transaction<'conn, C> {
+    pub(crate) fn begin(conn: &'conn mut C) -> Result<Self, ConnectorError> {
+        conn.begin_transaction()?;
+        Ok(Transaction { conn, done: false })
+    }
+
+    pub fn commit(mut self) -> Result<(), ConnectorError> {
+        self.done = true;
+        self.conn.end_transaction(true)
+    }
+
+    pub fn rollback(mut self) -> Result<(), ConnectorError> {
+        self.done = true;
+        self.conn.end_transaction(false)
+    }
+}
+
+impl<C: Connector> Drop for Transaction<'_, C> {
+    fn drop(&mut self) {
+        if !self.done {
+            if let Err(e) = self.conn.end_transaction(false) {
+                log::warn!("rollback failed: {e}");
+            }
+        }
+    }
+}
+
+impl<C: Connector> Connector for Transaction<'_, C> {
+    type Stmt<'a>
+        = C::Stmt<'a>
+    where
+        Self: 'a;
+
+    type Append<'a>
+        = C::Append<'a>
+    where
+        Self: 'a;
+
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        self.conn.query(query)
+    }
+
+    fn query_with<'a>(
+        &'a mut self,
+        query: &str,
+        options: ReadOptions,
+    ) -> Result<Self::Stmt<'a>, ConnectorError> {
+        self.conn.query_with(query, options)
+    }
+
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        self.conn.execute(query)
+    }
+
+    /// Executes statements one by one. If any of the statements fails,
+    /// the error is returned, but the transaction is not rolled back.
+    fn execute_batch(&mut self, statements: &[&str]) -> Result<Vec<u64>, ConnectorError> {
+        statements.iter().map(|s| self.conn.execute(s)).collect()
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        self.conn.query_schema(query)
+    }
+
+    fn append<'a>(&'a mut self, table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        self.conn.append(table_name)
+    }
+
+    fn append_with<'a>(
+        &'a mut self,
+        table_name: &str,
+        options: AppenderOptions,
+    ) -> Result<Self::Append<'a>, ConnectorError> {
+        self.conn.append_with(table_name, options)
+    }
+
+    fn delete_by_keys(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        keys: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        self.conn.delete_by_keys(table_name, key_columns, keys)
+    }
+
+    fn merge(
+        &mut self,
+        table_name: &str,
+        key_columns: &[&str],
+        batch: &RecordBatch,
+    ) -> Result<(), ConnectorError> {
+        // connection is already in a transaction, which makes delete and append atomic
+        crate::dml::delete_and_append(self.conn, table_name, key_columns, batch)
+    }
+
+    fn estimate_row_count(&mut self, table_name: &str) -> Result<u64, ConnectorError> {
+        self.conn.estimate_row_count(table_name)
+    }
+
+    fn explain_cost(&mut self, query: &str) -> Result<QueryCost, ConnectorError> {
+        self.conn.explain_cost(query)
+    }
+
+    fn load_parquet(&mut self, table_name: &str, path: &Path) -> Result<u64, ConnectorError> {
+        self.conn.load_parquet(table_name, path)
+    }
+
+    fn set_statement_cache_size(&mut self, size: usize) -> Result<(), ConnectorError> {
+        self.conn.set_statement_cache_size(size)
+    }
+
+    fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+        self.conn.statement_cache_stats()
+    }
+
+    fn cancel_token(&self) -> Result<CancelToken, ConnectorError> {
+        self.conn.cancel_token()
+    }
+
+    fn list_catalogs(&mut self) -> Result<Vec<String>, ConnectorError> {
+        self.conn.list_catalogs()
+    }
+
+    fn begin_transaction(&mut self) -> Result<(), ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::transaction",
+            feature: "nested transactions",
+        })
+    }
+
+    fn end_transaction(&mut self, _commit: bool) -> Result<(), ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::transaction",
+            feature: "nested transactions",
+        })
+    }
+
+    fn type_db_into_arrow(database_ty: &str) -> Option<DataType> {
+        C::type_db_into_arrow(database_ty)
+    }
+
+    fn type_arrow_into_db(ty: &DataType) -> Option<String> {
+        C::type_arrow_into_db(ty)
+    }
+}
+
+impl<C: Connector + SchemaGet> SchemaGet for Transaction<'_, C> {
+    fn table_list(&mut self) -> Result<Vec<String>, ConnectorError> {
+        self.conn.table_list()
+    }
+
+    fn table_get(&mut self, name: &str) -> Result<SchemaRef, ConnectorError> {
+        self.conn.table_get(name)
+    }
+
+    fn table_exists(&mut self, name: &str) -> Result<bool, ConnectorError> {
+        self.conn.table_exists(name)
+    }
+
+    fn table_primary_key(&mut self, name: &str) -> Result<Vec<String>, ConnectorError> {
+        self.conn.table_primary_key(name)
+    }
+
+    fn table_foreign_keys(&mut self, name: &str) -> Result<Vec<ForeignKey>, ConnectorError> {
+        self.conn.table_foreign_keys(name)
+    }
+}
+
+impl<C: Connector + SchemaEdit> SchemaEdit for Transaction<'_, C> {
+    fn table_create(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        self.conn.table_create(name, schema)
+    }
+
+    fn table_create_temp(&mut self, name: &str, schema: SchemaRef) -> Result<(), TableCreateError> {
+        self.conn.table_create_temp(name, schema)
+    }
+
+    fn table_drop(&mut self, name: &str) -> Result<(), TableDropError> {
+        self.conn.table_drop(name)
+    }
+
+    fn rename_table(&mut self, from: &str, to: &str) -> Result<(), ConnectorError> {
+        self.conn.rename_table(from, to)
+    }
+
+    fn rename_column(&mut self, table: &str, from: &str, to: &str) -> Result<(), ConnectorError> {
+        self.conn.rename_column(table, from, to)
+    }
+}
@@ -0,0 +1,237 @@
+//! Provides `connector_arrow` traits for [Trino](https://trino.io) (and other gateways that
+//! implement its [client protocol](https://trino.io/docs/current/develop/client-protocol.html)),
+//! using HTTP client [ureq](https://docs.rs/ureq).
+//!
+//! [TrinoConnection] is read-only: each query is submitted to the coordinator and its results
+//! are paged through, one batch per page of data.
+//!
+//! ```ignore
+//! let mut conn = TrinoConnection::new("http://localhost:8080", "user").with_catalog("tpch");
+//!
+//! let batches = connector_arrow::query(&mut conn, "SELECT * FROM tiny.nation")?;
+//! ```
+
+mod query;
+mod types;
+
+#[doc(hidden)]
+pub use query::{TrinoReader, TrinoStatement};
+
+use arrow::datatypes::{DataType, SchemaRef};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::api::{unimplemented, Connector, ResultReader, Statement};
+use crate::ConnectorError;
+
+pub struct TrinoConnection {
+    agent: ureq::Agent,
+    url: String,
+    user: String,
+    catalog: Option<String>,
+    schema: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum TrinoError {
+    #[error(transparent)]
+    Http(#[from] ureq::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("{name}: {message}")]
+    Query { name: String, message: String },
+}
+
+impl TrinoConnection {
+    /// Creates a connection to the coordinator at `url` (i.e. `http://localhost:8080`),
+    /// which will submit queries as `user`.
+    pub fn new(url: impl Into<String>, user: impl Into<String>) -> Self {
+        TrinoConnection {
+            agent: ureq::Agent::new_with_defaults(),
+            url: url.into().trim_end_matches('/').to_string(),
+            user: user.into(),
+            catalog: None,
+            schema: None,
+        }
+    }
+
+    /// Catalog of unqualified table names.
+    pub fn with_catalog(mut self, catalog: impl Into<String>) -> Self {
+        self.catalog = Some(catalog.into());
+        self
+    }
+
+    /// Schema of unqualified table names. Requires a catalog.
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    pub fn inner_mut(&mut self) -> &mut ureq::Agent {
+        &mut self.agent
+    }
+
+    /// Submits a query and returns the first page of its results.
+    fn submit(&self, query: &str) -> Result<QueryResults, ConnectorError> {
+        let mut request = self
+            .agent
+            .post(format!("{}/v1/statement", self.url))
+            .header("X-Trino-User", &self.user);
+        if let Some(catalog) = &self.catalog {
+            request = request.header("X-Trino-Catalog", catalog);
+        }
+        if let Some(schema) = &self.schema {
+            request = request.header("X-Trino-Schema", schema);
+        }
+        let mut response = request.send(query).map_err(TrinoError::from)?;
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(TrinoError::from)?;
+        QueryResults::parse(&text)
+    }
+
+    /// Fetches the next page of results of a query.
+    fn fetch(&self, next_uri: &str) -> Result<QueryResults, ConnectorError> {
+        let mut response = self
+            .agent
+            .get(next_uri)
+            .header("X-Trino-User", &self.user)
+            .call()
+            .map_err(TrinoError::from)?;
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(TrinoError::from)?;
+        QueryResults::parse(&text)
+    }
+}
+
+/// A page of query results, as returned by the coordinator.
+struct QueryResults {
+    next_uri: Option<String>,
+
+    /// Names and types of the result columns. Might be missing from the first pages.
+    columns: Option<Vec<(String, String)>>,
+
+    /// Rows of this page.
+    data: Vec<Vec<Value>>,
+
+    update_count: Option<u64>,
+}
+
+impl QueryResults {
+    fn parse(text: &str) -> Result<Self, ConnectorError> {
+        let value: Value = serde_json::from_str(text).map_err(TrinoError::from)?;
+
+        if let Some(error) = value.get("error") {
+            let get_str = |key: &str| error.get(key).and_then(Value::as_str).unwrap_or_default();
+            return Err(TrinoError::Query {
+                name: get_str("errorName").to_string(),
+                message: get_str("message").to_string(),
+            }
+            .into());
+        }
+
+        let columns = value
+            .get("columns")
+            .and_then(Value::as_array)
+            .map(|columns| {
+                let columns = columns.iter().map(|column| {
+                    let name = column.get("name").and_then(Value::as_str);
+                    let ty = column.get("type").and_then(Value::as_str);
+                    (
+                        name.unwrap_or_default().to_string(),
+                        ty.unwrap_or_default().to_string(),
+                    )
+                });
+                columns.collect()
+            });
+
+        let data = match value.get("data") {
+            Some(Value::Array(rows)) => rows
+                .iter()
+                .map(|row| match row {
+                    Value::Array(cells) => cells.clone(),
+                    _ => Vec::new(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(QueryResults {
+            next_uri: value
+                .get("nextUri")
+                .and_then(Value::as_str)
+                .map(String::from),
+            columns,
+            data,
+            update_count: value.get("updateCount").and_then(Value::as_u64),
+        })
+    }
+}
+
+impl Connector for TrinoConnection {
+    type Stmt<'conn>
+        = TrinoStatement<'conn>
+    where
+        Self: 'conn;
+
+    type Append<'conn>
+        = unimplemented::Appender
+    where
+        Self: 'conn;
+
+    fn query<'a>(&'a mut self, query: &str) -> Result<Self::Stmt<'a>, ConnectorError> {
+        Ok(TrinoStatement {
+            conn: self,
+            query: query.to_string(),
+        })
+    }
+
+    /// Returns the number of affected rows, as reported by the coordinator,
+    /// or 0 for statements that do not report it (i.e. DDL).
+    fn execute(&mut self, query: &str) -> Result<u64, ConnectorError> {
+        let mut stmt = self.query(query)?;
+        let mut reader = stmt.start([])?;
+        for batch in &mut reader {
+            batch?;
+        }
+        Ok(reader.update_count().unwrap_or_default())
+    }
+
+    fn execute_batch(&mut self, _statements: &[&str]) -> Result<Vec<u64>, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::trino",
+            feature: "execute_batch",
+        })
+    }
+
+    fn query_schema(&mut self, query: &str) -> Result<SchemaRef, ConnectorError> {
+        let query = query.trim().trim_end_matches(';');
+        let query = format!("SELECT * FROM ({query}) AS _q LIMIT 0");
+
+        let mut stmt = self.query(&query)?;
+        let mut reader = stmt.start([])?;
+        reader.get_schema()
+    }
+
+    fn append<'a>(&'a mut self, _table_name: &str) -> Result<Self::Append<'a>, ConnectorError> {
+        Err(ConnectorError::NotSupported {
+            connector_name: "connector_arrow::trino",
+            feature: "append",
+        })
+    }
+
+    /// Types with time zones, `varbinary` and containers (`array`, `map`, `row`) are not mapped
+    /// and are read as `Utf8`, in the JSON encoding of the Trino client protocol.
+    fn type_db_into_arrow(ty: &str) -> Option<DataType> {
+        types::type_db_into_arrow(ty)
+    }
+
+    fn type_arrow_into_db(ty: &DataType) -> Option<String> {
+        types::type_arrow_into_db(ty)
+    }
+}
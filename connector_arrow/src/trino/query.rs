@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use serde_json::Value;
+
+use crate::api::{ResultReader, Statement};
+use crate::ConnectorError;
+
+use super::{types, QueryResults, TrinoConnection};
+
+pub struct TrinoStatement<'conn> {
+    pub(super) conn: &'conn TrinoConnection,
+    pub(super) query: String,
+}
+
+impl<'conn> Statement<'conn> for TrinoStatement<'conn> {
+    type Reader<'stmt>
+        = TrinoReader<'stmt>
+    where
+        Self: 'stmt;
+
+    fn start_batch(
+        &mut self,
+        args: (&RecordBatch, usize),
+    ) -> Result<Self::Reader<'_>, ConnectorError> {
+        if args.0.num_columns() > 0 {
+            return Err(ConnectorError::NotSupported {
+                connector_name: "connector_arrow::trino",
+                feature: "query parameters",
+            });
+        }
+
+        let mut reader = TrinoReader {
+            conn: self.conn,
+            next_uri: None,
+            schema: None,
+            pages: VecDeque::new(),
+            update_count: None,
+        };
+        reader.accept(self.conn.submit(&self.query)?)?;
+        Ok(reader)
+    }
+}
+
+pub struct TrinoReader<'stmt> {
+    conn: &'stmt TrinoConnection,
+
+    /// URI of the next page of results. None when all pages have been fetched.
+    next_uri: Option<String>,
+    schema: Option<SchemaRef>,
+
+    /// Pages that have been fetched, but not converted yet.
+    pages: VecDeque<Vec<Vec<Value>>>,
+    update_count: Option<u64>,
+}
+
+impl TrinoReader<'_> {
+    /// Number of rows affected by the statement, if it was reported.
+    pub(super) fn update_count(&self) -> Option<u64> {
+        self.update_count
+    }
+
+    fn accept(&mut self, results: QueryResults) -> Result<(), ConnectorError> {
+        if let (None, Some(columns)) = (&self.schema, results.columns) {
+            let fields = columns
+                .into_iter()
+                .map(|(name, ty)| types::trino_field_to_arrow(name, &ty))
+                .collect::<Vec<_>>();
+            self.schema = Some(Arc::new(Schema::new(fields)));
+        }
+        if !results.data.is_empty() {
+            self.pages.push_back(results.data);
+        }
+        self.next_uri = results.next_uri;
+        self.update_count = results.update_count.or(self.update_count);
+        Ok(())
+    }
+
+    /// Fetches the next page. Returns false when there are no more pages.
+    fn advance(&mut self) -> Result<bool, ConnectorError> {
+        let Some(next_uri) = self.next_uri.take() else {
+            return Ok(false);
+        };
+        let results = self.conn.fetch(&next_uri)?;
+        self.accept(results)?;
+        Ok(true)
+    }
+}
+
+impl ResultReader<'_> for TrinoReader<'_> {
+    /// Columns are known only after the query has started executing, so
+    /// this might wait for the coordinator. Statements without a result
+    /// set have an empty schema.
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        while self.schema.is_none() {
+            if !self.advance()? {
+                break;
+            }
+        }
+        Ok(self
+            .schema
+            .clone()
+            .unwrap_or_else(|| Arc::new(Schema::empty())))
+    }
+}
+
+impl Iterator for TrinoReader<'_> {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(page) = self.pages.pop_front() {
+                return Some(
+                    self.get_schema()
+                        .and_then(|schema| types::page_into_batch(schema, page)),
+                );
+            }
+            match self.advance() {
+                Ok(true) => continue,
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
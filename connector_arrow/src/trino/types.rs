@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray};
+use arrow::compute::CastOptions;
+use arrow::datatypes::{DataType, Field, SchemaRef, TimeUnit};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::ConnectorError;
+
+pub fn type_db_into_arrow(ty: &str) -> Option<DataType> {
+    let ty = ty.to_lowercase();
+    if ty.ends_with("with time zone") {
+        return None;
+    }
+
+    // parse arguments
+    let (name, args) = if let Some((name, args)) = ty.split_once('(') {
+        let args = args.trim_end_matches(')').split(',');
+        (
+            name,
+            args.filter_map(|a| a.trim().parse::<u8>().ok())
+                .collect_vec(),
+        )
+    } else {
+        (ty.as_str(), vec![])
+    };
+
+    Some(match name {
+        "boolean" => DataType::Boolean,
+        "tinyint" => DataType::Int8,
+        "smallint" => DataType::Int16,
+        "integer" | "int" => DataType::Int32,
+        "bigint" => DataType::Int64,
+        "real" => DataType::Float32,
+        "double" => DataType::Float64,
+        "decimal" => {
+            let precision = args.first().copied().unwrap_or(38);
+            let scale = args.get(1).copied().unwrap_or(0);
+            DataType::Decimal128(precision, scale as i8)
+        }
+
+        "varchar" | "char" | "json" | "uuid" | "ipaddress" => DataType::Utf8,
+
+        "date" => DataType::Date32,
+        // precision of times and timestamps defaults to milliseconds
+        "time" => match args.first().copied().unwrap_or(3) {
+            0..=6 => DataType::Time64(TimeUnit::Microsecond),
+            7..=9 => DataType::Time64(TimeUnit::Nanosecond),
+            _ => return None,
+        },
+        "timestamp" => {
+            let unit = match args.first().copied().unwrap_or(3) {
+                0..=3 => TimeUnit::Millisecond,
+                4..=6 => TimeUnit::Microsecond,
+                7..=9 => TimeUnit::Nanosecond,
+                _ => return None,
+            };
+            DataType::Timestamp(unit, None)
+        }
+        _ => return None,
+    })
+}
+
+pub fn type_arrow_into_db(ty: &DataType) -> Option<String> {
+    Some(match ty {
+        DataType::Boolean => "boolean".into(),
+        DataType::Int8 => "tinyint".into(),
+        DataType::Int16 => "smallint".into(),
+        DataType::Int32 => "integer".into(),
+        DataType::Int64 => "bigint".into(),
+        DataType::Float32 => "real".into(),
+        DataType::Float64 => "double".into(),
+        DataType::Decimal128(precision, scale) if *scale >= 0 => {
+            format!("decimal({precision}, {scale})")
+        }
+
+        DataType::Utf8 | DataType::LargeUtf8 => "varchar".into(),
+        DataType::Binary | DataType::LargeBinary => "varbinary".into(),
+
+        DataType::Date32 => "date".into(),
+        DataType::Time64(TimeUnit::Microsecond) => "time(6)".into(),
+        DataType::Time64(TimeUnit::Nanosecond) => "time(9)".into(),
+        DataType::Timestamp(unit, None) => match unit {
+            TimeUnit::Second => "timestamp(0)".into(),
+            TimeUnit::Millisecond => "timestamp(3)".into(),
+            TimeUnit::Microsecond => "timestamp(6)".into(),
+            TimeUnit::Nanosecond => "timestamp(9)".into(),
+        },
+        _ => return None,
+    })
+}
+
+pub fn trino_field_to_arrow(name: String, db_ty: &str) -> Field {
+    let mut metadata = HashMap::new();
+
+    // if we cannot map to an arrow type, read the JSON encoding as text
+    let data_type = type_db_into_arrow(db_ty).unwrap_or_else(|| {
+        metadata.insert(crate::api::METADATA_DB_TYPE.to_string(), db_ty.to_string());
+        DataType::Utf8
+    });
+
+    Field::new(name, data_type, true).with_metadata(metadata)
+}
+
+/// Converts rows of a page into a batch.
+///
+/// Values are collected as text and then cast into the types of the schema,
+/// so any value that cannot be cast produces an error.
+pub fn page_into_batch(
+    schema: SchemaRef,
+    rows: Vec<Vec<Value>>,
+) -> Result<RecordBatch, ConnectorError> {
+    let options = CastOptions {
+        safe: false,
+        ..CastOptions::default()
+    };
+
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for (index, field) in schema.fields().iter().enumerate() {
+        let texts = rows.iter().map(|row| match row.get(index)? {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        });
+        let texts: ArrayRef = Arc::new(texts.collect::<StringArray>());
+
+        columns.push(arrow::compute::cast_with_options(
+            &texts,
+            field.data_type(),
+            &options,
+        )?);
+    }
+
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    Ok(RecordBatch::try_new_with_options(
+        schema, columns, &options,
+    )?)
+}
+
+#[test]
+fn test_type_db_into_arrow() {
+    assert_eq!(type_db_into_arrow("integer"), Some(DataType::Int32));
+    assert_eq!(
+        type_db_into_arrow("decimal(10, 2)"),
+        Some(DataType::Decimal128(10, 2))
+    );
+    assert_eq!(type_db_into_arrow("varchar(5)"), Some(DataType::Utf8));
+    assert_eq!(
+        type_db_into_arrow("timestamp(6)"),
+        Some(DataType::Timestamp(TimeUnit::Microsecond, None))
+    );
+    assert_eq!(
+        type_db_into_arrow("timestamp"),
+        Some(DataType::Timestamp(TimeUnit::Millisecond, None))
+    );
+    assert_eq!(type_db_into_arrow("timestamp(3) with time zone"), None);
+    assert_eq!(type_db_into_arrow("array(integer)"), None);
+}
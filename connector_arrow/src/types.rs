@@ -14,6 +14,10 @@ pub struct NullType;
 // arrow crate does not define fixed-sized binary array type
 pub struct FixedSizeBinaryType;
 
+// arrow crate does not define list array type
+// Values are the elements of a single list.
+pub struct ListType;
+
 impl ArrowType for NullType {
     type Native = ();
 }
@@ -70,9 +74,18 @@ impl ArrowType for LargeBinaryType {
 impl ArrowType for FixedSizeBinaryType {
     type Native = Vec<u8>;
 }
+impl ArrowType for ListType {
+    type Native = arrow::array::ArrayRef;
+}
+impl ArrowType for BinaryViewType {
+    type Native = Vec<u8>;
+}
 impl ArrowType for Utf8Type {
     type Native = String;
 }
 impl ArrowType for LargeUtf8Type {
     type Native = String;
 }
+impl ArrowType for StringViewType {
+    type Native = String;
+}
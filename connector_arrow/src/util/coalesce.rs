@@ -0,0 +1,67 @@
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+
+use crate::api::ResultReader;
+use crate::errors::ConnectorError;
+
+/// Wraps a reader so that consecutive small batches are concatenated until they contain
+/// at least `min_rows` rows. The last batch may contain fewer rows.
+///
+/// Useful for readers that yield many tiny batches (i.e. one row per batch).
+/// Batches that are already large enough are passed through without copying.
+pub fn coalesce<'stmt, R: ResultReader<'stmt>>(reader: R, min_rows: usize) -> Coalesce<R> {
+    Coalesce {
+        inner: reader,
+        min_rows,
+        pending: Vec::new(),
+        pending_rows: 0,
+    }
+}
+
+/// Reader returned by [coalesce].
+pub struct Coalesce<R> {
+    inner: R,
+    min_rows: usize,
+    pending: Vec<RecordBatch>,
+    pending_rows: usize,
+}
+
+impl<R> Coalesce<R> {
+    fn flush(&mut self) -> Option<Result<RecordBatch, ConnectorError>> {
+        self.pending_rows = 0;
+        let batches = std::mem::take(&mut self.pending);
+        match batches.len() {
+            0 => None,
+            1 => batches.into_iter().next().map(Ok),
+            _ => {
+                let schema = batches[0].schema();
+                let res = arrow::compute::concat_batches(&schema, &batches);
+                Some(res.map_err(ConnectorError::from))
+            }
+        }
+    }
+}
+
+impl<'stmt, R: ResultReader<'stmt>> Iterator for Coalesce<R> {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending_rows < self.min_rows {
+            match self.inner.next() {
+                Some(Ok(batch)) => {
+                    self.pending_rows += batch.num_rows();
+                    self.pending.push(batch);
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => break,
+            }
+        }
+        self.flush()
+    }
+}
+
+impl<'stmt, R: ResultReader<'stmt>> ResultReader<'stmt> for Coalesce<R> {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        self.inner.get_schema()
+    }
+}
@@ -1,7 +1,15 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use arrow::array::temporal_conversions::as_datetime_with_timezone;
+use arrow::array::timezone::Tz;
 use arrow::array::{Array, ArrayRef, AsArray, Float32Builder, Float64Builder};
-use arrow::datatypes::{DataType, Field, Float16Type, Schema, SchemaRef};
+use arrow::compute::CastOptions;
+use arrow::datatypes::{
+    ArrowTimestampType, DataType, Field, FieldRef, Float16Type, Schema, SchemaRef, TimeUnit,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType,
+};
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use itertools::Itertools;
@@ -52,12 +60,29 @@ where
             (DataType::Float16, DataType::Float32) => Ok(coerce_float_16_to_32(&array)),
             (DataType::Float16, DataType::Float64) => Ok(coerce_float_16_to_64(&array)),
             (DataType::Time32(_), DataType::Int64) => coerce_time32_to_int64(&array),
+            (DataType::Int64, DataType::Time32(_)) => coerce_int64_to_time32(&array, &new_ty),
             _ => arrow::compute::cast(&array, &new_ty),
         },
         None => Ok(array),
     }
 }
 
+/// Replaces dictionary-encoded columns with columns of their values,
+/// so they can be transported as the value type.
+pub fn decode_dictionaries(batch: RecordBatch) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    let is_dictionary = |f: &FieldRef| matches!(f.data_type(), DataType::Dictionary(_, _));
+    if !schema.fields().iter().any(is_dictionary) {
+        return Ok(batch);
+    }
+
+    let value_type = |ty: &DataType| match ty {
+        DataType::Dictionary(_, value) => Some(value.as_ref().clone()),
+        _ => None,
+    };
+    coerce_batch(&batch, value_type, None)
+}
+
 pub fn coerce_schema<F>(
     schema: SchemaRef,
     coerce_fn: F,
@@ -124,3 +149,142 @@ fn coerce_time32_to_int64(array: &dyn Array) -> Result<ArrayRef, ArrowError> {
     let array = arrow::compute::cast(array, &DataType::Int32)?;
     arrow::compute::cast(&array, &DataType::Int64)
 }
+
+fn coerce_int64_to_time32(array: &dyn Array, ty: &DataType) -> Result<ArrayRef, ArrowError> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let array = arrow::compute::cast_with_options(array, &DataType::Int32, &options)?;
+    arrow::compute::cast(&array, ty)
+}
+
+/// Casts columns of the batches to types of fields of `schema`, matching them by position.
+///
+/// This is needed for data stores that do not retain the Arrow type of a column
+/// (i.e. SQLite stores `Time64` as a bare `INTEGER`) when the original schema is known.
+pub fn cast_to_schema(
+    batches: &[RecordBatch],
+    schema: SchemaRef,
+) -> Result<Vec<RecordBatch>, ArrowError> {
+    batches
+        .iter()
+        .map(|batch| {
+            let columns = batch.columns().iter().zip(schema.fields()).map(|(a, f)| {
+                let ty = f.data_type();
+                coerce_array(a.clone(), |t| (t != ty).then(|| ty.clone()))
+            });
+            RecordBatch::try_new(schema.clone(), columns.try_collect()?)
+        })
+        .try_collect()
+}
+
+/// Casts columns of the batch, matched by name, to the given types.
+/// Other columns are not changed.
+///
+/// Values that cannot be converted (i.e. text that is not a valid date) are errors,
+/// and are not replaced by nulls.
+pub fn cast_columns(
+    batch: &RecordBatch,
+    types: &HashMap<String, DataType>,
+) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    if let Some(name) = types.keys().find(|n| schema.column_with_name(n).is_none()) {
+        return Err(ArrowError::SchemaError(format!(
+            "column `{name}` is not in the result"
+        )));
+    }
+
+    let options = CastOptions {
+        safe: false,
+        ..CastOptions::default()
+    };
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (field, array) in schema.fields().iter().zip(batch.columns()) {
+        match types.get(field.name()) {
+            Some(ty) if ty != field.data_type() => {
+                if !arrow::compute::can_cast_types(field.data_type(), ty) {
+                    return Err(ArrowError::CastError(format!(
+                        "cannot cast column `{}` from {} to {ty}",
+                        field.name(),
+                        field.data_type()
+                    )));
+                }
+                fields.push(Field::new(field.name(), ty.clone(), field.is_nullable()));
+                columns.push(arrow::compute::cast_with_options(array, ty, &options)?);
+            }
+            _ => {
+                fields.push(Field::clone(field));
+                columns.push(array.clone());
+            }
+        }
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Converts timestamp columns that have a time zone into time zone `tz`.
+///
+/// Instants are not changed, only the time zone of the data type is replaced.
+/// When `naive` is set, values are instead shifted to the wall-clock time in `tz`
+/// and the time zone is removed. Timestamp columns without a time zone are not changed.
+///
+/// `tz` is either a fixed offset (i.e. `+02:00`) or a name from the IANA database
+/// (i.e. `America/New_York`). Shifting to a named time zone requires feature `chrono-tz`.
+pub fn convert_timestamps_to(
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+    tz: &str,
+    naive: bool,
+) -> Result<(SchemaRef, Vec<RecordBatch>), ArrowError> {
+    let convert_fn = |ty: &DataType| match ty {
+        DataType::Timestamp(unit, Some(_)) => {
+            let tz = if naive { None } else { Some(tz.into()) };
+            Some(DataType::Timestamp(*unit, tz))
+        }
+        _ => None,
+    };
+
+    let schema = coerce_schema(schema, convert_fn, None);
+    let batches = batches
+        .iter()
+        .map(|batch| {
+            let columns = batch.columns().iter().map(|array| {
+                if convert_fn(array.data_type()).is_none() {
+                    return Ok(array.clone());
+                }
+                if naive {
+                    timestamp_to_local(array, &tz.parse()?)
+                } else {
+                    coerce_array(array.clone(), convert_fn)
+                }
+            });
+            RecordBatch::try_new(schema.clone(), columns.try_collect()?)
+        })
+        .try_collect()?;
+    Ok((schema, batches))
+}
+
+/// Shifts values of a timestamp array to wall-clock time in `tz` and removes its time zone.
+fn timestamp_to_local(array: &ArrayRef, tz: &Tz) -> Result<ArrayRef, ArrowError> {
+    fn shift<T: ArrowTimestampType>(array: &ArrayRef, tz: &Tz) -> Result<ArrayRef, ArrowError> {
+        let array = array.as_primitive::<T>().try_unary::<_, T, _>(|value| {
+            as_datetime_with_timezone::<T>(value, *tz)
+                .and_then(|dt| T::make_value(dt.naive_local()))
+                .ok_or_else(|| ArrowError::ComputeError(format!("timestamp out of range: {value}")))
+        })?;
+        Ok(Arc::new(array))
+    }
+
+    match array.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => shift::<TimestampSecondType>(array, tz),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            shift::<TimestampMillisecondType>(array, tz)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            shift::<TimestampMicrosecondType>(array, tz)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => shift::<TimestampNanosecondType>(array, tz),
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,62 @@
+use std::io::{Read, Write};
+
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+
+use crate::api::{Append, Connector, ResultReader, SchemaEdit};
+use crate::errors::{ConnectorError, TableCreateError};
+
+/// Write the schema and all batches of a result into an Arrow IPC stream.
+///
+/// Useful for serving query results over a socket or saving them into a file.
+/// Returns the inner writer, after the stream has been finished.
+pub fn write_ipc_stream<'a, R, W>(mut reader: R, writer: W) -> Result<W, ConnectorError>
+where
+    R: ResultReader<'a>,
+    W: Write,
+{
+    let schema = reader.get_schema()?;
+
+    let mut writer = StreamWriter::try_new(writer, &schema)?;
+    for batch in reader {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+
+    Ok(writer.into_inner()?)
+}
+
+/// Read an Arrow IPC stream and append all of its batches into a table.
+///
+/// When `create` is set, the table is first created with the schema of the stream,
+/// unless it already exists. Batches are appended with a single appender.
+/// Returns the number of appended rows.
+pub fn read_ipc_stream_into<C, R>(
+    conn: &mut C,
+    table_name: &str,
+    reader: R,
+    create: bool,
+) -> Result<u64, ConnectorError>
+where
+    C: Connector + SchemaEdit,
+    R: Read,
+{
+    let reader = StreamReader::try_new(reader, None)?;
+
+    if create {
+        match conn.table_create(table_name, reader.schema()) {
+            Ok(()) | Err(TableCreateError::TableExists) => {}
+            Err(TableCreateError::Connector(e)) => return Err(e),
+        }
+    }
+
+    let mut appender = conn.append(table_name)?;
+    let mut row_count = 0;
+    for batch in reader {
+        let batch = batch?;
+        row_count += batch.num_rows() as u64;
+        appender.append(batch)?;
+    }
+    appender.finish()?;
+    Ok(row_count)
+}
@@ -2,16 +2,28 @@
 //! Used by database client implementations.
 
 mod arrow_reader;
+mod coalesce;
 pub mod coerce;
 pub mod decimal;
+#[cfg(feature = "ipc")]
+mod ipc;
 mod row_collect;
 mod row_reader;
 mod row_writer;
+mod schema_diff;
+mod timeout;
 pub mod transport;
 
 pub(crate) mod escape;
 
 pub use arrow_reader::ArrowReader;
+pub use coalesce::{coalesce, Coalesce};
+#[cfg(feature = "ipc")]
+pub use ipc::{read_ipc_stream_into, write_ipc_stream};
 pub use row_collect::{collect_rows_to_arrow, next_batch_from_rows, CellReader, RowsReader};
 pub use row_reader::ArrayCellRef;
 pub use row_writer::ArrowRowWriter;
+pub use schema_diff::{schema_diff, schema_diff_with_renames, SchemaChange};
+pub use timeout::TimeoutReader;
+
+pub(crate) use timeout::Timer;
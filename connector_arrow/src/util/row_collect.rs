@@ -1,4 +1,4 @@
-use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{Field, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use itertools::Itertools;
 
@@ -6,6 +6,8 @@ use crate::errors::ConnectorError;
 use crate::util::{transport, ArrowRowWriter};
 
 /// Get next [RecordBatch] from a row-major reader.
+///
+/// Row indices in [ConnectorError::DataOutOfRangeAt] are relative to the start of this batch.
 pub fn next_batch_from_rows<'stmt, T: RowsReader<'stmt>>(
     schema: &SchemaRef,
     rows_reader: &mut T,
@@ -13,14 +15,15 @@ pub fn next_batch_from_rows<'stmt, T: RowsReader<'stmt>>(
 ) -> Result<Option<RecordBatch>, ConnectorError> {
     let mut writer = ArrowRowWriter::new(schema.clone(), batch_size);
 
-    for _ in 0..batch_size {
+    for row in 0..batch_size {
         if let Some(mut cell_reader) = rows_reader.next_row()? {
             writer.prepare_for_batch(1)?;
 
             for field in &schema.fields {
                 let cell_ref = cell_reader.next_cell();
 
-                transport::transport(field, cell_ref.unwrap(), &mut writer)?;
+                transport::transport(field, cell_ref.unwrap(), &mut writer)
+                    .map_err(|e| with_position(e, field, row))?;
             }
         } else {
             break;
@@ -44,6 +47,7 @@ pub fn collect_rows_to_arrow<'stmt, T: RowsReader<'stmt>>(
     let mut writer = ArrowRowWriter::new(schema.clone(), batch_size);
     log::debug!("reading rows");
 
+    let mut row = 0;
     while let Some(mut row_reader) = rows_reader.next_row()? {
         writer.prepare_for_batch(1)?;
 
@@ -53,12 +57,25 @@ pub fn collect_rows_to_arrow<'stmt, T: RowsReader<'stmt>>(
             let cell_ref = row_reader.next_cell();
 
             log::debug!("transporting cell: {cell_ref:?}");
-            transport::transport(field, cell_ref.unwrap(), &mut writer)?;
+            transport::transport(field, cell_ref.unwrap(), &mut writer)
+                .map_err(|e| with_position(e, field, row))?;
         }
+        row += 1;
     }
     writer.finish()
 }
 
+/// Adds column name and row index to [ConnectorError::DataOutOfRange].
+fn with_position(err: ConnectorError, field: &Field, row: usize) -> ConnectorError {
+    match err {
+        ConnectorError::DataOutOfRange => ConnectorError::DataOutOfRangeAt {
+            column: field.name().clone(),
+            row,
+        },
+        e => e,
+    }
+}
+
 /// Iterator over rows.
 // Cannot be an actual iterator, because of lifetime requirements (I think).
 pub trait RowsReader<'stmt> {
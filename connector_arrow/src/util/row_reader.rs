@@ -2,7 +2,7 @@ use arrow::array::{ArrayRef, AsArray, RecordBatch};
 use arrow::datatypes::*;
 use itertools::zip_eq;
 
-use crate::types::{ArrowType, FixedSizeBinaryType};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType};
 use crate::ConnectorError;
 
 use super::transport::{Produce, ProduceTy};
@@ -168,3 +168,42 @@ impl<'r> ProduceTy<'r, LargeUtf8Type> for &ArrayCellRef<'r> {
         })
     }
 }
+impl<'r> ProduceTy<'r, BinaryViewType> for &ArrayCellRef<'r> {
+    fn produce(self) -> Result<Vec<u8>, ConnectorError> {
+        let array = self.array.as_binary_view();
+        Ok(array.value(self.row_number).to_vec())
+    }
+    fn produce_opt(self) -> Result<Option<<BinaryViewType as ArrowType>::Native>, ConnectorError> {
+        Ok(if self.array.is_null(self.row_number) {
+            None
+        } else {
+            Some(ProduceTy::<BinaryViewType>::produce(self)?)
+        })
+    }
+}
+impl<'r> ProduceTy<'r, StringViewType> for &ArrayCellRef<'r> {
+    fn produce(self) -> Result<String, ConnectorError> {
+        let array = self.array.as_string_view();
+        Ok(array.value(self.row_number).to_string())
+    }
+    fn produce_opt(self) -> Result<Option<<StringViewType as ArrowType>::Native>, ConnectorError> {
+        Ok(if self.array.is_null(self.row_number) {
+            None
+        } else {
+            Some(ProduceTy::<StringViewType>::produce(self)?)
+        })
+    }
+}
+impl<'r> ProduceTy<'r, ListType> for &ArrayCellRef<'r> {
+    fn produce(self) -> Result<ArrayRef, ConnectorError> {
+        let array = self.array.as_list::<i32>();
+        Ok(array.value(self.row_number))
+    }
+    fn produce_opt(self) -> Result<Option<<ListType as ArrowType>::Native>, ConnectorError> {
+        Ok(if self.array.is_null(self.row_number) {
+            None
+        } else {
+            Some(ProduceTy::<ListType>::produce(self)?)
+        })
+    }
+}
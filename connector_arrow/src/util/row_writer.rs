@@ -1,11 +1,17 @@
 use std::any::Any;
 
-use arrow::array::{ArrayBuilder, ArrayRef, FixedSizeBinaryBuilder};
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayBuilder, ArrayRef, BinaryViewBuilder, FixedSizeBinaryBuilder, ListArray,
+    StringViewBuilder,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 
 use crate::errors::ConnectorError;
-use crate::types::{ArrowType, FixedSizeBinaryType, NullType};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType, NullType};
 use crate::util::transport::{Consume, ConsumeTy};
 
 /// Receives values row-by-row and passes them to [ArrayBuilder]s,
@@ -24,6 +30,10 @@ pub struct ArrowRowWriter {
     rows_reserved: usize,
     /// Number of rows allocated within builders.
     rows_capacity: usize,
+
+    /// First error of a value that could not be written.
+    /// [ConsumeTy] cannot return errors, so it is returned on next flush.
+    error: Option<ConnectorError>,
 }
 
 impl ArrowRowWriter {
@@ -35,6 +45,7 @@ impl ArrowRowWriter {
             builders: None,
             rows_reserved: 0,
             rows_capacity: 0,
+            error: None,
 
             schema,
             min_batch_size,
@@ -66,7 +77,7 @@ impl ArrowRowWriter {
             .schema
             .fields
             .iter()
-            .map(|f| arrow::array::make_builder(f.data_type(), to_allocate))
+            .map(|f| make_builder(f.data_type(), to_allocate))
             .collect();
 
         self.builders = Some(builders);
@@ -76,6 +87,9 @@ impl ArrowRowWriter {
     }
 
     fn flush(&mut self) -> Result<(), ConnectorError> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
         let Some(mut builders) = self.builders.take() else {
             return Ok(());
         };
@@ -99,10 +113,121 @@ impl ArrowRowWriter {
         let builders = self.builders.as_mut().unwrap();
         builders[col].as_any_mut()
     }
+
+    fn push_list(&mut self, list: Option<ArrayRef>) {
+        let Some(builder) = self.next_builder().downcast_mut::<ListArrayBuilder>() else {
+            self.error.get_or_insert(ConnectorError::DataSchemaMismatch(
+                "list value for a column that is not a list".into(),
+            ));
+            return;
+        };
+
+        // elements of all lists are concatenated, so they must be of the same type
+        if let Some(values) = &list {
+            let expected = builder.field.data_type();
+            if values.data_type() != expected {
+                let err = ConnectorError::DataSchemaMismatch(format!(
+                    "list elements of type {}, expected {expected}",
+                    values.data_type()
+                ));
+                builder.lists.push(None);
+                self.error.get_or_insert(err);
+                return;
+            }
+        }
+        builder.lists.push(list);
+    }
 }
 
 impl Consume for ArrowRowWriter {}
 
+/// Same as [arrow::array::make_builder], but with a builder for lists
+/// that accepts whole arrays of list elements.
+fn make_builder(data_type: &DataType, capacity: usize) -> Box<dyn ArrayBuilder> {
+    match data_type {
+        DataType::List(field) => Box::new(ListArrayBuilder::new(field.clone(), capacity)),
+        // not supported by arrow's make_builder
+        DataType::Utf8View => Box::new(StringViewBuilder::with_capacity(capacity)),
+        DataType::BinaryView => Box::new(BinaryViewBuilder::with_capacity(capacity)),
+        _ => arrow::array::make_builder(data_type, capacity),
+    }
+}
+
+/// Builds a [ListArray] from arrays of elements of each of the lists.
+struct ListArrayBuilder {
+    field: FieldRef,
+    lists: Vec<Option<ArrayRef>>,
+}
+
+impl ListArrayBuilder {
+    fn new(field: FieldRef, capacity: usize) -> Self {
+        ListArrayBuilder {
+            field,
+            lists: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn build(&self) -> ArrayRef {
+        let lengths = self.lists.iter().map(|l| l.as_ref().map_or(0, |a| a.len()));
+        let offsets = OffsetBuffer::from_lengths(lengths);
+
+        let values: Vec<&dyn Array> = self.lists.iter().flatten().map(|a| a.as_ref()).collect();
+        let values = if values.is_empty() {
+            arrow::array::new_empty_array(self.field.data_type())
+        } else {
+            // elements are checked to be of the same type by ArrowRowWriter::push_list
+            arrow::compute::concat(&values).expect("list elements of mismatched types")
+        };
+
+        let nulls: Vec<bool> = self.lists.iter().map(Option::is_some).collect();
+        let nulls = NullBuffer::from(nulls);
+        Arc::new(ListArray::new(
+            self.field.clone(),
+            offsets,
+            values,
+            Some(nulls),
+        ))
+    }
+}
+
+impl ArrayBuilder for ListArrayBuilder {
+    fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        let array = self.build();
+        self.lists.clear();
+        array
+    }
+
+    fn finish_cloned(&self) -> ArrayRef {
+        self.build()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_box_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl ConsumeTy<ListType> for ArrowRowWriter {
+    fn consume(&mut self, _ty: &DataType, value: ArrayRef) {
+        self.push_list(Some(value));
+    }
+
+    fn consume_null(&mut self, _ty: &DataType) {
+        self.push_list(None);
+    }
+}
+
 /// Determines into which column the next stream value should go.
 pub struct Organizer {
     col_count: usize,
@@ -241,8 +366,10 @@ impl_consume_ref_ty! {
     { BinaryType          => BinaryBuilder          }
     { LargeBinaryType     => LargeBinaryBuilder     }
 //  { FixedSizeBinaryType => FixedSizeBinaryBuilder } custom impl
+    { BinaryViewType      => BinaryViewBuilder      }
     { Utf8Type            => StringBuilder          }
     { LargeUtf8Type       => LargeStringBuilder     }
+    { StringViewType      => StringViewBuilder      }
 }
 
 impl ConsumeTy<FixedSizeBinaryType> for ArrowRowWriter {
@@ -0,0 +1,99 @@
+use arrow::datatypes::{DataType, FieldRef, Schema};
+
+/// A change that turns a column of the current schema of a table into
+/// a column of the desired schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    AddColumn(FieldRef),
+    DropColumn(String),
+    RenameColumn {
+        from: String,
+        to: String,
+    },
+    ChangeType {
+        name: String,
+        from: DataType,
+        to: DataType,
+    },
+    ChangeNullability {
+        name: String,
+        nullable: bool,
+    },
+}
+
+/// Compares the current schema of a table (i.e. from [crate::api::SchemaGet::table_get])
+/// with the desired schema and returns the changes between them.
+///
+/// Columns are matched by name, so the order of fields does not matter.
+/// A renamed column is reported as [SchemaChange::DropColumn] and [SchemaChange::AddColumn],
+/// see [schema_diff_with_renames] for renames that are known up-front.
+pub fn schema_diff(current: &Schema, desired: &Schema) -> Vec<SchemaChange> {
+    schema_diff_with_renames(current, desired, &[])
+}
+
+/// Like [schema_diff], but with hints of renamed columns, as `(from, to)` pairs.
+///
+/// A hint is used only when column `from` exists in the current schema and column `to`
+/// exists in the desired schema, but not the other way around. Renamed columns are
+/// reported as [SchemaChange::RenameColumn], followed by changes of the column under
+/// its new name.
+///
+/// Changes are ordered: renames, drops, changes of existing columns and then additions.
+pub fn schema_diff_with_renames(
+    current: &Schema,
+    desired: &Schema,
+    renames: &[(&str, &str)],
+) -> Vec<SchemaChange> {
+    let has = |schema: &Schema, name: &str| schema.field_with_name(name).is_ok();
+
+    let renames: Vec<(&str, &str)> = (renames.iter().copied())
+        .filter(|(from, _)| has(current, from) && !has(desired, from))
+        .filter(|(from, to)| has(desired, to) && !has(current, to) && from != to)
+        .collect();
+    fn renamed<'a>(renames: &[(&'a str, &'a str)], name: &'a str) -> &'a str {
+        let rename = renames.iter().find(|(from, _)| *from == name);
+        rename.map_or(name, |(_, to)| *to)
+    }
+
+    let mut changes: Vec<SchemaChange> = (renames.iter())
+        .map(|(from, to)| SchemaChange::RenameColumn {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+        .collect();
+
+    let mut changed = Vec::new();
+    for field in current.fields() {
+        let name = renamed(&renames, field.name());
+        let Ok(desired_field) = desired.field_with_name(name) else {
+            changes.push(SchemaChange::DropColumn(field.name().clone()));
+            continue;
+        };
+
+        if field.data_type() != desired_field.data_type() {
+            changed.push(SchemaChange::ChangeType {
+                name: name.to_string(),
+                from: field.data_type().clone(),
+                to: desired_field.data_type().clone(),
+            });
+        }
+        if field.is_nullable() != desired_field.is_nullable() {
+            changed.push(SchemaChange::ChangeNullability {
+                name: name.to_string(),
+                nullable: desired_field.is_nullable(),
+            });
+        }
+    }
+    changes.extend(changed);
+
+    for field in desired.fields() {
+        let exists = current
+            .fields()
+            .iter()
+            .any(|f| renamed(&renames, f.name()) == field.name());
+        if !exists {
+            changes.push(SchemaChange::AddColumn(field.clone()));
+        }
+    }
+    changes
+}
@@ -0,0 +1,114 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+
+use crate::api::{CancelToken, ResultReader};
+use crate::ConnectorError;
+
+/// Timer thread that cancels the query with a [CancelToken] when it is not stopped in time.
+pub(crate) struct Timer {
+    timeout: Duration,
+    fired: Arc<AtomicBool>,
+
+    /// Timer is stopped when the sender is dropped.
+    done: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Timer {
+    pub(crate) fn start(token: CancelToken, timeout: Duration) -> Self {
+        let fired = Arc::new(AtomicBool::new(false));
+        let (done, done_rx) = mpsc::channel::<()>();
+
+        let thread = {
+            let fired = fired.clone();
+            std::thread::spawn(move || {
+                if let Err(mpsc::RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+                    fired.store(true, Ordering::SeqCst);
+
+                    // the error of the query is reported, whether or not it was cancelled
+                    if let Err(e) = token.cancel() {
+                        log::warn!("cancelling query after timeout failed: {e}");
+                    }
+                }
+            })
+        };
+
+        Timer {
+            timeout,
+            fired,
+            done: Some(done),
+            thread: Some(thread),
+        }
+    }
+
+    /// Reports cancellation caused by this timer as [ConnectorError::Timeout].
+    pub(crate) fn map_err(&self, err: ConnectorError) -> ConnectorError {
+        match err {
+            ConnectorError::Cancelled if self.fired.load(Ordering::SeqCst) => {
+                ConnectorError::Timeout(self.timeout)
+            }
+            err => err,
+        }
+    }
+
+    /// Stops the timer and waits for a pending cancellation request to complete,
+    /// so it cannot cancel a later query on the same connection.
+    pub(crate) fn stop(&mut self) {
+        drop(self.done.take());
+        if let Some(thread) = self.thread.take() {
+            thread.join().expect("timer thread panicked");
+        }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Reader of a statement started by [crate::api::Statement::start_with_timeout].
+///
+/// Yields [ConnectorError::Timeout] when the statement was cancelled because it did
+/// not complete in time. Timer is stopped when all batches are read or when the reader
+/// is dropped.
+pub struct TimeoutReader<R> {
+    inner: R,
+    timer: Timer,
+}
+
+impl<R> TimeoutReader<R> {
+    pub(crate) fn new(inner: R, timer: Timer) -> Self {
+        TimeoutReader { inner, timer }
+    }
+}
+
+impl<'stmt, R: ResultReader<'stmt>> ResultReader<'stmt> for TimeoutReader<R> {
+    fn get_schema(&mut self) -> Result<SchemaRef, ConnectorError> {
+        self.inner.get_schema().map_err(|e| self.timer.map_err(e))
+    }
+}
+
+impl<'stmt, R: ResultReader<'stmt>> Iterator for TimeoutReader<R> {
+    type Item = Result<RecordBatch, ConnectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next();
+        match item {
+            None => {
+                self.timer.stop();
+                None
+            }
+            Some(Err(e)) => {
+                self.timer.stop();
+                Some(Err(self.timer.map_err(e)))
+            }
+            Some(Ok(batch)) => Some(Ok(batch)),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use arrow::datatypes::*;
 
 /// Moving of typed values from a producer into a consumer
 use crate::errors::ConnectorError;
-use crate::types::{ArrowType, FixedSizeBinaryType, NullType};
+use crate::types::{ArrowType, FixedSizeBinaryType, ListType, NullType};
 
 macro_rules! impl_transport_match {
     ($f: expr, $c: expr, $p: expr, $({ $Pat: pat => $ArrTy: ty })*) => {
@@ -81,10 +81,13 @@ pub fn transport<'r, P: Produce<'r>, C: Consume>(
         { Binary => BinaryType }
         { LargeBinary => LargeBinaryType }
         { FixedSizeBinary(_) => FixedSizeBinaryType }
+        { BinaryView => BinaryViewType }
         { Utf8 => Utf8Type }
         { LargeUtf8 => LargeUtf8Type }
+        { Utf8View => StringViewType }
         { Decimal128(_, _) => Decimal128Type }
         { Decimal256(_, _) => Decimal256Type }
+        { List(_) => ListType }
     );
     Ok(())
 }
@@ -123,10 +126,13 @@ pub trait Produce<'r>:
     + ProduceTy<'r, BinaryType>
     + ProduceTy<'r, LargeBinaryType>
     + ProduceTy<'r, FixedSizeBinaryType>
+    + ProduceTy<'r, BinaryViewType>
     + ProduceTy<'r, Utf8Type>
     + ProduceTy<'r, LargeUtf8Type>
+    + ProduceTy<'r, StringViewType>
     + ProduceTy<'r, Decimal128Type>
     + ProduceTy<'r, Decimal256Type>
+    + ProduceTy<'r, ListType>
 {
 }
 
@@ -172,10 +178,13 @@ pub trait Consume:
     + ConsumeTy<BinaryType>
     + ConsumeTy<LargeBinaryType>
     + ConsumeTy<FixedSizeBinaryType>
+    + ConsumeTy<BinaryViewType>
     + ConsumeTy<Utf8Type>
     + ConsumeTy<LargeUtf8Type>
+    + ConsumeTy<StringViewType>
     + ConsumeTy<Decimal128Type>
     + ConsumeTy<Decimal256Type>
+    + ConsumeTy<ListType>
 {
 }
 
@@ -5,11 +5,17 @@ mod util;
 
 #[cfg(feature = "src_duckdb")]
 mod test_duckdb;
+#[cfg(feature = "src_iceberg")]
+mod test_iceberg;
 #[cfg(feature = "src_mysql")]
 mod test_mysql;
+#[cfg(feature = "src_parquet")]
+mod test_parquet;
 #[cfg(feature = "src_postgres")]
 mod test_postgres;
 #[cfg(feature = "src_sqlite")]
 mod test_sqlite;
 #[cfg(feature = "src_tiberius")]
 mod test_tiberius;
+#[cfg(feature = "src_trino")]
+mod test_trino;
@@ -1,6 +1,15 @@
-use super::spec;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Decimal128Array, RecordBatch};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::util::pretty::pretty_format_batches;
+use connector_arrow::api::{Append, Connector, SchemaEdit, SchemaGet};
+use connector_arrow::duckdb::DuckDBConnection;
+use connector_arrow::{ConnectorError, TableCreateError};
 use rstest::*;
 
+use super::spec;
+
 fn init() -> connector_arrow::duckdb::DuckDBConnection {
     let _ = env_logger::builder().is_test(true).try_init();
 
@@ -62,6 +71,114 @@ fn schema_edit() {
     super::tests::schema_edit(&mut conn, table_name, spec::basic_types());
 }
 
+#[test]
+fn temp_table() {
+    let table_name = "temp_table";
+
+    let mut conn = init();
+    super::tests::temp_table(&mut conn, table_name, '"');
+}
+
+#[test]
+fn delete_by_keys() {
+    let table_name = "delete_by_keys";
+
+    let mut conn = init();
+    super::tests::delete_by_keys(&mut conn, table_name, '"');
+}
+
+#[test]
+fn merge() {
+    let table_name = "merge";
+
+    let mut conn = init();
+    super::tests::merge(&mut conn, table_name);
+}
+
+#[test]
+fn execute() {
+    let mut conn = init();
+    super::tests::execute(&mut conn, "execute_counts");
+}
+
+#[test]
+fn execute_batch() {
+    let mut conn = init();
+    super::tests::execute_batch(&mut conn, "execute_batch");
+}
+
+#[test]
+fn transaction() {
+    let mut conn = init();
+    super::tests::transaction(&mut conn, "transaction_test");
+}
+
+#[test]
+fn query_schema() {
+    let mut conn = init();
+    super::tests::query_schema(&mut conn, "query_schema");
+}
+
+#[test]
+fn append_or_create() {
+    let mut conn = init();
+    super::tests::append_or_create(&mut conn, "append_or_create");
+}
+
+#[test]
+fn table_list() {
+    let mut conn = init();
+    super::tests::table_list(&mut conn, "table_list");
+}
+
+#[test]
+fn table_exists() {
+    let mut conn = init();
+    super::tests::table_exists(&mut conn, "table_exists");
+}
+
+#[test]
+fn table_primary_key() {
+    let mut conn = init();
+    super::tests::table_primary_key(&mut conn, "table_primary_key");
+}
+
+#[test]
+fn rename() {
+    let mut conn = init();
+    super::tests::rename(&mut conn, "rename");
+}
+
+#[test]
+fn list_catalogs() {
+    let mut conn = init();
+    super::tests::list_catalogs(&mut conn, "memory");
+}
+
+#[test]
+fn estimate_row_count() {
+    let mut conn = init();
+    super::tests::estimate_row_count(&mut conn, "estimate_row_count");
+}
+
+#[test]
+fn query_iter() {
+    let mut conn = init();
+    super::tests::query_iter(&mut conn, "query_iter");
+}
+
+#[test]
+fn query_with_coercions() {
+    let mut conn = init();
+    super::tests::query_with_coercions(&mut conn);
+}
+
+#[test]
+fn explain_cost() {
+    let mut conn = init();
+    super::tests::explain_cost(&mut conn, "explain_cost");
+}
+
 #[test]
 fn ident_escaping() {
     let table_name = "simple::ident_escaping";
@@ -69,3 +186,151 @@ fn ident_escaping() {
     let mut conn = init();
     super::tests::ident_escaping(&mut conn, table_name);
 }
+
+#[test]
+fn query_nested() {
+    let mut conn = init();
+    let query = "SELECT
+        [1, 2, 3]::INTEGER[] AS l,
+        {'a': 1, 'b': 'x'}::STRUCT(a INTEGER, b TEXT) AS s,
+        MAP {'k': 1} AS m
+    ";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let schema = batches[0].schema();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["l", "s", "m"]);
+    assert!(
+        matches!(schema.field(0).data_type(), DataType::List(f) if f.data_type() == &DataType::Int32)
+    );
+    assert!(matches!(schema.field(1).data_type(), DataType::Struct(f) if f.len() == 2));
+    assert!(matches!(schema.field(2).data_type(), DataType::Map(_, _)));
+
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+-----------+--------------+--------+\n\
+         | l         | s            | m      |\n\
+         +-----------+--------------+--------+\n\
+         | [1, 2, 3] | {a: 1, b: x} | {k: 1} |\n\
+         +-----------+--------------+--------+"
+    );
+}
+
+#[test]
+fn decimal() {
+    let mut conn = init();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("price", DataType::Decimal128(10, 2), true),
+        Field::new("big", DataType::Decimal128(38, 0), true),
+    ]));
+    let decimal = |values: Vec<Option<i128>>, p: u8, s: i8| {
+        Arc::new(
+            Decimal128Array::from(values)
+                .with_precision_and_scale(p, s)
+                .unwrap(),
+        ) as ArrayRef
+    };
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            decimal(vec![Some(12345), Some(-5), None], 10, 2),
+            decimal(vec![Some(i128::pow(10, 37)), None, Some(-1)], 38, 0),
+        ],
+    )
+    .unwrap();
+
+    conn.table_create("decimals", schema.clone()).unwrap();
+    assert_eq!(conn.table_get("decimals").unwrap(), schema);
+
+    let mut appender = conn.append("decimals").unwrap();
+    appender.append(batch.clone()).unwrap();
+    appender.finish().unwrap();
+
+    let batches = connector_arrow::query(&mut conn, "SELECT * FROM decimals").unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        pretty_format_batches(&[batch]).unwrap().to_string(),
+    );
+
+    // precision above 38 is not supported by DuckDB
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Decimal256(45, 12),
+        true,
+    )]));
+    let res = conn.table_create("decimal_256", schema);
+    assert!(matches!(
+        res,
+        Err(TableCreateError::Connector(
+            ConnectorError::NotSupported { .. }
+        ))
+    ));
+
+    assert_eq!(
+        DuckDBConnection::type_db_into_arrow("DECIMAL(10,2)"),
+        Some(DataType::Decimal128(10, 2))
+    );
+    assert_eq!(
+        DuckDBConnection::type_arrow_into_db(&DataType::Decimal256(45, 12)),
+        None
+    );
+}
+
+#[test]
+fn query_files() {
+    let mut conn = init();
+
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/basic_small.parquet"
+    );
+    let batches = conn.query_parquet(path).unwrap();
+    let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+    let batch = batch.project(&[3, 1]).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&[batch]).unwrap().to_string(),
+        "+----------+----------+
+| test_str | test_int |
++----------+----------+
+| str1     | 1        |
+| str2     | 2        |
+| a        | 0        |
+| b        | 3        |
+| c        | 4        |
+|          | 1314     |
++----------+----------+"
+    );
+
+    let path = std::env::temp_dir().join("connector_arrow_duckdb_query_files.csv");
+    std::fs::write(&path, "a,b\n1,x\n2,y\n").unwrap();
+    let batches = conn.query_csv(path.to_str().unwrap()).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+---+---+
+| a | b |
++---+---+
+| 1 | x |
+| 2 | y |
++---+---+"
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn load_parquet() {
+    let mut conn = init();
+    let table_name = "load_parquet";
+    let path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/basic_small.parquet");
+
+    conn.execute(&format!(
+        "CREATE TABLE {table_name} AS SELECT * FROM read_parquet('{}') LIMIT 0",
+        path.display()
+    ))
+    .unwrap();
+
+    let rows = conn.load_parquet(table_name, &path).unwrap();
+    assert_eq!(rows, 6);
+    assert_eq!(conn.estimate_row_count(table_name).unwrap(), 6);
+}
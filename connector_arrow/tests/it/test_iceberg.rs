@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use arrow::array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use arrow::util::pretty::pretty_format_batches;
+use async_trait::async_trait;
+use connector_arrow::api::{Connector, ResultReader, SchemaGet, Statement};
+use connector_arrow::iceberg::IcebergConnection;
+use iceberg::io::{FileIO, FileIOBuilder};
+use iceberg::spec::{
+    DataFileFormat, NestedField, PrimitiveType, Schema, TableMetadata, TableMetadataBuilder, Type,
+};
+use iceberg::table::Table;
+use iceberg::transaction::Transaction;
+use iceberg::writer::base_writer::data_file_writer::DataFileWriterBuilder;
+use iceberg::writer::file_writer::location_generator::{
+    DefaultFileNameGenerator, DefaultLocationGenerator,
+};
+use iceberg::writer::file_writer::ParquetWriterBuilder;
+use iceberg::writer::{IcebergWriter, IcebergWriterBuilder};
+use iceberg::{Catalog, Namespace, NamespaceIdent, TableCommit, TableCreation, TableIdent};
+use parquet::file::properties::WriterProperties;
+use tokio::runtime::{self, Runtime};
+
+/// Catalog that keeps table metadata in memory and data files in a local directory.
+#[derive(Debug)]
+struct LocalCatalog {
+    file_io: FileIO,
+    warehouse: String,
+    tables: Mutex<HashMap<TableIdent, TableMetadata>>,
+}
+
+impl LocalCatalog {
+    fn new(name: &str) -> Self {
+        let warehouse = std::env::temp_dir()
+            .join("connector_arrow_iceberg")
+            .join(name);
+        let _ = std::fs::remove_dir_all(&warehouse);
+
+        LocalCatalog {
+            file_io: FileIOBuilder::new_fs_io().build().unwrap(),
+            warehouse: warehouse.to_string_lossy().into_owned(),
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn table(&self, ident: TableIdent, metadata: TableMetadata) -> iceberg::Result<Table> {
+        Table::builder()
+            .file_io(self.file_io.clone())
+            .metadata(metadata)
+            .identifier(ident)
+            .build()
+    }
+}
+
+#[async_trait]
+impl Catalog for LocalCatalog {
+    async fn list_namespaces(
+        &self,
+        _: Option<&NamespaceIdent>,
+    ) -> iceberg::Result<Vec<NamespaceIdent>> {
+        unimplemented!()
+    }
+
+    async fn create_namespace(
+        &self,
+        _: &NamespaceIdent,
+        _: HashMap<String, String>,
+    ) -> iceberg::Result<Namespace> {
+        unimplemented!()
+    }
+
+    async fn get_namespace(&self, _: &NamespaceIdent) -> iceberg::Result<Namespace> {
+        unimplemented!()
+    }
+
+    async fn namespace_exists(&self, _: &NamespaceIdent) -> iceberg::Result<bool> {
+        unimplemented!()
+    }
+
+    async fn update_namespace(
+        &self,
+        _: &NamespaceIdent,
+        _: HashMap<String, String>,
+    ) -> iceberg::Result<()> {
+        unimplemented!()
+    }
+
+    async fn drop_namespace(&self, _: &NamespaceIdent) -> iceberg::Result<()> {
+        unimplemented!()
+    }
+
+    async fn list_tables(&self, namespace: &NamespaceIdent) -> iceberg::Result<Vec<TableIdent>> {
+        let tables = self.tables.lock().unwrap();
+        let idents = tables.keys().filter(|t| &t.namespace == namespace);
+        Ok(idents.cloned().collect())
+    }
+
+    async fn create_table(
+        &self,
+        namespace: &NamespaceIdent,
+        mut creation: TableCreation,
+    ) -> iceberg::Result<Table> {
+        let ident = TableIdent::new(namespace.clone(), creation.name.clone());
+        creation.location = Some(format!("{}/{}", self.warehouse, creation.name));
+
+        let metadata = TableMetadataBuilder::from_table_creation(creation)?
+            .build()?
+            .metadata;
+        let mut tables = self.tables.lock().unwrap();
+        tables.insert(ident.clone(), metadata.clone());
+        self.table(ident, metadata)
+    }
+
+    async fn load_table(&self, ident: &TableIdent) -> iceberg::Result<Table> {
+        let tables = self.tables.lock().unwrap();
+        let metadata = tables.get(ident).cloned().ok_or_else(|| {
+            iceberg::Error::new(iceberg::ErrorKind::DataInvalid, "table does not exist")
+        })?;
+        self.table(ident.clone(), metadata)
+    }
+
+    async fn drop_table(&self, _: &TableIdent) -> iceberg::Result<()> {
+        unimplemented!()
+    }
+
+    async fn table_exists(&self, _: &TableIdent) -> iceberg::Result<bool> {
+        unimplemented!()
+    }
+
+    async fn rename_table(&self, _: &TableIdent, _: &TableIdent) -> iceberg::Result<()> {
+        unimplemented!()
+    }
+
+    /// Applies updates, without checking requirements.
+    async fn update_table(&self, mut commit: TableCommit) -> iceberg::Result<Table> {
+        let ident = commit.identifier().clone();
+        let mut tables = self.tables.lock().unwrap();
+        let metadata = tables.get(&ident).cloned().unwrap();
+
+        let mut builder = TableMetadataBuilder::new_from_metadata(metadata, None);
+        for update in commit.take_updates() {
+            builder = update.apply(builder)?;
+        }
+        let metadata = builder.build()?.metadata;
+        tables.insert(ident.clone(), metadata.clone());
+        self.table(ident, metadata)
+    }
+}
+
+fn init(name: &str) -> (Arc<Runtime>, IcebergConnection<LocalCatalog>) {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let rt = Arc::new(
+        runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap(),
+    );
+    let namespace = NamespaceIdent::new("test".into());
+    let conn = IcebergConnection::new(rt.clone(), LocalCatalog::new(name), namespace);
+    (rt, conn)
+}
+
+/// Creates a table with columns `id` and `name`, optionally appending a snapshot of a few rows.
+fn create_table(rt: &Runtime, conn: &IcebergConnection<LocalCatalog>, name: &str, rows: bool) {
+    let catalog = conn.catalog();
+    let schema = Schema::builder()
+        .with_fields(vec![
+            NestedField::required(1, "id", Type::Primitive(PrimitiveType::Long)).into(),
+            NestedField::optional(2, "name", Type::Primitive(PrimitiveType::String)).into(),
+        ])
+        .build()
+        .unwrap();
+    let creation = TableCreation::builder()
+        .name(name.to_string())
+        .schema(schema.clone())
+        .build();
+    let namespace = NamespaceIdent::new("test".into());
+    let table = rt
+        .block_on(catalog.create_table(&namespace, creation))
+        .unwrap();
+    if !rows {
+        return;
+    }
+
+    // batch schema must contain ids of iceberg fields
+    let arrow_schema = iceberg::arrow::schema_to_arrow_schema(&schema).unwrap();
+    let batch = RecordBatch::try_new(
+        Arc::new(arrow_schema),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef,
+            Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])) as ArrayRef,
+        ],
+    )
+    .unwrap();
+
+    rt.block_on(async {
+        let parquet_writer = ParquetWriterBuilder::new(
+            WriterProperties::default(),
+            table.metadata().current_schema().clone(),
+            table.file_io().clone(),
+            DefaultLocationGenerator::new(table.metadata().clone()).unwrap(),
+            DefaultFileNameGenerator::new("data".into(), None, DataFileFormat::Parquet),
+        );
+        let mut writer = DataFileWriterBuilder::new(parquet_writer, None)
+            .build()
+            .await
+            .unwrap();
+        writer.write(batch).await.unwrap();
+        let data_files = writer.close().await.unwrap();
+
+        let tx = Transaction::new(&table);
+        let mut append = tx.fast_append(None, vec![]).unwrap();
+        append.add_data_files(data_files).unwrap();
+        append.apply().await.unwrap().commit(catalog).await.unwrap();
+    });
+}
+
+#[test]
+fn table_list() {
+    let (rt, mut conn) = init("table_list");
+    create_table(&rt, &conn, "b", false);
+    create_table(&rt, &conn, "a", false);
+
+    let tables = conn.table_list().unwrap();
+    assert_eq!(tables, vec!["a", "b"]);
+}
+
+#[test]
+fn query_table() {
+    let (rt, mut conn) = init("query_table");
+    create_table(&rt, &conn, "people", true);
+
+    let schema = conn.table_get("people").unwrap();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["id", "name"]);
+
+    let mut stmt = conn.query("people").unwrap();
+    let mut reader = stmt.start([]).unwrap();
+    assert_eq!(reader.get_schema().unwrap(), schema);
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+----+------+
+| id | name |
++----+------+
+| 1  | a    |
+| 2  |      |
+| 3  | c    |
++----+------+"
+    );
+}
+
+#[test]
+fn query_empty_table() {
+    let (rt, mut conn) = init("query_empty_table");
+    create_table(&rt, &conn, "empty", false);
+
+    let schema = conn.query_schema("empty").unwrap();
+    assert_eq!(schema.fields().len(), 2);
+
+    let batches = connector_arrow::query(&mut conn, "empty").unwrap();
+    assert!(batches.is_empty());
+}
@@ -1,6 +1,18 @@
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, BinaryArray, Int64Array, RecordBatch, StringArray};
+use arrow::datatypes::{
+    DataType, Field, Int16Type, Int64Type, Schema, TimeUnit, TimestampMicrosecondType, UInt32Type,
+};
+use connector_arrow::api::{
+    Append, Connector, NullPolicy, ReadOptions, ResultReader, SchemaGet, Statement,
+    StatementCacheStats,
+};
 use connector_arrow::mysql::MySQLConnection;
+use connector_arrow::ConnectorError;
 use rstest::*;
 
+use crate::util::create_table;
 use crate::{spec, util::QueryOfSingleLiteral};
 
 fn init() -> MySQLConnection<mysql::Conn> {
@@ -11,6 +23,22 @@ fn init() -> MySQLConnection<mysql::Conn> {
     MySQLConnection::new(conn)
 }
 
+#[test]
+fn cancel() {
+    let url = std::env::var("MYSQL_URL").unwrap();
+    let opts = mysql::Opts::from_url(&url).unwrap();
+    let mut conn = init().with_cancellation(opts).unwrap();
+    super::tests::cancel(&mut conn, "SELECT BENCHMARK(10000000000, MD5('a'))");
+}
+
+#[test]
+fn query_with_timeout() {
+    let url = std::env::var("MYSQL_URL").unwrap();
+    let opts = mysql::Opts::from_url(&url).unwrap();
+    let mut conn = init().with_cancellation(opts).unwrap();
+    super::tests::query_with_timeout(&mut conn, "SELECT BENCHMARK(10000000000, MD5('a'))");
+}
+
 #[test]
 fn query_01() {
     let mut conn = init();
@@ -35,6 +63,149 @@ fn schema_edit() {
     super::tests::schema_edit(&mut conn, table_name, column_spec);
 }
 
+#[test]
+fn execute() {
+    let mut conn = init();
+    super::tests::execute(&mut conn, "execute_counts");
+}
+
+#[test]
+fn query_returning() {
+    let mut conn = init();
+    let table_name = "query_returning";
+    create_table(&mut conn, table_name, "id INTEGER, x INTEGER");
+
+    let update = format!("UPDATE {table_name} SET x = x + 1 WHERE id < 5 RETURNING id, x");
+    let err = connector_arrow::query(&mut conn, &update).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ConnectorError::NotSupported {
+                feature: "RETURNING",
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+#[test]
+fn execute_batch() {
+    let mut conn = init();
+    super::tests::execute_batch(&mut conn, "execute_batch");
+}
+
+#[test]
+fn transaction() {
+    let mut conn = init();
+    super::tests::transaction(&mut conn, "transaction_test");
+}
+
+#[test]
+fn query_schema() {
+    let mut conn = init();
+    super::tests::query_schema(&mut conn, "query_schema");
+}
+
+#[test]
+fn delete_by_keys() {
+    let table_name = "delete_by_keys";
+
+    let mut conn = init();
+    super::tests::delete_by_keys(&mut conn, table_name, '`');
+}
+
+#[test]
+fn merge() {
+    let table_name = "merge";
+    let mut conn = init();
+
+    // rows are matched on the primary key
+    create_table(&mut conn, table_name, "a BIGINT PRIMARY KEY, b BIGINT");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES (1, 10), (2, 20)"))
+        .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, false),
+        Field::new("b", DataType::Int64, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int64Array::from(vec![2, 3])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![200, 300])) as ArrayRef,
+        ],
+    )
+    .unwrap();
+    conn.merge(table_name, &["a"], &batch).unwrap();
+
+    let query = format!("SELECT a, b FROM {table_name} ORDER BY a");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        "+---+-----+\n\
+         | a | b   |\n\
+         +---+-----+\n\
+         | 1 | 10  |\n\
+         | 2 | 200 |\n\
+         | 3 | 300 |\n\
+         +---+-----+",
+        arrow::util::pretty::pretty_format_batches(&batches)
+            .unwrap()
+            .to_string(),
+    );
+}
+
+#[test]
+fn append_conflict() {
+    let mut conn = init();
+    super::tests::append_conflict(&mut conn, "append_conflict");
+}
+
+#[test]
+fn append_or_create() {
+    let mut conn = init();
+    super::tests::append_or_create(&mut conn, "append_or_create");
+}
+
+#[test]
+fn table_list() {
+    let mut conn = init();
+    super::tests::table_list(&mut conn, "table_list");
+}
+
+#[test]
+fn table_exists() {
+    let mut conn = init();
+    super::tests::table_exists(&mut conn, "table_exists");
+}
+
+#[test]
+fn table_primary_key() {
+    let mut conn = init();
+    super::tests::table_primary_key(&mut conn, "table_primary_key");
+}
+
+#[test]
+fn table_foreign_keys() {
+    let mut conn = init();
+    super::tests::table_foreign_keys(&mut conn, "table_foreign_keys");
+}
+
+#[test]
+fn rename() {
+    let mut conn = init();
+    super::tests::rename(&mut conn, "rename");
+}
+
+#[test]
+fn list_catalogs() {
+    let mut conn = init();
+    super::tests::list_catalogs(&mut conn, "db");
+}
+
 #[test]
 fn ident_escaping() {
     // https://github.com/blackbeam/rust_mysql_common/issues/129
@@ -63,6 +234,76 @@ fn roundtrip(#[case] table_name: &str, #[case] spec: spec::ArrowGenSpec) {
     super::tests::roundtrip(&mut conn, table_name, spec, '`', true);
 }
 
+#[test]
+fn query_spatial() {
+    let query = "SELECT ST_GeomFromText('POINT(1 2)', 3857) AS p";
+    let wkb_point: &[u8] = &[
+        0x01, 0x01, 0x00, 0x00, 0x00, // little-endian, point
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, // x = 1.0
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, // y = 2.0
+    ];
+
+    // default: WKB
+    let mut conn = init();
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let wkb = batches[0].column(0).as_binary::<i32>();
+    assert_eq!(wkb, &BinaryArray::from(vec![wkb_point]));
+
+    // struct of SRID and WKB
+    let mut conn = init().with_spatial_srid(true);
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let point = batches[0].column(0).as_struct();
+    assert_eq!(point.column(0).as_primitive::<UInt32Type>().value(0), 3857);
+    assert_eq!(point.column(1).as_binary::<i32>().value(0), wkb_point);
+}
+
+#[test]
+fn roundtrip_enum_set() {
+    let mut conn = init();
+    let table_name = "roundtrip_enum_set";
+    create_table(
+        &mut conn,
+        table_name,
+        "e ENUM('small', 'large'), s SET('a', 'b', 'c')",
+    );
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("e", DataType::Utf8, true),
+        Field::new("s", DataType::Utf8, true),
+    ]));
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec![Some("large"), None])) as ArrayRef,
+            Arc::new(StringArray::from(vec![Some("a,c"), Some("")])),
+        ],
+    )
+    .unwrap();
+    let mut appender = conn.append(table_name).unwrap();
+    appender.append(batch.clone()).unwrap();
+    appender.finish().unwrap();
+
+    let query = format!("SELECT e, s FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(batches, vec![batch]);
+
+    // MySQL rejects values that are not members of the enum
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(vec!["medium"])) as ArrayRef,
+            Arc::new(StringArray::from(vec!["a"])),
+        ],
+    )
+    .unwrap();
+    let mut appender = conn.append(table_name).unwrap();
+    appender.append(batch).unwrap_err();
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
 #[rstest]
 #[case::strings(literals_cases::strings())]
 #[case::decimals(literals_cases::decimals())]
@@ -106,3 +347,266 @@ mod literals_cases {
         ]
     }
 }
+
+#[test]
+fn append_chunks() {
+    let mut conn = init();
+    let table_name = "append_chunks";
+    create_table(&mut conn, table_name, "a BIGINT NOT NULL, b TEXT");
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5])) as ArrayRef,
+            Arc::new(StringArray::from(vec![
+                Some("a"),
+                None,
+                Some("c"),
+                None,
+                None,
+            ])),
+        ],
+    )
+    .unwrap();
+
+    // 5 rows in chunks of 2
+    let mut appender = conn
+        .append(table_name)
+        .unwrap()
+        .with_rows_per_insert(2)
+        .unwrap();
+    appender.append(batch.clone()).unwrap();
+    appender.finish().unwrap();
+
+    // the second chunk contains a NULL in a NOT NULL column
+    let invalid = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int64Array::from(vec![Some(6), Some(7), None])) as ArrayRef,
+            Arc::new(StringArray::from(vec![None::<&str>, None, None])),
+        ],
+    )
+    .unwrap();
+    let mut appender = conn
+        .append(table_name)
+        .unwrap()
+        .with_rows_per_insert(2)
+        .unwrap();
+    appender.append(batch).unwrap();
+    appender.append(invalid).unwrap_err();
+    let err = appender.finish().unwrap_err();
+    assert!(matches!(err, ConnectorError::AppendRolledBack));
+
+    // only the first append was committed
+    let query = format!("SELECT CAST(COUNT(*) AS SIGNED) FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let count = batches[0].column(0).as_primitive::<Int64Type>();
+    assert_eq!(count.value(0), 5);
+}
+
+#[test]
+fn append_reuses_prepared_insert() {
+    let mut conn = init();
+    let table_name = "append_reuses_prepared_insert";
+    create_table(&mut conn, table_name, "a BIGINT");
+
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+    let batch =
+        RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from_iter_values(0..5))]).unwrap();
+
+    // each batch is inserted as a chunk of 2, a chunk of 2 and a chunk of 1
+    let mut appender = conn
+        .append(table_name)
+        .unwrap()
+        .with_rows_per_insert(2)
+        .unwrap();
+    for _ in 0..10 {
+        appender.append(batch.clone()).unwrap();
+    }
+    assert_eq!(
+        appender.statement_cache_stats(),
+        StatementCacheStats {
+            hits: 28,
+            misses: 2,
+            size: 2
+        }
+    );
+    appender.finish().unwrap();
+
+    let query = format!("SELECT CAST(COUNT(*) AS SIGNED) FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let count = batches[0].column(0).as_primitive::<Int64Type>();
+    assert_eq!(count.value(0), 50);
+}
+
+#[test]
+fn query_timestamp_session_time_zone() {
+    let table_name = "query_timestamp_session_time_zone";
+    let mut conn = init().with_session_time_zone(true);
+
+    create_table(&mut conn, table_name, "ts TIMESTAMP(6)");
+    conn.execute("SET time_zone = '+02:00'").unwrap();
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES ('2020-01-01 12:00:00.000001')"
+    ))
+    .unwrap();
+
+    let query = format!("SELECT ts FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let ty = DataType::Timestamp(TimeUnit::Microsecond, Some("+02:00".into()));
+    assert_eq!(batches[0].schema().field(0).data_type(), &ty);
+
+    // 2020-01-01T10:00:00.000001Z
+    let ts = batches[0]
+        .column(0)
+        .as_primitive::<TimestampMicrosecondType>();
+    assert_eq!(ts.value(0), 1_577_872_800_000_001);
+
+    // negative offsets
+    conn.execute("SET time_zone = '-05:30'").unwrap();
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let ty = DataType::Timestamp(TimeUnit::Microsecond, Some("-05:30".into()));
+    assert_eq!(batches[0].schema().field(0).data_type(), &ty);
+    let ts = batches[0]
+        .column(0)
+        .as_primitive::<TimestampMicrosecondType>();
+    assert_eq!(ts.value(0), 1_577_872_800_000_001);
+}
+
+#[test]
+fn query_datetime_assume_utc() {
+    let table_name = "query_datetime_assume_utc";
+    let mut conn = init();
+
+    create_table(&mut conn, table_name, "dt DATETIME(6), ts TIMESTAMP(6)");
+    conn.execute("SET time_zone = '+00:00'").unwrap();
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES ('2020-01-01 10:00:00.000001', '2020-01-01 10:00:00.000001')"
+    ))
+    .unwrap();
+    let query = format!("SELECT dt, ts FROM {table_name}");
+
+    // by default, datetime is read as text
+    conn.execute("SET time_zone = '+02:00'").unwrap();
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    assert_eq!(batches[0].schema().field(0).data_type(), &DataType::Utf8);
+    assert_eq!(batches[0].schema().field(1).data_type(), &DataType::Utf8);
+
+    // 2020-01-01T10:00:00.000001Z
+    let mut conn = conn.with_assume_utc(true).unwrap();
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let ty = DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()));
+    for column in 0..2 {
+        assert_eq!(batches[0].schema().field(column).data_type(), &ty);
+        let ts = batches[0]
+            .column(column)
+            .as_primitive::<TimestampMicrosecondType>();
+        assert_eq!(ts.value(0), 1_577_872_800_000_001);
+    }
+    assert_eq!(conn.query_schema(&query).unwrap(), batches[0].schema());
+}
+
+#[test]
+fn query_year_bit() {
+    let mut conn = init();
+    let table_name = "query_year_bit";
+    create_table(&mut conn, table_name, "y YEAR, b BIT(8), b12 BIT(12)");
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES (2024, b'10100101', b'100000000001'), (NULL, NULL, NULL)"
+    ))
+    .unwrap();
+
+    let schema = conn.table_get(table_name).unwrap();
+    assert_eq!(schema.field(0).data_type(), &DataType::Int16);
+    assert_eq!(schema.field(1).data_type(), &DataType::Binary);
+
+    let batches =
+        connector_arrow::query(&mut conn, &format!("SELECT y, b, b12 FROM {table_name}")).unwrap();
+    let batch = &batches[0];
+    assert_eq!(batch.schema().field(0).data_type(), &DataType::Int16);
+
+    let years = batch.column(0).as_primitive::<Int16Type>();
+    assert_eq!(years.value(0), 2024);
+    assert!(years.is_null(1));
+
+    let bits = batch.column(1).as_binary::<i32>();
+    assert_eq!(bits.value(0), &[0b10100101]);
+    assert!(bits.is_null(1));
+
+    // bytes of the whole field are preserved
+    let bits = batch.column(2).as_binary::<i32>();
+    assert_eq!(bits.value(0), &[0b1000, 0b00000001]);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+#[test]
+fn query_unexpected_null() {
+    let mut conn = init();
+    let table_name = "query_unexpected_null";
+    create_table(&mut conn, table_name, "a INT NOT NULL");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES (1), (2)"))
+        .unwrap();
+    let query = format!("SELECT a FROM {table_name}");
+
+    // by default, the column is declared as non-nullable
+    let schema = conn.query_schema(&query).unwrap();
+    assert!(!schema.field(0).is_nullable());
+
+    let options = ReadOptions {
+        on_unexpected_null: NullPolicy::Promote,
+        ..Default::default()
+    };
+    let mut stmt = conn.query_with(&query, options.clone()).unwrap();
+    let mut reader = stmt.start([]).unwrap();
+    assert!(reader.get_schema().unwrap().field(0).is_nullable());
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(batches[0].num_rows(), 2);
+    drop(stmt);
+
+    // the super-aggregate row of ROLLUP contains a NULL
+    let query = format!("SELECT a, COUNT(*) AS c FROM {table_name} GROUP BY a WITH ROLLUP");
+    let mut stmt = conn.query_with(&query, options).unwrap();
+    let batches = stmt
+        .start([])
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(batches[0].num_rows(), 3);
+    assert_eq!(batches[0].column(0).null_count(), 1);
+    drop(stmt);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+#[test]
+fn query_with_read_options() {
+    let mut conn = init();
+    let query = "WITH RECURSIVE s(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM s WHERE i < 10) \
+        SELECT i FROM s";
+
+    let options = ReadOptions {
+        batch_size: Some(4),
+        ..Default::default()
+    };
+    let mut stmt = conn.query_with(query, options).unwrap();
+    let batches = stmt
+        .start([])
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let row_counts: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+    assert_eq!(row_counts, vec![4, 4, 2]);
+    drop(stmt);
+
+    let options = ReadOptions {
+        prefetch_rows: Some(4),
+        ..Default::default()
+    };
+    let err = conn.query_with(query, options).err().unwrap();
+    assert!(matches!(err, ConnectorError::NotSupported { .. }));
+}
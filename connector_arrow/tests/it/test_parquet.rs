@@ -0,0 +1,92 @@
+use arrow::util::pretty::pretty_format_batches;
+use connector_arrow::api::{Connector, ResultReader, SchemaGet, Statement};
+use connector_arrow::parquet::ParquetConnection;
+use connector_arrow::ConnectorError;
+
+fn init() -> ParquetConnection {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    ParquetConnection::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data"))
+}
+
+#[test]
+fn table_list() {
+    let mut conn = init();
+    let tables = conn.table_list().unwrap();
+    assert_eq!(tables, vec!["basic_small.parquet", "empty.parquet"]);
+
+    let path = conn.path().join("empty.parquet");
+    let mut conn = ParquetConnection::new(path);
+    let tables = conn.table_list().unwrap();
+    assert_eq!(tables, vec!["empty.parquet"]);
+}
+
+#[test]
+fn query_file() {
+    let mut conn = init();
+
+    let schema = conn.table_get("basic_small.parquet").unwrap();
+
+    let mut stmt = conn.query("basic_small.parquet").unwrap();
+    let mut reader = stmt.start([]).unwrap();
+    assert_eq!(reader.get_schema().unwrap(), schema);
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+-----------+----------+--------------+----------+------------+-----------+
+| test_null | test_int | test_nullint | test_str | test_float | test_bool |
++-----------+----------+--------------+----------+------------+-----------+
+|           | 1        | 3            | str1     |            | true      |
+|           | 2        |              | str2     | 2.2        | false     |
+|           | 0        | 5            | a        | 3.1        |           |
+|           | 3        | 7            | b        | 3.0        | false     |
+|           | 4        | 9            | c        | 7.8        |           |
+|           | 1314     | 2            |          | -10.0      | true      |
++-----------+----------+--------------+----------+------------+-----------+"
+    );
+}
+
+#[test]
+fn query_select() {
+    let mut conn = init();
+
+    let query = "SELECT test_str, \"test_int\" FROM 'basic_small.parquet'";
+    let schema = conn.query_schema(query).unwrap();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["test_str", "test_int"]);
+
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+----------+----------+
+| test_str | test_int |
++----------+----------+
+| str1     | 1        |
+| str2     | 2        |
+| a        | 0        |
+| b        | 3        |
+| c        | 4        |
+|          | 1314     |
++----------+----------+"
+    );
+
+    let batches = connector_arrow::query(&mut conn, "SELECT * FROM 'empty.parquet'").unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+    let err = connector_arrow::query(&mut conn, "SELECT nope FROM 'basic_small.parquet'");
+    assert!(matches!(err, Err(ConnectorError::ColumnNotFound { .. })));
+}
+
+#[test]
+fn read_only() {
+    let mut conn = init();
+
+    let err = conn.append("basic_small.parquet").err().unwrap();
+    assert!(matches!(err, ConnectorError::NotSupported { .. }));
+
+    let err = conn
+        .execute("DELETE FROM 'basic_small.parquet'")
+        .unwrap_err();
+    assert!(matches!(err, ConnectorError::NotSupported { .. }));
+}
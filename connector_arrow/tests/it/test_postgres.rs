@@ -1,6 +1,32 @@
-use connector_arrow::postgres::PostgresConnection;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, BooleanBuilder, Decimal128Array, Decimal128Builder,
+    Decimal256Array, FixedSizeBinaryArray, Int32Array, Int32Builder, Int64Array,
+    IntervalDayTimeArray, IntervalMonthDayNanoArray, IntervalMonthDayNanoBuilder,
+    IntervalYearMonthArray, LargeListArray, ListArray, ListBuilder, RecordBatch, StringArray,
+    StringBuilder, StructArray, StructBuilder, Time32MillisecondArray, Time32SecondArray,
+    Time64MicrosecondArray, TimestampMicrosecondArray, TimestampMicrosecondBuilder,
+};
+use arrow::buffer::NullBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::{
+    i256, DataType, Date64Type, DurationMillisecondType, DurationSecondType, Field, Fields,
+    Float64Type, Int32Type, IntervalDayTime, IntervalMonthDayNano, IntervalUnit, Schema, TimeUnit,
+};
+use arrow::util::pretty::pretty_format_batches;
+use connector_arrow::api::{
+    Append, AsyncConnector, Connector, ReadOptions, ResultReader, SchemaEdit, SchemaGet, Statement,
+    StatementCacheStats,
+};
+use connector_arrow::postgres::{
+    DatetimeEncoding, PostgresAsyncConnection, PostgresConnection, TypeMapOverride,
+};
+use connector_arrow::util::coerce;
+use connector_arrow::ConnectorError;
 use rstest::rstest;
 
+use crate::util::{create_table, load_into_table, query_table};
 use crate::{spec, util::QueryOfSingleLiteral};
 
 fn init() -> PostgresConnection {
@@ -47,6 +73,235 @@ fn schema_edit() {
     super::tests::schema_edit(&mut conn, table_name, column_spec);
 }
 
+#[test]
+fn temp_table() {
+    let table_name = "temp_table";
+
+    let mut conn = init();
+    super::tests::temp_table(&mut conn, table_name, '"');
+}
+
+#[test]
+fn prepared_only() {
+    let conn = init();
+    super::tests::prepared_only(conn);
+}
+
+#[test]
+fn delete_by_keys() {
+    let table_name = "delete_by_keys";
+
+    let mut conn = init();
+    super::tests::delete_by_keys(&mut conn, table_name, '"');
+}
+
+#[test]
+fn merge() {
+    let table_name = "merge";
+
+    let mut conn = init();
+    super::tests::merge(&mut conn, table_name);
+}
+
+#[test]
+#[cfg(feature = "ipc")]
+fn write_ipc_stream() {
+    let mut conn = init();
+    super::tests::write_ipc_stream(&mut conn);
+}
+
+#[test]
+#[cfg(feature = "ipc")]
+fn read_ipc_stream_into() {
+    let mut conn = init();
+    super::tests::read_ipc_stream_into(&mut conn, "read_ipc_stream_into");
+}
+
+#[test]
+#[cfg(feature = "sink_csv")]
+fn write_csv() {
+    let mut conn = init();
+    super::tests::write_csv(&mut conn);
+
+    // temporal and decimal values
+    let mut conn = init().with_numeric_decimal(true);
+    conn.execute("SET TimeZone = 'Europe/Ljubljana'").unwrap();
+    let query = "SELECT
+        '2024-02-23 16:18:36.000001+01'::timestamptz AS tz,
+        '2024-02-23 16:18:36'::timestamp AS naive,
+        '2024-02-23'::date AS d,
+        '16:18:36.5'::time AS t,
+        1.50::numeric(10, 2) AS n
+    ";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    let csv = connector_arrow::sink::write_csv(&schema, &batches, Vec::new(), &Default::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "tz,naive,d,t,n\n\
+        2024-02-23T15:18:36.000001Z,2024-02-23T16:18:36,2024-02-23,16:18:36.500,1.50\n"
+    );
+}
+
+#[test]
+fn execute() {
+    let mut conn = init();
+    super::tests::execute(&mut conn, "execute_counts");
+}
+
+#[test]
+fn query_returning() {
+    let mut conn = init();
+    super::tests::query_returning(&mut conn, "query_returning");
+}
+
+#[test]
+#[cfg(feature = "pool")]
+fn pooled() {
+    let dburl = std::env::var("POSTGRES_URL").unwrap();
+    let manager =
+        r2d2_postgres::PostgresConnectionManager::new(dburl.parse().unwrap(), postgres::NoTls);
+    let pool = connector_arrow::pool::PooledConnector::new(manager).unwrap();
+    super::tests::pooled(pool);
+}
+
+#[test]
+#[cfg(feature = "pool")]
+fn query_partitioned() {
+    let dburl = std::env::var("POSTGRES_URL").unwrap();
+    let manager =
+        r2d2_postgres::PostgresConnectionManager::new(dburl.parse().unwrap(), postgres::NoTls);
+    let pool = connector_arrow::pool::PooledConnector::new(manager).unwrap();
+    super::tests::query_partitioned(pool, "query_partitioned");
+}
+
+#[test]
+fn execute_batch() {
+    let mut conn = init();
+    super::tests::execute_batch(&mut conn, "execute_batch");
+}
+
+#[test]
+fn transaction() {
+    let mut conn = init();
+    super::tests::transaction(&mut conn, "transaction_test");
+}
+
+#[test]
+fn schema_diff() {
+    let mut conn = init();
+    super::tests::schema_diff(&mut conn, "schema_diff");
+}
+
+#[test]
+fn query_schema() {
+    let mut conn = init();
+    super::tests::query_schema(&mut conn, "query_schema");
+}
+
+#[test]
+fn append_or_create() {
+    let mut conn = init();
+    super::tests::append_or_create(&mut conn, "append_or_create");
+}
+
+#[test]
+fn append_conflict() {
+    let mut conn = init();
+    super::tests::append_conflict(&mut conn, "append_conflict");
+}
+
+#[test]
+fn table_list() {
+    let mut conn = init();
+    super::tests::table_list(&mut conn, "table_list");
+}
+
+#[test]
+fn cancel() {
+    let mut conn = init();
+    super::tests::cancel(&mut conn, "SELECT pg_sleep(60)");
+}
+
+#[test]
+fn query_with_timeout() {
+    let mut conn = init();
+    super::tests::query_with_timeout(&mut conn, "SELECT pg_sleep(60)");
+}
+
+#[test]
+fn table_exists() {
+    let mut conn = init();
+    super::tests::table_exists(&mut conn, "table_exists");
+}
+
+#[test]
+fn table_primary_key() {
+    let mut conn = init();
+    super::tests::table_primary_key(&mut conn, "table_primary_key");
+}
+
+#[test]
+fn append_dictionary() {
+    let mut conn = init();
+    super::tests::append_dictionary(&mut conn, "append_dictionary");
+}
+
+#[test]
+fn table_foreign_keys() {
+    let mut conn = init();
+    super::tests::table_foreign_keys(&mut conn, "table_foreign_keys");
+}
+
+#[test]
+fn rename() {
+    let mut conn = init();
+    super::tests::rename(&mut conn, "rename");
+}
+
+#[test]
+fn list_catalogs() {
+    let mut conn = init();
+    super::tests::list_catalogs(&mut conn, "db");
+}
+
+#[test]
+fn estimate_row_count() {
+    let mut conn = init();
+    super::tests::estimate_row_count(&mut conn, "estimate_row_count");
+}
+
+#[test]
+fn query_iter() {
+    let mut conn = init();
+    super::tests::query_iter(&mut conn, "query_iter");
+}
+
+#[test]
+fn query_with_coercions() {
+    let mut conn = init();
+    super::tests::query_with_coercions(&mut conn);
+}
+
+#[test]
+fn explain_cost() {
+    let mut conn = init();
+    super::tests::explain_cost(&mut conn, "explain_cost");
+}
+
+#[test]
+fn query_cached() {
+    let conn = init();
+    super::tests::query_cached(conn, "query_cached");
+}
+
+#[test]
+fn start_named() {
+    let mut conn = init();
+    super::tests::start_named(&mut conn);
+}
+
 #[test]
 fn ident_escaping() {
     let table_name = "ident_escaping";
@@ -85,18 +340,1274 @@ fn roundtrip(#[case] table_name: &str, #[case] spec: spec::ArrowGenSpec) {
 #[case::interval(literals_cases::interval())]
 #[case::binary(literals_cases::binary())]
 #[case::text(literals_cases::text())]
+#[case::json(literals_cases::json())]
+#[case::network_addr(literals_cases::network_addr())]
 fn query_literals(#[case] queries: Vec<QueryOfSingleLiteral>) {
     let mut conn = init();
     crate::util::query_literals(&mut conn, queries)
 }
 
 #[rstest]
-#[case::bool(literals_cases::network_addr())]
+#[case::geometry(literals_cases::geometry())]
 fn query_literals_binary(#[case] queries: Vec<QueryOfSingleLiteral>) {
     let mut conn = init();
     crate::util::query_literals_binary(&mut conn, queries)
 }
 
+#[test]
+fn query_timestamptz_naive() {
+    let mut conn = init().with_timestamptz_naive(true);
+    let query = "SELECT CAST('2024-02-23 16:18:36+01' AS timestamptz) AS t";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let field = batches[0].schema().field(0).clone();
+    assert_eq!(
+        field.data_type(),
+        &DataType::Timestamp(TimeUnit::Microsecond, None)
+    );
+
+    let array = batches[0].column(0).as_primitive();
+    assert_eq!(
+        array,
+        &TimestampMicrosecondArray::from(vec![1708701516000000])
+    );
+}
+
+#[test]
+fn query_timestamptz() {
+    let table_name = "query_timestamptz";
+    let mut conn = init();
+    create_table(&mut conn, table_name, "tz timestamptz, naive timestamp");
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES ('2024-02-23 16:18:36+01', '2024-02-23 16:18:36')"
+    ))
+    .unwrap();
+
+    // session time zone must not affect the values
+    conn.execute("SET TimeZone = 'America/New_York'").unwrap();
+
+    let tz = DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()));
+    let naive = DataType::Timestamp(TimeUnit::Microsecond, None);
+
+    let schema = conn.table_get(table_name).unwrap();
+    assert_eq!(schema.field(0).data_type(), &tz);
+    assert_eq!(schema.field(1).data_type(), &naive);
+
+    let query = format!("SELECT tz, naive FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &tz);
+    assert_eq!(schema.field(1).data_type(), &naive);
+
+    let array = batches[0].column(0).as_primitive();
+    assert_eq!(
+        array,
+        &TimestampMicrosecondArray::from(vec![1708701516000000]).with_timezone("+00:00")
+    );
+    let array = batches[0].column(1).as_primitive();
+    assert_eq!(
+        array,
+        &TimestampMicrosecondArray::from(vec![1708705116000000])
+    );
+
+    conn.execute("RESET TimeZone").unwrap();
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+#[test]
+fn append_timestamptz() {
+    let table_name = "append_timestamptz";
+    let mut conn = init().with_timestamptz_append(true);
+
+    let tz = DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()));
+    let schema = Arc::new(Schema::new(vec![Field::new("t", tz, true)]));
+    let array = TimestampMicrosecondArray::from(vec![Some(1708701516000000), Some(0), None])
+        .with_timezone("+00:00");
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+
+    load_into_table(&mut conn, schema, std::slice::from_ref(&batch), table_name).unwrap();
+
+    // column is a real timestamptz, so values can be compared with SQL timestamps
+    let query = format!(
+        "SELECT t = '2024-02-23 16:18:36+01'::timestamptz AS eq, \
+        pg_typeof(t)::text AS ty FROM {table_name}"
+    );
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+-------+--------------------------+
+| eq    | ty                       |
++-------+--------------------------+
+| true  | timestamp with time zone |
+| false | timestamp with time zone |
+|       | timestamp with time zone |
++-------+--------------------------+"
+    );
+
+    let query = format!("SELECT t FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(batches, vec![batch]);
+
+    conn.table_drop(table_name).unwrap();
+}
+
+#[test]
+fn query_utf8_unchecked() {
+    let query = "SELECT 'hello' AS a, CAST('čćž 日本 🦀' AS varchar) AS b, CAST('x' AS char(3)) AS c, NULL::text AS d";
+
+    let mut conn = init();
+    let checked = connector_arrow::query(&mut conn, query).unwrap();
+
+    let mut conn = unsafe { init().with_utf8_unchecked(true) };
+    let unchecked = connector_arrow::query(&mut conn, query).unwrap();
+
+    similar_asserts::assert_eq!(checked, unchecked);
+}
+
+#[test]
+fn query_batch_size() {
+    let mut conn = init();
+    let query = "SELECT * FROM generate_series(1, 10)";
+
+    let stmt = conn.query(query).unwrap();
+    let mut stmt = stmt.with_batch_size(4).unwrap();
+    let reader = stmt.start([]).unwrap();
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    let row_counts: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+    assert_eq!(row_counts, vec![4, 4, 2]);
+
+    let stmt = conn.query(query).unwrap();
+    let err = stmt.with_batch_size(0).err().unwrap();
+    assert!(matches!(err, ConnectorError::InvalidBatchSize));
+}
+
+#[test]
+fn query_out_of_range_position() {
+    let mut conn = init();
+
+    // 'infinity' is stored as the maximum value, which does not fit after shifting to 1970
+    let query = "SELECT d, ts FROM (VALUES
+        ('2024-01-01'::date, '2024-01-01 12:00'::timestamp),
+        ('2024-01-02', '2024-01-02 12:00'),
+        ('2024-01-03', '2024-01-03 12:00'),
+        ('2024-01-04', 'infinity')
+    ) AS t(d, ts)";
+    let stmt = conn.query(query).unwrap();
+    let mut stmt = stmt.with_batch_size(3).unwrap();
+    let mut reader = stmt.start([]).unwrap();
+    assert_eq!(reader.next().unwrap().unwrap().num_rows(), 3);
+    let err = reader.next().unwrap().unwrap_err();
+    assert!(
+        matches!(&err, ConnectorError::DataOutOfRangeAt { column, row: 3 } if column == "ts"),
+        "{:?}",
+        err
+    );
+    assert_eq!(
+        err.to_string(),
+        "Value in column `ts` at row 3 fell out of supported range of Arrow types."
+    );
+    drop(reader);
+    drop(stmt);
+
+    let query = "SELECT 'infinity'::date AS d";
+    let err = connector_arrow::query(&mut conn, query).unwrap_err();
+    assert!(
+        matches!(&err, ConnectorError::DataOutOfRangeAt { column, row: 0 } if column == "d"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn query_portal_fetch_size() {
+    let mut conn = init();
+    let query = "SELECT i, i * $1 AS j FROM generate_series(1, 100) AS i";
+    let params = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![Field::new("", DataType::Int32, false)])),
+        vec![Arc::new(Int32Array::from(vec![3]))],
+    )
+    .unwrap();
+
+    let mut stmt = conn.query(query).unwrap();
+    let expected = stmt.start_batch((&params, 0)).unwrap();
+    let expected = expected.collect::<Result<Vec<_>, _>>().unwrap();
+    drop(stmt);
+
+    for fetch_size in [1, 7, 30, 100, 1000] {
+        let stmt = conn.query(query).unwrap();
+        let stmt = stmt.with_batch_size(30).unwrap();
+        let mut stmt = stmt.with_portal_fetch_size(fetch_size).unwrap();
+        let reader = stmt.start_batch((&params, 0)).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        let row_counts: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(
+            row_counts,
+            vec![30, 30, 30, 10],
+            "fetch size {}",
+            fetch_size
+        );
+        let schema = expected[0].schema();
+        similar_asserts::assert_eq!(
+            arrow::compute::concat_batches(&schema, &batches).unwrap(),
+            arrow::compute::concat_batches(&schema, &expected).unwrap()
+        );
+    }
+
+    // transaction of the portal is committed
+    let table_name = "query_portal_fetch_size";
+    create_table(&mut conn, table_name, "a int");
+    let insert = format!("INSERT INTO {table_name} SELECT generate_series(1, 10) RETURNING a");
+    let stmt = conn.query(&insert).unwrap();
+    let mut stmt = stmt.with_portal_fetch_size(3).unwrap();
+    let batches = stmt
+        .start([])
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 10);
+    drop(stmt);
+    assert_eq!(conn.estimate_row_count(table_name).unwrap(), 10);
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+
+    let stmt = conn.query(query).unwrap();
+    let err = stmt.with_portal_fetch_size(0).err().unwrap();
+    assert!(matches!(err, ConnectorError::InvalidBatchSize));
+}
+
+#[test]
+fn query_with_read_options() {
+    let mut conn = init();
+    let options = ReadOptions {
+        prefetch_rows: Some(3),
+        batch_size: Some(4),
+        ..Default::default()
+    };
+    let mut stmt = conn
+        .query_with("SELECT generate_series(1, 10) AS a", options)
+        .unwrap();
+    let batches = stmt
+        .start([])
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let row_counts: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+    assert_eq!(row_counts, vec![4, 4, 2]);
+}
+
+#[test]
+fn query_numeric_decimal() {
+    let mut conn = init().with_numeric_decimal(true);
+    let query = "SELECT
+        CAST(3950.123456 AS numeric(10, 6)) AS a,
+        CAST(-1.5 AS numeric(5, 1)) AS b,
+        CAST(1 AS numeric) AS c,
+        CAST(NULL AS numeric(4, 2)) AS d
+    ";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Decimal128(10, 6), true),
+        Field::new("b", DataType::Decimal128(5, 1), true),
+        Field::new("c", DataType::Utf8, true),
+        Field::new("d", DataType::Decimal128(4, 2), true),
+    ]));
+    let decimal = |v: Option<i128>, p: u8, s: i8| -> ArrayRef {
+        Arc::new(
+            Decimal128Array::from(vec![v])
+                .with_precision_and_scale(p, s)
+                .unwrap(),
+        )
+    };
+    let expected = RecordBatch::try_new(
+        schema,
+        vec![
+            decimal(Some(3950123456), 10, 6),
+            decimal(Some(-15), 5, 1),
+            Arc::new(StringArray::from(vec!["1"])),
+            decimal(None, 4, 2),
+        ],
+    )
+    .unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+}
+
+#[test]
+fn query_numeric_decimal256() {
+    let mut conn = init().with_numeric_decimal(true);
+    let query = "SELECT
+        CAST('12345678901234567890123456789012345678.9' AS numeric(39, 1)) AS a,
+        CAST(-1.5 AS numeric(76, 40)) AS b,
+        CAST(1 AS numeric(77, 0)) AS c,
+        CAST(NULL AS numeric(50, 2)) AS d
+    ";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Decimal256(39, 1), true),
+        Field::new("b", DataType::Decimal256(76, 40), true),
+        Field::new("c", DataType::Utf8, true),
+        Field::new("d", DataType::Decimal256(50, 2), true),
+    ]));
+    let decimal = |v: Option<&str>, p: u8, s: i8| -> ArrayRef {
+        let v = v.map(|v| i256::from_string(v).unwrap());
+        Arc::new(
+            Decimal256Array::from(vec![v])
+                .with_precision_and_scale(p, s)
+                .unwrap(),
+        )
+    };
+    let expected = RecordBatch::try_new(
+        schema,
+        vec![
+            decimal(Some("123456789012345678901234567890123456789"), 39, 1),
+            decimal(Some(&format!("-15{}", "0".repeat(39))), 76, 40),
+            Arc::new(StringArray::from(vec!["1"])),
+            decimal(None, 50, 2),
+        ],
+    )
+    .unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+}
+
+#[test]
+fn numeric_trailing_zeros() {
+    let cases = [
+        ("numeric(10, 4)", "1.5"),
+        ("numeric(10, 4)", "0100234.4400"),
+        ("numeric(10, 4)", "-0.01"),
+        ("numeric(10, 4)", "0"),
+        ("numeric(10, 2)", "1000"),
+        ("numeric(10, 2)", "10000000"),
+        ("numeric(12, 8)", "0.0001"),
+        ("numeric(12, 8)", "1.00000001"),
+        ("numeric(3, 3)", "-0.2"),
+        ("numeric(20, 0)", "1000000000000"),
+        ("numeric", "10.000"),
+        ("numeric", "0.00000"),
+        ("numeric", "-120000.0"),
+    ];
+
+    let mut conn = init();
+    for (ty, literal) in cases {
+        let query =
+            format!("SELECT CAST({literal} AS {ty}) AS a, CAST({literal} AS {ty})::text AS b");
+        let batches = connector_arrow::query(&mut conn, &query).unwrap();
+        let utf8 = batches[0].column(0).as_string::<i32>().value(0);
+        let text = batches[0].column(1).as_string::<i32>().value(0);
+        assert_eq!(utf8, text, "{} {}", ty, literal);
+    }
+
+    let mut conn = init().with_numeric_decimal(true);
+    for &(ty, literal) in cases.iter().filter(|(ty, _)| ty.contains('(')) {
+        let query =
+            format!("SELECT CAST({literal} AS {ty}) AS a, CAST({literal} AS {ty})::text AS b");
+        let batches = connector_arrow::query(&mut conn, &query).unwrap();
+        let array: &Decimal128Array = batches[0].column(0).as_primitive();
+        assert!(matches!(array.data_type(), DataType::Decimal128(_, _)));
+        let text = batches[0].column(1).as_string::<i32>().value(0);
+        assert_eq!(array.value_as_string(0), text, "{} {}", ty, literal);
+    }
+}
+
+#[test]
+fn query_aclitem() {
+    let mut conn = init();
+
+    // aclitem has no binary output function, so it is read as text
+    let query = "SELECT relacl, relname::text FROM pg_class WHERE relacl IS NOT NULL LIMIT 1;";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).name(), "relacl");
+    assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+    assert_eq!(schema.field(1).name(), "relname");
+    let relacl = batches[0].column(0).as_string::<i32>().value(0);
+    assert!(relacl.starts_with('{'), "{}", relacl);
+
+    // views that contain aclitem
+    connector_arrow::query(&mut conn, "SELECT * FROM pg_class").unwrap();
+    connector_arrow::query(&mut conn, "SELECT * FROM information_schema.tables").unwrap();
+}
+
+#[test]
+fn query_reg_types() {
+    let mut conn = init();
+
+    // OID-alias types are read as text, which contains the object name
+    let query = "SELECT 'int4'::regtype AS t, 'pg_class'::regclass AS c, \
+        'english'::regconfig AS cfg, ARRAY['int4'::regtype, 'text'::regtype] AS ts;";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).name(), "t");
+    assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+
+    let batch = &batches[0];
+    assert_eq!(batch.column(0).as_string::<i32>().value(0), "integer");
+    assert_eq!(batch.column(1).as_string::<i32>().value(0), "pg_class");
+    assert_eq!(batch.column(2).as_string::<i32>().value(0), "english");
+
+    let ts = batch.column(3).as_list::<i32>().value(0);
+    let ts = ts.as_string::<i32>();
+    assert_eq!(ts.value(0), "integer");
+    assert_eq!(ts.value(1), "text");
+
+    // catalog tables that contain regproc columns
+    connector_arrow::query(&mut conn, "SELECT * FROM pg_type").unwrap();
+}
+
+#[test]
+fn query_view_types() {
+    let query = "SELECT * FROM (VALUES \
+        ('hello'::text, 'abc'::varchar(5), '\\x0102'::bytea, ARRAY['a', NULL]::text[]), \
+        ('a string that does not fit inline into a view', NULL, '\\x'::bytea, NULL) \
+    ) AS t(t, v, b, l)";
+
+    let expected = connector_arrow::query(&mut init(), query).unwrap();
+    let mut conn = init().with_view_types(true);
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Utf8View);
+    assert_eq!(schema.field(1).data_type(), &DataType::Utf8View);
+    assert_eq!(schema.field(2).data_type(), &DataType::BinaryView);
+    let DataType::List(item) = schema.field(3).data_type() else {
+        panic!()
+    };
+    assert_eq!(item.data_type(), &DataType::Utf8View);
+
+    let batch = &batches[0];
+    assert_eq!(
+        batch.column(0).as_string_view().value(1),
+        "a string that does not fit inline into a view"
+    );
+
+    // values match the non-view path
+    for (view, expected) in batch.columns().iter().zip(expected[0].columns()) {
+        let view = cast(view, expected.data_type()).unwrap();
+        assert_eq!(&view, expected);
+    }
+}
+
+#[test]
+fn type_override() {
+    let query = "SELECT 1.5::numeric AS n, ARRAY[2.5]::numeric[] AS a, 3::int4 AS i";
+
+    // default mapping of another connection is not affected
+    let batches = connector_arrow::query(&mut init(), query).unwrap();
+    assert_eq!(batches[0].schema().field(0).data_type(), &DataType::Utf8);
+
+    let mut conn = init();
+    conn.set_type_override(|ty: &str| match ty {
+        "numeric" => Some(DataType::Float64),
+        "_numeric" => Some(DataType::new_list(DataType::Float64, true)),
+        _ => None,
+    });
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Float64);
+    assert_eq!(
+        schema.field(1).data_type(),
+        &DataType::new_list(DataType::Float64, true)
+    );
+    assert_eq!(schema.field(2).data_type(), &DataType::Int32);
+    assert_eq!(
+        batches[0].column(0).as_primitive::<Float64Type>().value(0),
+        1.5
+    );
+    assert_eq!(conn.query_schema(query).unwrap(), schema);
+
+    // override of created column types
+    struct NumericFloats;
+    impl TypeMapOverride for NumericFloats {
+        fn db_into_arrow(&self, db_ty: &str) -> Option<DataType> {
+            (db_ty == "numeric").then_some(DataType::Float64)
+        }
+        fn arrow_into_db(&self, ty: &DataType) -> Option<String> {
+            (ty == &DataType::Float64).then(|| "numeric".to_string())
+        }
+    }
+    let table_name = "type_override";
+    let mut conn = init();
+    conn.set_type_override(NumericFloats);
+    let _ = conn.table_drop(table_name);
+    let schema = Arc::new(Schema::new(vec![Field::new("f", DataType::Float64, true)]));
+    conn.table_create(table_name, schema.clone()).unwrap();
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    let column_ty = connector_arrow::query(
+        &mut init(),
+        "SELECT data_type::text FROM information_schema.columns WHERE table_name = 'type_override'",
+    )
+    .unwrap();
+    assert_eq!(
+        column_ty[0].column(0).as_string::<i32>().value(0),
+        "numeric"
+    );
+    conn.table_drop(table_name).unwrap();
+}
+
+#[test]
+fn query_date64() {
+    let mut conn = init();
+    conn.set_type_override(|ty: &str| (ty == "date").then_some(DataType::Date64));
+    let query = "SELECT '2000-01-02'::date AS d, '1969-12-31'::date AS b, NULL::date AS n";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Date64);
+
+    let dates = batches[0].column(0).as_primitive::<Date64Type>();
+    assert_eq!(dates.value(0), 946_771_200_000);
+    let before_epoch = batches[0].column(1).as_primitive::<Date64Type>();
+    assert_eq!(before_epoch.value(0), -86_400_000);
+    assert!(batches[0].column(2).is_null(0));
+
+    let query = "SELECT 'infinity'::date AS d";
+    let err = connector_arrow::query(&mut conn, query).unwrap_err();
+    assert!(
+        matches!(err, ConnectorError::DataOutOfRangeAt { .. }),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn query_interval_as_duration() {
+    let mut conn = init();
+    conn.set_type_override(|ty: &str| {
+        (ty == "interval").then_some(DataType::Duration(TimeUnit::Second))
+    });
+    let query = "SELECT 'PT3600S'::interval AS d, NULL::interval AS n";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(
+        schema.field(0).data_type(),
+        &DataType::Duration(TimeUnit::Second)
+    );
+    let durations = batches[0].column(0).as_primitive::<DurationSecondType>();
+    assert_eq!(durations.value(0), 3600);
+    assert!(batches[0].column(1).is_null(0));
+
+    let mut conn = init();
+    conn.set_type_override(|ty: &str| {
+        (ty == "interval").then_some(DataType::Duration(TimeUnit::Millisecond))
+    });
+    let query = "SELECT 'PT3600.5S'::interval AS d";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let durations = batches[0]
+        .column(0)
+        .as_primitive::<DurationMillisecondType>();
+    assert_eq!(durations.value(0), 3_600_500);
+
+    // months and days cannot be represented as a duration
+    let query = "SELECT '1 day'::interval AS d";
+    let err = connector_arrow::query(&mut conn, query).unwrap_err();
+    assert!(
+        matches!(&err, ConnectorError::DataOutOfRangeAt { column, row: 0 } if column == "d"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn datetime_encoding() {
+    assert_eq!(
+        DatetimeEncoding::from_setting("on"),
+        DatetimeEncoding::Integer
+    );
+    assert_eq!(
+        DatetimeEncoding::from_setting("off"),
+        DatetimeEncoding::Float
+    );
+
+    let mut conn = init();
+    assert_eq!(conn.datetime_encoding().unwrap(), DatetimeEncoding::Integer);
+
+    let query = "SELECT TIMESTAMP '2024-02-23 15:18:36' AS ts";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    assert_eq!(batches[0].num_rows(), 1);
+}
+
+#[test]
+fn query_uuid() {
+    let mut conn = init();
+
+    let table_name = "query_uuid";
+    create_table(&mut conn, table_name, "id uuid");
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES ('a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'), (NULL)"
+    ))
+    .unwrap();
+
+    let batches =
+        connector_arrow::query(&mut conn, &format!("SELECT id FROM {table_name}")).unwrap();
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::FixedSizeBinary(16));
+
+    let ids = batches[0].column(0).as_fixed_size_binary();
+    assert_eq!(
+        ids.value(0),
+        [
+            0xa0, 0xee, 0xbc, 0x99, 0x9c, 0x0b, 0x4e, 0xf8, 0xbb, 0x6d, 0x6b, 0xb9, 0xbd, 0x38,
+            0x0a, 0x11
+        ]
+    );
+    assert!(ids.is_null(1));
+}
+
+#[test]
+fn roundtrip_fixed_size_binary() {
+    let mut conn = init();
+    conn.set_type_override(|ty: &str| (ty == "bytea").then_some(DataType::FixedSizeBinary(32)));
+    let table_name = "roundtrip_fixed_size_binary";
+
+    let hashes: Vec<Option<[u8; 32]>> = vec![Some([0xab; 32]), None, Some([0; 32])];
+    let hashes =
+        FixedSizeBinaryArray::try_from_sparse_iter_with_size(hashes.into_iter(), 32).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "hash",
+        DataType::FixedSizeBinary(32),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(hashes)]).unwrap();
+
+    load_into_table(
+        &mut conn,
+        schema.clone(),
+        std::slice::from_ref(&batch),
+        table_name,
+    )
+    .unwrap();
+
+    let query = format!("SELECT hash FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(batches, vec![batch.clone()]);
+
+    // as a query parameter
+    let mut stmt = conn
+        .query(&format!(
+            "SELECT octet_length($1::bytea), $1 = hash FROM {table_name}"
+        ))
+        .unwrap();
+    let res = stmt.start_batch((&batch, 0)).unwrap();
+    let res = res.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        res[0]
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int32Type>()
+            .value(0),
+        32
+    );
+    assert!(res[0].column(1).as_boolean().value(0));
+    assert!(res[0].column(1).is_null(1));
+    assert!(!res[0].column(1).as_boolean().value(2));
+    drop(stmt);
+
+    // values of other lengths cannot be read
+    conn.execute(&format!("INSERT INTO {table_name} VALUES ('\\x0102')"))
+        .unwrap();
+    let res = connector_arrow::query(&mut conn, &query);
+    assert!(matches!(res, Err(ConnectorError::Arrow(_))), "{:?}", res);
+
+    conn.table_drop(table_name).unwrap();
+}
+
+#[test]
+fn append_large_list() {
+    let mut conn = init();
+    let table_name = "append_large_list";
+
+    let item = Arc::new(Field::new("item", DataType::Int32, true));
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::LargeList(item),
+        true,
+    )]));
+    let array = LargeListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), None, Some(3)]),
+        Some(vec![]),
+        None,
+    ]);
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    load_into_table(&mut conn, schema, &[batch], table_name).unwrap();
+
+    let query = format!("SELECT pg_typeof(a)::text, a::text FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    let ty = batches[0].column(0).as_string::<i32>();
+    assert_eq!(ty.value(0), "integer[]");
+    let values = batches[0].column(1).as_string::<i32>();
+    assert_eq!(values.value(0), "{1,NULL,3}");
+    assert_eq!(values.value(1), "{}");
+    assert!(batches[0].column(1).is_null(2));
+}
+
+#[test]
+fn query_enum() {
+    let mut conn = init();
+    let table_name = "query_enum";
+    conn.execute_batch(&[
+        &format!("DROP TABLE IF EXISTS {table_name}"),
+        "DROP TYPE IF EXISTS query_enum_mood",
+        "CREATE TYPE query_enum_mood AS ENUM ('sad', 'ok', 'happy')",
+        &format!("CREATE TABLE {table_name} (m query_enum_mood, ms query_enum_mood[])"),
+        &format!(
+            "INSERT INTO {table_name} VALUES \
+            ('happy', ARRAY['ok', 'sad']::query_enum_mood[]), (NULL, NULL), ('sad', '{{}}')"
+        ),
+    ])
+    .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("m", DataType::Utf8, true),
+        Field::new("ms", DataType::new_list(DataType::Utf8, true), true),
+    ]));
+    let mut ms = ListBuilder::new(StringBuilder::new());
+    ms.append_value([Some("ok"), Some("sad")]);
+    ms.append_null();
+    ms.append_value([] as [Option<&str>; 0]);
+    let expected = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec![Some("happy"), None, Some("sad")])),
+            Arc::new(ms.finish()),
+        ],
+    )
+    .unwrap();
+
+    let (_, batches) = query_table(&mut conn, table_name, '"').unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    conn.execute_batch(&[
+        &format!("DROP TABLE {table_name}"),
+        "DROP TYPE query_enum_mood",
+    ])
+    .unwrap();
+}
+
+#[test]
+fn query_bit_boolean() {
+    let mut conn = init();
+    let table_name = "query_bit_boolean";
+    conn.execute_batch(&[
+        &format!("DROP TABLE IF EXISTS {table_name}"),
+        &format!("CREATE TABLE {table_name} (b bit(1), bs bit(1)[], b4 bit(4))"),
+        &format!(
+            "INSERT INTO {table_name} VALUES \
+            (B'1', ARRAY[B'0', B'1'], B'1010'), (NULL, NULL, NULL), (B'0', '{{}}', B'0000')"
+        ),
+    ])
+    .unwrap();
+
+    // bit(1) is boolean, other bit strings are still binary
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("b", DataType::Boolean, true),
+        Field::new("bs", DataType::new_list(DataType::Boolean, true), true),
+        Field::new("b4", DataType::Binary, true),
+    ]));
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    let mut bs = ListBuilder::new(BooleanBuilder::new());
+    bs.append_value([Some(false), Some(true)]);
+    bs.append_null();
+    bs.append_value([] as [Option<bool>; 0]);
+    let batches =
+        connector_arrow::query(&mut conn, &format!("SELECT b, bs FROM {table_name}")).unwrap();
+    let expected = RecordBatch::try_new(
+        Arc::new(Schema::new(schema.fields()[..2].to_vec())),
+        vec![
+            Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])),
+            Arc::new(bs.finish()),
+        ],
+    )
+    .unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+#[test]
+fn query_bit_list() {
+    let mut conn = init().with_bit_list(true);
+    let query = "SELECT B'1011' AS b, v FROM (VALUES (B'101'::varbit), (NULL)) AS t(v)";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let mut b = ListBuilder::new(BooleanBuilder::new());
+    b.append_value([Some(true), Some(false), Some(true), Some(true)]);
+    b.append_value([Some(true), Some(false), Some(true), Some(true)]);
+    let mut v = ListBuilder::new(BooleanBuilder::new());
+    v.append_value([Some(true), Some(false), Some(true)]);
+    v.append_null();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("b", DataType::new_list(DataType::Boolean, true), true),
+        Field::new("v", DataType::new_list(DataType::Boolean, true), true),
+    ]));
+    let expected =
+        RecordBatch::try_new(schema, vec![Arc::new(b.finish()), Arc::new(v.finish())]).unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+}
+
+#[test]
+fn append_view() {
+    let mut conn = init();
+    let table_name = "append_view";
+    conn.execute_batch(&[
+        &format!("DROP TABLE IF EXISTS {table_name} CASCADE"),
+        &format!("CREATE TABLE {table_name} (a int8, b text)"),
+        &format!("CREATE VIEW {table_name}_plain AS SELECT a, b FROM {table_name}"),
+        &format!("CREATE VIEW {table_name}_trigger AS SELECT a, b FROM {table_name}"),
+        &format!(
+            "CREATE OR REPLACE FUNCTION {table_name}_insert() RETURNS trigger AS $$ \
+            BEGIN INSERT INTO {table_name} VALUES (NEW.a * 10, upper(NEW.b)); RETURN NEW; END; \
+            $$ LANGUAGE plpgsql"
+        ),
+        &format!(
+            "CREATE TRIGGER {table_name}_insert INSTEAD OF INSERT ON {table_name}_trigger \
+            FOR EACH ROW EXECUTE FUNCTION {table_name}_insert()"
+        ),
+    ])
+    .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int64Array::from(vec![Some(1), None])),
+            Arc::new(StringArray::from(vec![Some("x"), Some("y")])),
+        ],
+    )
+    .unwrap();
+
+    // plain view cannot be copied into, so rows are inserted
+    // view with INSTEAD OF trigger is copied into, which fires the trigger for each row
+    for view in ["plain", "trigger"] {
+        let mut appender = conn.append(&format!("{table_name}_{view}")).unwrap();
+        appender.append(batch.clone()).unwrap();
+        appender.finish().unwrap();
+    }
+
+    // rejected COPY does not abort an open transaction
+    let mut tx = conn.transaction().unwrap();
+    let mut appender = tx.append(&format!("{table_name}_plain")).unwrap();
+    appender.append(batch.clone()).unwrap();
+    appender.finish().unwrap();
+    tx.commit().unwrap();
+
+    let query = format!("SELECT a, b FROM {table_name} ORDER BY b, a");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+----+---+
+| a  | b |
++----+---+
+| 10 | X |
+|    | Y |
+| 1  | x |
+| 1  | x |
+|    | y |
+|    | y |
++----+---+"
+    );
+
+    conn.execute(&format!("DROP TABLE {table_name} CASCADE"))
+        .unwrap();
+}
+
+#[test]
+fn query_schema_not_executed() {
+    let mut conn = init();
+
+    // would fail with "division by zero" if it was executed
+    let schema = conn.query_schema("SELECT 1 / 0 AS x").unwrap();
+    assert_eq!(schema.field(0).name(), "x");
+    assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+}
+
+#[test]
+fn statement_cache() {
+    let mut conn = init();
+
+    // disabled by default
+    connector_arrow::query(&mut conn, "SELECT 1").unwrap();
+    let stats = conn.statement_cache_stats().unwrap();
+    assert_eq!(stats, StatementCacheStats::default());
+
+    conn.set_statement_cache_size(2).unwrap();
+    connector_arrow::query(&mut conn, "SELECT 1").unwrap();
+    connector_arrow::query(&mut conn, "SELECT 1").unwrap();
+    connector_arrow::query(&mut conn, "SELECT 1").unwrap();
+    connector_arrow::query(&mut conn, "SELECT 2").unwrap();
+    let stats = conn.statement_cache_stats().unwrap();
+    assert_eq!(
+        stats,
+        StatementCacheStats {
+            hits: 2,
+            misses: 2,
+            size: 2
+        }
+    );
+
+    // least recently used statement is evicted
+    connector_arrow::query(&mut conn, "SELECT 3").unwrap();
+    connector_arrow::query(&mut conn, "SELECT 2").unwrap();
+    let stats = conn.statement_cache_stats().unwrap();
+    assert_eq!(stats.hits, 3);
+    assert_eq!(stats.misses, 3);
+    assert_eq!(stats.size, 2);
+
+    conn.set_statement_cache_size(0).unwrap();
+    assert_eq!(conn.statement_cache_stats().unwrap().size, 0);
+}
+
+#[test]
+fn roundtrip_array() {
+    let mut conn = init();
+    let table_name = "roundtrip_array";
+
+    let ints = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), None, Some(3)]),
+        Some(vec![]),
+        None,
+        Some(vec![None]),
+    ]);
+    let mut texts = ListBuilder::new(StringBuilder::new());
+    texts.append_value([Some("a"), Some("b")]);
+    texts.append_value([] as [Option<&str>; 0]);
+    texts.append_value([None, Some("")]);
+    texts.append_null();
+    let texts = texts.finish();
+
+    let item = |ty| Arc::new(Field::new_list_field(ty, true));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ints", DataType::List(item(DataType::Int32)), true),
+        Field::new("texts", DataType::List(item(DataType::Utf8)), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(ints) as ArrayRef, Arc::new(texts) as ArrayRef],
+    )
+    .unwrap();
+
+    load_into_table(
+        &mut conn,
+        schema.clone(),
+        std::slice::from_ref(&batch),
+        table_name,
+    )
+    .unwrap();
+
+    let schema_introspected = conn.table_get(table_name).unwrap();
+    assert_eq!(
+        schema_introspected.field(0).data_type(),
+        schema.field(0).data_type()
+    );
+    assert_eq!(
+        schema_introspected.field(1).data_type(),
+        schema.field(1).data_type()
+    );
+
+    let (_, batches) = query_table(&mut conn, table_name, '"').unwrap();
+    similar_asserts::assert_eq!(batches, vec![batch]);
+
+    // multidimensional arrays are not supported
+    let res = connector_arrow::query(&mut conn, "SELECT ARRAY[[1, 2], [3, 4]]");
+    assert!(matches!(res, Err(ConnectorError::NotSupported { .. })));
+}
+
+#[test]
+fn query_money() {
+    let mut conn = init();
+    let query = "SELECT m FROM (VALUES ('$1,234.56'::money), ('-$0.07'), (NULL)) AS t(m)";
+
+    let ty = DataType::Decimal128(19, 2);
+    let schema = Arc::new(Schema::new(vec![Field::new("m", ty.clone(), true)]));
+    let expected = Decimal128Array::from(vec![Some(123456), Some(-7), None]).with_data_type(ty);
+    let expected = RecordBatch::try_new(schema.clone(), vec![Arc::new(expected)]).unwrap();
+
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+    assert_eq!(conn.query_schema(query).unwrap(), schema);
+}
+
+#[test]
+fn query_money_array() {
+    let mut conn = init();
+    let table_name = "query_money_array";
+
+    create_table(&mut conn, table_name, "m money[]");
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES \
+        (ARRAY[1234.56::numeric::money, NULL, (-0.01)::numeric::money]), \
+        ('{{}}'), \
+        (NULL), \
+        (ARRAY[92233720368547758.07::numeric::money])"
+    ))
+    .unwrap();
+
+    let ty = DataType::Decimal128(19, 2);
+    let mut expected = ListBuilder::new(Decimal128Builder::new().with_data_type(ty.clone()));
+    expected.append_value([Some(123456), None, Some(-1)]);
+    expected.append_value([]);
+    expected.append_null();
+    expected.append_value([Some(i64::MAX as i128)]);
+    let expected = expected.finish();
+
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "m",
+        DataType::new_list(ty, true),
+        true,
+    )]));
+    let expected = RecordBatch::try_new(schema.clone(), vec![Arc::new(expected)]).unwrap();
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    let (_, batches) = query_table(&mut conn, table_name, '"').unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+}
+
+#[test]
+fn roundtrip_bool_array() {
+    let mut conn = init();
+    let table_name = "roundtrip_bool_array";
+
+    let mut bools = ListBuilder::new(BooleanBuilder::new());
+    bools.append_value([Some(true), Some(false), None]);
+    bools.append_value([]);
+    bools.append_value([None]);
+    bools.append_null();
+    bools.append_value([Some(false)]);
+    let bools = bools.finish();
+
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "b",
+        DataType::new_list(DataType::Boolean, true),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(bools)]).unwrap();
+
+    load_into_table(
+        &mut conn,
+        schema.clone(),
+        std::slice::from_ref(&batch),
+        table_name,
+    )
+    .unwrap();
+
+    let query = format!("SELECT b FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(batches, vec![batch.clone()]);
+
+    // the same values, written as literals
+    let literals = connector_arrow::query(
+        &mut conn,
+        "SELECT b FROM (VALUES \
+        (ARRAY[true, false, NULL]), ('{}'::bool[]), (ARRAY[NULL]::bool[]), (NULL), (ARRAY[false]) \
+        ) AS t(b)",
+    )
+    .unwrap();
+    similar_asserts::assert_eq!(literals, vec![batch.clone()]);
+
+    // as a query parameter
+    let mut stmt = conn.query("SELECT $1::bool[] AS b").unwrap();
+    for row in 0..batch.num_rows() {
+        let res = stmt.start_batch((&batch, row)).unwrap();
+        let res = res.collect::<Result<Vec<_>, _>>().unwrap();
+        similar_asserts::assert_eq!(res, vec![batch.slice(row, 1)]);
+    }
+}
+
+#[test]
+fn query_temporal_array() {
+    let mut conn = init();
+    let table_name = "query_temporal_array";
+
+    create_table(
+        &mut conn,
+        table_name,
+        "intervals interval[], timestamps timestamptz[], naive timestamp[]",
+    );
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES \
+        (ARRAY['1 year 2 mons 3 days 00:00:04', NULL, '-1 mons -00:00:00.000001']::interval[], \
+         ARRAY['2023-11-14 22:13:20.000001+00', NULL]::timestamptz[], \
+         ARRAY[NULL, '2000-01-01 00:00:00']::timestamp[]), \
+        ('{{}}', ARRAY['1969-12-31 23:59:59.999999+00']::timestamptz[], '{{}}'), \
+        (NULL, NULL, NULL)"
+    ))
+    .unwrap();
+
+    let mut intervals = ListBuilder::new(IntervalMonthDayNanoBuilder::new());
+    intervals.append_value([
+        Some(IntervalMonthDayNano::new(14, 3, 4_000_000_000)),
+        None,
+        Some(IntervalMonthDayNano::new(-1, 0, -1_000)),
+    ]);
+    intervals.append_value([] as [Option<IntervalMonthDayNano>; 0]);
+    intervals.append_null();
+
+    let tz = "+00:00";
+    let mut timestamps = ListBuilder::new(TimestampMicrosecondBuilder::new().with_timezone(tz));
+    timestamps.append_value([Some(1_700_000_000_000_001), None]);
+    timestamps.append_value([Some(-1)]);
+    timestamps.append_null();
+
+    let mut naive = ListBuilder::new(TimestampMicrosecondBuilder::new());
+    naive.append_value([None, Some(946_684_800_000_000)]);
+    naive.append_value([] as [Option<i64>; 0]);
+    naive.append_null();
+
+    let item = |ty| Arc::new(Field::new_list_field(ty, true));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "intervals",
+            DataType::List(item(DataType::Interval(IntervalUnit::MonthDayNano))),
+            true,
+        ),
+        Field::new(
+            "timestamps",
+            DataType::List(item(DataType::Timestamp(
+                TimeUnit::Microsecond,
+                Some(tz.into()),
+            ))),
+            true,
+        ),
+        Field::new(
+            "naive",
+            DataType::List(item(DataType::Timestamp(TimeUnit::Microsecond, None))),
+            true,
+        ),
+    ]));
+    let expected = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(intervals.finish()) as ArrayRef,
+            Arc::new(timestamps.finish()),
+            Arc::new(naive.finish()),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    let (_, batches) = query_table(&mut conn, table_name, '"').unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+}
+
+#[test]
+fn roundtrip_json() {
+    let mut conn = init();
+    let table_name = "roundtrip_json";
+
+    create_table(&mut conn, table_name, "j json, jb jsonb");
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("j", DataType::Utf8, true),
+        Field::new("jb", DataType::Utf8, true),
+    ]));
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    // jsonb is normalized, so values are written in its output format
+    let values = vec![
+        Some(r#"{"a": {"b": [1, 2.5, null, "c"]}, "dd": true}"#),
+        Some(r#"[{"a": []}, {}, "x", 1]"#),
+        Some(r#""plain string""#),
+        None,
+    ];
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(values.clone())) as ArrayRef,
+            Arc::new(StringArray::from(values)),
+        ],
+    )
+    .unwrap();
+
+    let mut appender = conn.append(table_name).unwrap();
+    appender.append(batch.clone()).unwrap();
+    appender.finish().unwrap();
+
+    let (_, batches) = query_table(&mut conn, table_name, '"').unwrap();
+    similar_asserts::assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+fn query_jsonb_struct() {
+    let fields = Fields::from(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Utf8, true),
+    ]);
+    let struct_ty = DataType::Struct(fields.clone());
+
+    let mut conn = init();
+    let ty = struct_ty.clone();
+    conn.set_type_override(move |db_ty: &str| (db_ty == "jsonb").then(|| ty.clone()));
+
+    let query = r#"SELECT j FROM (VALUES
+        ('{"a": 1, "b": "x"}'::jsonb),
+        ('{"b": 2, "c": true}'),
+        ('{"a": "not a number", "b": null}'),
+        ('[1, 2]'),
+        (NULL)
+    ) AS t(j)"#;
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+
+    let schema = Arc::new(Schema::new(vec![Field::new("j", struct_ty, true)]));
+    let expected = StructArray::new(
+        fields,
+        vec![
+            Arc::new(Int64Array::from(vec![Some(1), None, None, None, None])) as ArrayRef,
+            Arc::new(StringArray::from(vec![
+                Some("x"),
+                Some("2"),
+                None,
+                None,
+                None,
+            ])),
+        ],
+        Some(NullBuffer::from(vec![true, true, true, false, false])),
+    );
+    let expected = RecordBatch::try_new(schema.clone(), vec![Arc::new(expected)]).unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+    assert_eq!(conn.query_schema(query).unwrap(), schema);
+}
+
+#[test]
+fn convert_timestamps_to() {
+    let mut conn = init();
+    let query = "SELECT
+        '2024-02-23 16:18:36+01'::timestamptz AS tz,
+        '2024-02-23 16:18:36'::timestamp AS naive";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let schema = batches[0].schema();
+    let instant = 1708701516000000; // 2024-02-23 15:18:36 UTC
+    let naive = 1708705116000000; // 2024-02-23 16:18:36
+
+    // instant is kept, only the time zone changes
+    let (schema_ny, batches_ny) =
+        coerce::convert_timestamps_to(schema.clone(), &batches, "America/New_York", false).unwrap();
+    let tz_ny = DataType::Timestamp(TimeUnit::Microsecond, Some("America/New_York".into()));
+    let naive_ty = DataType::Timestamp(TimeUnit::Microsecond, None);
+    assert_eq!(schema_ny.field(0).data_type(), &tz_ny);
+    assert_eq!(schema_ny.field(1).data_type(), &naive_ty);
+    let tz_values = batches_ny[0].column(0).as_primitive();
+    assert_eq!(
+        tz_values,
+        &TimestampMicrosecondArray::from(vec![instant]).with_timezone("America/New_York")
+    );
+    let naive_values = batches_ny[0].column(1).as_primitive();
+    assert_eq!(naive_values, &TimestampMicrosecondArray::from(vec![naive]));
+
+    // values are shifted to wall-clock time
+    let (schema_local, batches_local) =
+        coerce::convert_timestamps_to(schema, &batches, "-05:00", true).unwrap();
+    assert_eq!(schema_local.field(0).data_type(), &naive_ty);
+    let local_values = batches_local[0].column(0).as_primitive();
+    let local = instant - 5 * 3600 * 1000000; // 2024-02-23 10:18:36
+    assert_eq!(local_values, &TimestampMicrosecondArray::from(vec![local]));
+}
+
 /// These tests cases are used to test of querying of Postgres-native types
 /// that cannot be obtained by converting Arrow into PostgreSQL.
 pub mod literals_cases {
@@ -108,6 +1619,8 @@ pub mod literals_cases {
         vec![
             ("boolean", "false", false).into(),
             ("boolean", "true", true).into(),
+            ("bit(1)", "B'0'", false).into(),
+            ("bit(1)", "B'1'", true).into(),
         ]
     }
 
@@ -279,6 +1792,24 @@ pub mod literals_cases {
         ]
     }
 
+    pub fn json() -> Vec<QueryOfSingleLiteral> {
+        vec![
+            (
+                "json",
+                r#"'{"b": [1, {}],  "a": null}'"#,
+                r#"{"b": [1, {}],  "a": null}"#.to_string(),
+            )
+                .into(),
+            (
+                "jsonb",
+                r#"'{"b": [1, {}],  "a": null}'"#,
+                r#"{"a": null, "b": [1, {}]}"#.to_string(),
+            )
+                .into(),
+            ("jsonb", "'[]'", "[]".to_string()).into(),
+        ]
+    }
+
     pub fn network_addr() -> Vec<QueryOfSingleLiteral> {
         vec![
             (
@@ -333,8 +1864,19 @@ pub mod literals_cases {
         ]
     }
 
+    pub fn geometry() -> Vec<QueryOfSingleLiteral> {
+        vec![(
+            "point",
+            "'(1, 2)'",
+            vec![
+                0x3f, 0xf0, 0, 0, 0, 0, 0, 0, // x: 1.0
+                0x40, 0x00, 0, 0, 0, 0, 0, 0, // y: 2.0
+            ],
+        )
+            .into()]
+    }
+
     // TODO:
-    // point
     // box
     // circle
     // line
@@ -342,10 +1884,6 @@ pub mod literals_cases {
     // polygon
     // path
     //
-    // money
-    //
-    // json
-    // jsonb
     // xml
     // uuid
     //
@@ -356,3 +1894,255 @@ pub mod literals_cases {
     // txid_snapshot
     //
 }
+
+#[test]
+fn async_connector() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let url = std::env::var("POSTGRES_URL").unwrap();
+    let table_name = "async_connector";
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let (client, connection) = tokio_postgres::connect(&url, tokio_postgres::NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+        let mut conn = PostgresAsyncConnection::new(client);
+
+        conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"))
+            .await
+            .unwrap();
+        conn.execute(&format!("CREATE TABLE {table_name} (a BIGINT, b TEXT)"))
+            .await
+            .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), None, Some(3)])),
+                Arc::new(StringArray::from(vec![Some("x"), Some("y"), None])),
+            ],
+        )
+        .unwrap();
+        conn.append(table_name, vec![batch.clone(), batch.clone()])
+            .await
+            .unwrap();
+
+        let query = format!("SELECT a, b FROM {table_name}");
+        let mut reader = conn.query(&query).await.unwrap();
+        assert_eq!(reader.get_schema().unwrap(), batch.schema());
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        similar_asserts::assert_eq!(
+            batches,
+            vec![arrow::compute::concat_batches(&batch.schema(), &[batch.clone(), batch]).unwrap()]
+        );
+
+        conn.execute(&format!("DROP TABLE {table_name}"))
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+fn roundtrip_composite_array() {
+    let mut conn = init();
+    let table_name = "roundtrip_composite_array";
+    conn.execute_batch(&[
+        &format!("DROP TABLE IF EXISTS {table_name}"),
+        "DROP TYPE IF EXISTS composite_pair",
+        "CREATE TYPE composite_pair AS (a int, b text)",
+        &format!("CREATE TABLE {table_name} (id int, ps composite_pair[])"),
+        &format!(
+            "INSERT INTO {table_name} VALUES \
+            (1, ARRAY[ROW(1, 'x'), NULL, ROW(NULL, 'y')]::composite_pair[]), \
+            (2, NULL), (3, '{{}}')"
+        ),
+    ])
+    .unwrap();
+
+    let fields = Fields::from(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Utf8, true),
+    ]);
+    let mut ps = ListBuilder::new(StructBuilder::from_fields(fields.clone(), 0));
+    for (valid, a, b) in [
+        (true, Some(1), Some("x")),
+        (false, None, None),
+        (true, None, Some("y")),
+    ] {
+        let item = ps.values();
+        item.field_builder::<Int32Builder>(0)
+            .unwrap()
+            .append_option(a);
+        item.field_builder::<StringBuilder>(1)
+            .unwrap()
+            .append_option(b);
+        item.append(valid);
+    }
+    ps.append(true);
+    ps.append(false);
+    ps.append(true);
+    let expected = ps.finish();
+
+    let query = format!("SELECT ps FROM {table_name} ORDER BY id");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    assert_eq!(
+        batches[0].schema().field(0).data_type(),
+        &DataType::List(Arc::new(Field::new_list_field(
+            DataType::Struct(fields),
+            true
+        )))
+    );
+    similar_asserts::assert_eq!(batches[0].column(0).as_list::<i32>(), &expected);
+
+    // composites that contain arrays cannot be decoded
+    conn.execute_batch(&[
+        "DROP TYPE IF EXISTS composite_nested",
+        "CREATE TYPE composite_nested AS (a int[])",
+    ])
+    .unwrap();
+    let query = "SELECT ARRAY[ROW(ARRAY[1])]::composite_nested[] AS ns";
+    let err = connector_arrow::query(&mut conn, query).unwrap_err();
+    assert!(
+        matches!(err, ConnectorError::NotSupported { .. }),
+        "{:?}",
+        err
+    );
+
+    conn.execute_batch(&[
+        &format!("DROP TABLE {table_name}"),
+        "DROP TYPE composite_pair",
+        "DROP TYPE composite_nested",
+    ])
+    .unwrap();
+}
+
+#[test]
+fn query_network_addr_text() {
+    let mut conn = init().with_network_addr_text(true);
+    let query = "SELECT \
+        '192.168/25'::cidr AS c4, \
+        '2001:4f8:3:ba:2e0:81ff:fe22:d1f1/128'::cidr AS c6, \
+        '192.168.0.1/24'::inet AS i24, \
+        '192.168.0.1'::inet AS i32, \
+        '08:00:2b:01:02:03'::macaddr AS m, \
+        '08:00:2b:01:02:03:04:05'::macaddr8 AS m8";
+    let batches = connector_arrow::query(&mut conn, query).unwrap();
+    let batch = &batches[0];
+
+    let mut values = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        assert_eq!(field.data_type(), &DataType::Utf8);
+        values.push(column.as_string::<i32>().value(0).to_string());
+    }
+    assert_eq!(
+        values,
+        vec![
+            "192.168.0.0/25",
+            "2001:4f8:3:ba:2e0:81ff:fe22:d1f1/128",
+            "192.168.0.1/24",
+            "192.168.0.1",
+            "08:00:2b:01:02:03",
+            "08:00:2b:01:02:03:04:05",
+        ]
+    );
+}
+
+#[test]
+fn roundtrip_interval() {
+    let mut conn = init();
+    let table_name = "roundtrip_interval";
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ym", DataType::Interval(IntervalUnit::YearMonth), true),
+        Field::new("dt", DataType::Interval(IntervalUnit::DayTime), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(IntervalYearMonthArray::from(vec![Some(14), None, Some(-3)])),
+            Arc::new(IntervalDayTimeArray::from(vec![
+                Some(IntervalDayTime::new(3, 1500)),
+                Some(IntervalDayTime::new(-1, -250)),
+                None,
+            ])),
+        ],
+    )
+    .unwrap();
+
+    load_into_table(&mut conn, schema, &[batch], table_name).unwrap();
+
+    // both layouts are read as MonthDayNano
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ym", DataType::Interval(IntervalUnit::MonthDayNano), true),
+        Field::new("dt", DataType::Interval(IntervalUnit::MonthDayNano), true),
+    ]));
+    let expected = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(IntervalMonthDayNanoArray::from(vec![
+                Some(IntervalMonthDayNano::new(14, 0, 0)),
+                None,
+                Some(IntervalMonthDayNano::new(-3, 0, 0)),
+            ])),
+            Arc::new(IntervalMonthDayNanoArray::from(vec![
+                Some(IntervalMonthDayNano::new(0, 3, 1_500_000_000)),
+                Some(IntervalMonthDayNano::new(0, -1, -250_000_000)),
+                None,
+            ])),
+        ],
+    )
+    .unwrap();
+
+    let query = format!("SELECT ym, dt FROM {table_name}");
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+
+    conn.table_drop(table_name).unwrap();
+}
+
+#[test]
+fn query_time_precision() {
+    let mut conn = init();
+    let table_name = "query_time_precision";
+    conn.execute_batch(&[
+        &format!("DROP TABLE IF EXISTS {table_name}"),
+        &format!("CREATE TABLE {table_name} (t0 time(0), t3 time(3), t6 time(6), t time)"),
+        &format!(
+            "INSERT INTO {table_name} VALUES \
+            ('17:18:36.789123', '17:18:36.789123', '17:18:36.789123', '17:18:36.789123')"
+        ),
+    ])
+    .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("t0", DataType::Time32(TimeUnit::Second), true),
+        Field::new("t3", DataType::Time32(TimeUnit::Millisecond), true),
+        Field::new("t6", DataType::Time64(TimeUnit::Microsecond), true),
+        Field::new("t", DataType::Time64(TimeUnit::Microsecond), true),
+    ]));
+    let expected = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            // values are rounded to the declared precision
+            Arc::new(Time32SecondArray::from(vec![62317])),
+            Arc::new(Time32MillisecondArray::from(vec![62316789])),
+            Arc::new(Time64MicrosecondArray::from(vec![62316789123])),
+            Arc::new(Time64MicrosecondArray::from(vec![62316789123])),
+        ],
+    )
+    .unwrap();
+
+    let (_, batches) = query_table(&mut conn, table_name, '"').unwrap();
+    similar_asserts::assert_eq!(batches, vec![expected]);
+    assert_eq!(conn.table_get(table_name).unwrap(), schema);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
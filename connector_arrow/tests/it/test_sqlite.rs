@@ -1,6 +1,23 @@
-use super::spec;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, Float64Array, Int64Array, ListArray, RecordBatch, StringArray,
+    StructArray,
+};
+use arrow::datatypes::{DataType, Field, Fields, Int64Type, Schema};
+use arrow::util::pretty::pretty_format_batches;
+use connector_arrow::any::AnyConnection;
+use connector_arrow::api::{
+    Append, AppenderOptions, Connector, NullPolicy, ReadOptions, ResultReader, SchemaEdit,
+    SchemaGet, Statement, StatementCacheStats,
+};
+use connector_arrow::util::coerce;
+use connector_arrow::ConnectorError;
+use rand::SeedableRng;
 use rstest::*;
 
+use super::spec;
+
 fn init() -> connector_arrow::sqlite::SQLiteConnection {
     let _ = env_logger::builder().is_test(true).try_init();
 
@@ -47,6 +64,21 @@ fn roundtrip(#[case] table_name: &str, #[case] spec: spec::ArrowGenSpec) {
     super::tests::roundtrip(&mut conn, table_name, spec, '"', false);
 }
 
+#[test]
+fn roundtrip_time_cast_to_schema() {
+    let table_name = "roundtrip_time_cast_to_schema";
+    let mut conn = init();
+
+    // time is stored as INTEGER, so original schema is needed to read it back
+    let mut rng = rand_chacha::ChaCha8Rng::from_seed([0; 32]);
+    let (schema, batches) = super::generator::generate_batch(spec::time(), &mut rng);
+    super::util::load_into_table(&mut conn, schema.clone(), &batches, table_name).unwrap();
+
+    let (_, batches_query) = super::util::query_table(&mut conn, table_name, '"').unwrap();
+    let batches_query = coerce::cast_to_schema(&batches_query, schema).unwrap();
+    similar_asserts::assert_eq!(batches, batches_query);
+}
+
 #[test]
 #[ignore] // cannot introspect the Null column
 fn schema_get() {
@@ -66,6 +98,211 @@ fn schema_edit() {
     super::tests::schema_edit(&mut conn, table_name, column_spec);
 }
 
+#[test]
+fn temp_table() {
+    let table_name = "temp_table";
+
+    let mut conn = init();
+    super::tests::temp_table(&mut conn, table_name, '"');
+}
+
+#[test]
+fn delete_by_keys() {
+    let table_name = "delete_by_keys";
+
+    let mut conn = init();
+    super::tests::delete_by_keys(&mut conn, table_name, '"');
+}
+
+#[test]
+fn merge() {
+    let table_name = "merge";
+
+    let mut conn = init();
+    super::tests::merge(&mut conn, table_name);
+}
+
+#[test]
+#[cfg(feature = "ipc")]
+fn write_ipc_stream() {
+    let mut conn = init();
+    super::tests::write_ipc_stream(&mut conn);
+}
+
+#[test]
+#[cfg(feature = "ipc")]
+fn read_ipc_stream_into() {
+    let mut conn = init();
+    super::tests::read_ipc_stream_into(&mut conn, "read_ipc_stream_into");
+}
+
+#[test]
+#[cfg(feature = "sink_csv")]
+fn write_csv() {
+    let mut conn = init();
+    super::tests::write_csv(&mut conn);
+}
+
+#[test]
+fn execute() {
+    let mut conn = init();
+    super::tests::execute(&mut conn, "execute_counts");
+}
+
+#[test]
+fn query_returning() {
+    let mut conn = init();
+    super::tests::query_returning(&mut conn, "query_returning");
+}
+
+#[test]
+#[cfg(feature = "pool")]
+fn pooled() {
+    let manager = r2d2_sqlite::SqliteConnectionManager::memory();
+    let pool = connector_arrow::pool::PooledConnector::new(manager).unwrap();
+    super::tests::pooled(pool);
+}
+
+#[test]
+#[cfg(feature = "pool")]
+fn query_partitioned() {
+    let path = std::env::temp_dir().join("connector_arrow_query_partitioned.db");
+    let manager = r2d2_sqlite::SqliteConnectionManager::file(path);
+    let pool = connector_arrow::pool::PooledConnector::new(manager).unwrap();
+    super::tests::query_partitioned(pool, "query_partitioned");
+}
+
+#[test]
+fn execute_batch() {
+    let mut conn = init();
+    super::tests::execute_batch(&mut conn, "execute_batch");
+}
+
+#[test]
+fn transaction() {
+    let mut conn = init();
+    super::tests::transaction(&mut conn, "transaction_test");
+}
+
+#[test]
+fn schema_diff() {
+    let mut conn = init();
+    super::tests::schema_diff(&mut conn, "schema_diff");
+}
+
+#[test]
+fn query_schema() {
+    let mut conn = init();
+    super::tests::query_schema(&mut conn, "query_schema");
+}
+
+#[test]
+fn append_or_create() {
+    let mut conn = init();
+    super::tests::append_or_create(&mut conn, "append_or_create");
+}
+
+#[test]
+fn append_conflict() {
+    let mut conn = init();
+    super::tests::append_conflict(&mut conn, "append_conflict");
+}
+
+#[test]
+fn table_list() {
+    let mut conn = init();
+    super::tests::table_list(&mut conn, "table_list");
+}
+
+#[test]
+fn cancel() {
+    let mut conn = init();
+    let query = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+        SELECT COUNT(*) FROM c";
+    super::tests::cancel(&mut conn, query);
+}
+
+#[test]
+fn query_with_timeout() {
+    let mut conn = init();
+    let query = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+        SELECT COUNT(*) FROM c";
+    super::tests::query_with_timeout(&mut conn, query);
+}
+
+#[test]
+fn table_exists() {
+    let mut conn = init();
+    super::tests::table_exists(&mut conn, "table_exists");
+
+    // identifiers are case-insensitive
+    conn.execute("CREATE TABLE table_exists_case (a INTEGER)")
+        .unwrap();
+    assert!(conn.table_exists("TABLE_EXISTS_CASE").unwrap());
+    conn.table_drop_if_exists("Table_Exists_Case").unwrap();
+    assert!(!conn.table_exists("table_exists_case").unwrap());
+}
+
+#[test]
+fn table_primary_key() {
+    let mut conn = init();
+    super::tests::table_primary_key(&mut conn, "table_primary_key");
+}
+
+#[test]
+fn append_dictionary() {
+    let mut conn = init();
+    super::tests::append_dictionary(&mut conn, "append_dictionary");
+}
+
+#[test]
+fn table_foreign_keys() {
+    let mut conn = init();
+    super::tests::table_foreign_keys(&mut conn, "table_foreign_keys");
+}
+
+#[test]
+fn rename() {
+    let mut conn = init();
+    super::tests::rename(&mut conn, "rename");
+}
+
+#[test]
+fn list_catalogs() {
+    let mut conn = init();
+    super::tests::list_catalogs(&mut conn, "main");
+}
+
+#[test]
+fn estimate_row_count() {
+    let mut conn = init();
+    super::tests::estimate_row_count(&mut conn, "estimate_row_count");
+}
+
+#[test]
+fn query_iter() {
+    let mut conn = init();
+    super::tests::query_iter(&mut conn, "query_iter");
+}
+
+#[test]
+fn query_with_coercions() {
+    let mut conn = init();
+    super::tests::query_with_coercions(&mut conn);
+}
+
+#[test]
+fn query_cached() {
+    let conn = init();
+    super::tests::query_cached(conn, "query_cached");
+}
+
+#[test]
+fn start_named() {
+    let mut conn = init();
+    super::tests::start_named(&mut conn);
+}
+
 #[test]
 fn ident_escaping() {
     let table_name = "simple::ident_escaping";
@@ -73,3 +310,452 @@ fn ident_escaping() {
     let mut conn = init();
     super::tests::ident_escaping(&mut conn, table_name);
 }
+
+#[test]
+fn append_null_as_default() {
+    let mut conn = init();
+    conn.inner_mut()
+        .execute(
+            "CREATE TABLE null_as_default (a INTEGER NOT NULL, b INTEGER NOT NULL DEFAULT 42)",
+            (),
+        )
+        .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Int64, true),
+    ]));
+    let options = AppenderOptions {
+        null_as_default: true,
+        ..Default::default()
+    };
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![Some(1), None])) as ArrayRef,
+        ],
+    )
+    .unwrap();
+    let mut appender = conn
+        .append_with("null_as_default", options.clone())
+        .unwrap();
+    appender.append(batch).unwrap();
+    appender.finish().unwrap();
+
+    // column without default
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![None])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![Some(3)])) as ArrayRef,
+        ],
+    )
+    .unwrap();
+    let mut appender = conn.append_with("null_as_default", options).unwrap();
+    let err = appender.append(batch).unwrap_err();
+    assert!(matches!(err, ConnectorError::DataSchemaMismatch(_)));
+    drop(appender);
+
+    let batches = connector_arrow::query(&mut conn, "SELECT * FROM null_as_default").unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+---+----+\n\
+         | a | b  |\n\
+         +---+----+\n\
+         | 1 | 1  |\n\
+         | 2 | 42 |\n\
+         +---+----+"
+    );
+}
+
+#[test]
+fn query_stream() {
+    let mut conn = init();
+
+    conn.execute("CREATE TABLE query_stream (a INTEGER, b TEXT)")
+        .unwrap();
+    conn.execute(
+        "WITH RECURSIVE s(i) AS (SELECT 0 UNION ALL SELECT i + 1 FROM s WHERE i < 24)
+        INSERT INTO query_stream SELECT iif(i < 12, NULL, i), 'x' FROM s",
+    )
+    .unwrap();
+
+    let mut stmt = conn
+        .query("SELECT a, b, NULL AS c FROM query_stream")
+        .unwrap();
+    let empty = RecordBatch::new_empty(std::sync::Arc::new(arrow::datatypes::Schema::empty()));
+    let mut reader = stmt.start_stream((&empty, 0), 10).unwrap();
+
+    // types of table columns are known, even though the first batch contains only nulls
+    let schema = reader.get_schema().unwrap();
+    assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+    assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+    assert_eq!(schema.field(2).data_type(), &DataType::Null);
+
+    let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    let sizes: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+    assert_eq!(sizes, vec![10, 10, 5]);
+
+    let last = batches[2].column(0).as_primitive::<Int64Type>();
+    assert_eq!(last.value(4), 24);
+    assert_eq!(batches[0].column(0).null_count(), 10);
+}
+
+#[test]
+fn query_stream_coalesce() {
+    let mut conn = init();
+
+    let mut stmt = conn
+        .query(
+            "WITH RECURSIVE s(i) AS (SELECT 0 UNION ALL SELECT i + 1 FROM s WHERE i < 249)
+            SELECT i FROM s",
+        )
+        .unwrap();
+    let empty = RecordBatch::new_empty(std::sync::Arc::new(arrow::datatypes::Schema::empty()));
+    let reader = stmt.start_stream((&empty, 0), 1).unwrap();
+
+    let mut reader = connector_arrow::util::coalesce(reader, 100);
+    let schema = reader.get_schema().unwrap();
+
+    let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    let sizes: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+    assert_eq!(sizes, vec![100, 100, 50]);
+    assert!(batches.iter().all(|b| b.schema() == schema));
+}
+
+#[test]
+fn table_list_internal() {
+    let mut conn = init();
+    conn.execute("CREATE TABLE table_list_internal (a INTEGER PRIMARY KEY AUTOINCREMENT)")
+        .unwrap();
+    conn.execute("INSERT INTO table_list_internal DEFAULT VALUES")
+        .unwrap();
+    conn.execute("ANALYZE").unwrap();
+
+    // sqlite_sequence and sqlite_stat1 are not listed
+    let tables = conn.table_list().unwrap();
+    assert_eq!(tables, vec!["table_list_internal".to_string()]);
+}
+
+/// Examples from https://sqlite.org/datatype3.html#affinity_name_examples
+#[rstest]
+#[case::int("INT", Some(DataType::Int64))]
+#[case::integer("INTEGER", Some(DataType::Int64))]
+#[case::tinyint("TINYINT", Some(DataType::Int64))]
+#[case::smallint("SMALLINT", Some(DataType::Int64))]
+#[case::mediumint("MEDIUMINT", Some(DataType::Int64))]
+#[case::bigint("BIGINT", Some(DataType::Int64))]
+#[case::unsigned_big_int("UNSIGNED BIG INT", Some(DataType::Int64))]
+#[case::int2("INT2", Some(DataType::Int64))]
+#[case::int8("INT8", Some(DataType::Int64))]
+#[case::character("CHARACTER(20)", Some(DataType::LargeUtf8))]
+#[case::varchar("VARCHAR(255)", Some(DataType::LargeUtf8))]
+#[case::varying_character("VARYING CHARACTER(255)", Some(DataType::LargeUtf8))]
+#[case::nchar("NCHAR(55)", Some(DataType::LargeUtf8))]
+#[case::native_character("NATIVE CHARACTER(70)", Some(DataType::LargeUtf8))]
+#[case::nvarchar("NVARCHAR(100)", Some(DataType::LargeUtf8))]
+#[case::text("TEXT", Some(DataType::LargeUtf8))]
+#[case::clob("CLOB", Some(DataType::LargeUtf8))]
+#[case::blob("BLOB", Some(DataType::LargeBinary))]
+#[case::no_type("", Some(DataType::LargeBinary))]
+#[case::real("REAL", Some(DataType::Float64))]
+#[case::double("DOUBLE", Some(DataType::Float64))]
+#[case::double_precision("DOUBLE PRECISION", Some(DataType::Float64))]
+#[case::float("FLOAT", Some(DataType::Float64))]
+#[case::numeric("NUMERIC", None)]
+#[case::decimal("DECIMAL(10,5)", None)]
+#[case::boolean("BOOLEAN", None)]
+#[case::date("DATE", None)]
+#[case::datetime("DATETIME", None)]
+// contains "INT", so it has INTEGER affinity
+#[case::floating_point("FLOATING POINT", Some(DataType::Int64))]
+#[case::string("STRING", None)]
+fn table_get_affinity(#[case] decl_ty: &str, #[case] expected: Option<DataType>) {
+    let mut conn = init();
+    conn.execute(&format!(
+        "CREATE TABLE affinity (id INTEGER PRIMARY KEY, a {decl_ty}) WITHOUT ROWID"
+    ))
+    .unwrap();
+
+    let res = conn.table_get("affinity");
+    match expected {
+        Some(expected) => assert_eq!(res.unwrap().field(1).data_type(), &expected),
+        None => assert!(matches!(
+            res,
+            Err(ConnectorError::IncompatibleSchema { .. })
+        )),
+    }
+}
+
+#[test]
+fn table_get_strict() {
+    let mut conn = init();
+    conn.execute("CREATE TABLE strict_types (a INT, b INTEGER, c REAL, d TEXT, e BLOB) STRICT")
+        .unwrap();
+
+    let schema = conn.table_get("strict_types").unwrap();
+    let types: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|f| f.data_type().clone())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            DataType::Int64,
+            DataType::Int64,
+            DataType::Float64,
+            DataType::LargeUtf8,
+            DataType::LargeBinary
+        ]
+    );
+}
+
+#[test]
+fn any_connection() {
+    let mut conn = AnyConnection::connect("sqlite://:memory:").unwrap();
+    assert!(matches!(conn, AnyConnection::SQLite(_)));
+    super::tests::query_01(&mut conn);
+    super::tests::execute(&mut conn, "any_connection_execute");
+    super::tests::append_or_create(&mut conn, "any_connection_append");
+
+    let err = AnyConnection::connect("oracle://localhost").err().unwrap();
+    assert!(matches!(err, ConnectorError::UnsupportedUrlScheme(s) if s == "oracle"));
+}
+
+#[test]
+fn query_json_extract() {
+    let mut conn = init();
+
+    conn.execute("CREATE TABLE query_json_extract (doc TEXT)")
+        .unwrap();
+    conn.execute(
+        "INSERT INTO query_json_extract VALUES
+            ('{\"id\": 1, \"price\": 2, \"name\": \"a\", \"ok\": true}'),
+            ('{\"id\": 2, \"price\": 2.5, \"name\": \"b\", \"ok\": false}'),
+            ('{\"id\": 3, \"name\": null}'),
+            (NULL);",
+    )
+    .unwrap();
+
+    let batch = conn
+        .query_json_extract(
+            "query_json_extract",
+            "doc",
+            &[
+                ("id", "$.id"),
+                ("price", "$.price"),
+                ("name", "$.name"),
+                ("ok", "$.ok"),
+                ("missing", "$.missing"),
+            ],
+        )
+        .unwrap();
+
+    similar_asserts::assert_eq!(
+        pretty_format_batches(std::slice::from_ref(&batch))
+            .unwrap()
+            .to_string(),
+        "+----+-------+------+-------+---------+
+| id | price | name | ok    | missing |
++----+-------+------+-------+---------+
+| 1  | 2.0   | a    | true  |         |
+| 2  | 2.5   | b    | false |         |
+| 3  |       |      |       |         |
+|    |       |      |       |         |
++----+-------+------+-------+---------+"
+    );
+    let types: Vec<_> = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.data_type().clone())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            DataType::Int64,
+            DataType::Float64,
+            DataType::Utf8,
+            DataType::Boolean,
+            DataType::Null
+        ]
+    );
+}
+
+#[test]
+fn query_json_extract_mixed_boolean() {
+    let mut conn = init();
+
+    conn.execute("CREATE TABLE query_json_extract_mixed_boolean (doc TEXT)")
+        .unwrap();
+    conn.execute(
+        "INSERT INTO query_json_extract_mixed_boolean VALUES
+            ('{\"int\": true, \"real\": false}'),
+            ('{\"int\": 1, \"real\": 2.5}');",
+    )
+    .unwrap();
+
+    // booleans mixed with numbers are read as text, not as numbers
+    let batch = conn
+        .query_json_extract(
+            "query_json_extract_mixed_boolean",
+            "doc",
+            &[("int", "$.int"), ("real", "$.real")],
+        )
+        .unwrap();
+
+    similar_asserts::assert_eq!(
+        pretty_format_batches(std::slice::from_ref(&batch))
+            .unwrap()
+            .to_string(),
+        "+------+-------+
+| int  | real  |
++------+-------+
+| true | false |
+| 1    | 2.5   |
++------+-------+"
+    );
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+    assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+}
+
+#[test]
+fn append_reuses_prepared_insert() {
+    let mut conn = init();
+    let table_name = "append_reuses_prepared_insert";
+    conn.execute(&format!("CREATE TABLE {table_name} (a INTEGER)"))
+        .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+    let batch =
+        RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from_iter_values(0..45))]).unwrap();
+
+    // each batch is inserted as a chunk of 30 rows and a chunk of 15 rows
+    let mut appender = conn.append(table_name).unwrap();
+    for _ in 0..10 {
+        appender.append(batch.clone()).unwrap();
+    }
+    assert_eq!(
+        appender.statement_cache_stats(),
+        StatementCacheStats {
+            hits: 18,
+            misses: 2,
+            size: 2
+        }
+    );
+    appender.finish().unwrap();
+
+    assert_eq!(conn.estimate_row_count(table_name).unwrap(), 450);
+}
+
+#[test]
+fn append_json_nested() {
+    let table_name = "append_json_nested";
+
+    let list = ListArray::from_iter_primitive::<Int64Type, _, _>(vec![
+        Some(vec![Some(1), None, Some(3)]),
+        Some(vec![]),
+        None,
+    ]);
+    let fields = Fields::from(vec![
+        Field::new("a", DataType::Float64, true),
+        Field::new("b", DataType::Utf8, true),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from(vec![Some(1.5), None, Some(f64::NAN)])),
+        Arc::new(StringArray::from(vec![Some("x"), Some("\"y\""), None])),
+    ];
+    let strct = StructArray::new(fields.clone(), columns, None);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("list", list.data_type().clone(), true),
+        Field::new("strct", DataType::Struct(fields), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(list),
+            Arc::new(strct),
+        ],
+    )
+    .unwrap();
+
+    let mut conn = init();
+    conn.table_create(table_name, schema).unwrap();
+
+    // without json_nested, nested types cannot be appended
+    let mut appender = conn.append(table_name).unwrap();
+    let err = appender.append(batch.clone()).unwrap_err();
+    assert!(
+        matches!(err, ConnectorError::NotSupported { .. }),
+        "{}",
+        err
+    );
+    drop(appender);
+
+    let mut conn = conn.with_json_nested(true);
+    let mut appender = conn.append(table_name).unwrap();
+    appender.append(batch).unwrap();
+    appender.finish().unwrap();
+
+    let query = format!(
+        "SELECT list, strct, json_extract(strct, '$.b') AS b FROM {table_name} ORDER BY id"
+    );
+    let batches = connector_arrow::query(&mut conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        r#"+------------+------------------------+-----+
+| list       | strct                  | b   |
++------------+------------------------+-----+
+| [1,null,3] | {"a":1.5,"b":"x"}      | x   |
+| []         | {"a":null,"b":"\"y\""} | "y" |
+|            | {"a":null,"b":null}    |     |
++------------+------------------------+-----+"#
+    );
+}
+
+#[test]
+#[cfg(feature = "src_parquet")]
+fn load_parquet() {
+    let mut conn = init();
+    let table_name = "load_parquet";
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+
+    let mut file = connector_arrow::parquet::ParquetConnection::new(&dir);
+    let schema = file.table_get("basic_small.parquet").unwrap();
+    conn.table_create(table_name, schema).unwrap();
+
+    let rows = conn
+        .load_parquet(table_name, &dir.join("basic_small.parquet"))
+        .unwrap();
+    assert_eq!(rows, 6);
+    assert_eq!(conn.estimate_row_count(table_name).unwrap(), 6);
+}
+
+#[test]
+fn query_mixed_storage_classes() {
+    let mut conn = init();
+
+    // type is inferred from the first value, the second one does not match it
+    let query = "SELECT 1 AS a UNION ALL SELECT 'one'";
+    let err = connector_arrow::query(&mut conn, query).unwrap_err();
+    assert!(
+        matches!(err, ConnectorError::DataSchemaMismatch(_)),
+        "{}",
+        err
+    );
+
+    // all columns are nullable, so NULLs are never unexpected
+    let options = ReadOptions {
+        on_unexpected_null: NullPolicy::Promote,
+        ..Default::default()
+    };
+    let mut stmt = conn.query_with("SELECT NULL AS a", options).unwrap();
+    let batches = stmt.start([]).unwrap().collect::<Result<Vec<_>, _>>();
+    assert_eq!(batches.unwrap()[0].num_rows(), 1);
+}
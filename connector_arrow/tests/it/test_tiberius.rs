@@ -77,6 +77,14 @@ fn schema_edit() {
     super::tests::schema_edit(&mut conn, table_name, column_spec);
 }
 
+#[test]
+fn delete_by_keys() {
+    let table_name = "simple::delete_by_keys";
+
+    let mut conn = init();
+    super::tests::delete_by_keys(&mut conn, table_name, '"');
+}
+
 #[test]
 #[ignore]
 fn ident_escaping() {
@@ -0,0 +1,40 @@
+use arrow::array::{AsArray, Int32Array};
+use arrow::datatypes::{DataType, Int32Type};
+use connector_arrow::trino::TrinoConnection;
+
+fn init() -> TrinoConnection {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let url = std::env::var("TRINO_URL").unwrap();
+    TrinoConnection::new(url, "connector_arrow")
+}
+
+#[test]
+fn query_01() {
+    let mut conn = init();
+    super::tests::query_01(&mut conn);
+}
+
+#[test]
+fn query_select_1() {
+    let mut conn = init();
+    let batches = connector_arrow::query(&mut conn, "SELECT 1 AS a").unwrap();
+
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).name(), "a");
+    assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+
+    let array = batches[0].column(0).as_primitive::<Int32Type>();
+    assert_eq!(array, &Int32Array::from(vec![1]));
+}
+
+#[test]
+fn query_error() {
+    let mut conn = init();
+    let err = connector_arrow::query(&mut conn, "SELECT * FROM no_such_table").unwrap_err();
+    assert!(
+        matches!(err, connector_arrow::ConnectorError::Trino(_)),
+        "{}",
+        err
+    );
+}
@@ -1,17 +1,27 @@
 #![allow(dead_code)]
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use arrow::array::{ArrayRef, Int64Builder, RecordBatch};
-use arrow::datatypes::{Field, Schema};
+use arrow::array::{
+    Array, ArrayRef, AsArray, Date32Array, DictionaryArray, Int64Array, Int64Builder, RecordBatch,
+    StringArray,
+};
+use arrow::datatypes::{DataType, Field, Int32Type, Int64Type, Schema};
 use arrow::util::pretty::pretty_format_batches;
 use connector_arrow::api::{
-    Append, ArrowValue, Connector, ResultReader, SchemaEdit, SchemaGet, Statement,
+    Append, AppenderOptions, ArrowValue, ConflictMode, Connector, ForeignKey, ResultReader,
+    SchemaEdit, SchemaGet, Statement,
 };
-use connector_arrow::{util::coerce, TableCreateError, TableDropError};
+#[cfg(feature = "sink_csv")]
+use connector_arrow::sink::CsvOptions;
+use connector_arrow::util::{coerce, schema_diff_with_renames, SchemaChange};
+use connector_arrow::{ConnectorError, TableCreateError, TableDropError};
 use rand::SeedableRng;
 
-use crate::util::{coerce_type, load_into_table, query_table};
+use crate::util::{coerce_type, create_table, load_into_table, query_table};
 use crate::{generator::generate_batch, spec::ArrowGenSpec};
 
 pub fn query_01<C: Connector>(conn: &mut C) {
@@ -140,6 +150,41 @@ where
     ));
 }
 
+pub fn temp_table<C>(conn: &mut C, table_name: &str, ident_quote_char: char)
+where
+    C: Connector + SchemaEdit,
+{
+    let field = Field::new("a", arrow::datatypes::DataType::Int64, true);
+    let schema = Arc::new(Schema::new(vec![field]));
+
+    conn.table_create_temp(table_name, schema.clone()).unwrap();
+    assert!(matches!(
+        conn.table_create_temp(table_name, schema.clone())
+            .unwrap_err(),
+        TableCreateError::TableExists
+    ));
+
+    let batch = {
+        let mut builder = Int64Builder::new();
+        builder.append_value(1);
+        builder.append_null();
+        builder.append_value(3);
+        let array = Arc::new(builder.finish()) as ArrayRef;
+        RecordBatch::try_new(schema.clone(), vec![array]).unwrap()
+    };
+    let mut appender = conn.append(table_name).unwrap();
+    appender.append(batch.clone()).unwrap();
+    appender.finish().unwrap();
+
+    let (_, batches) = query_table(conn, table_name, ident_quote_char).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&[batch]).unwrap().to_string(),
+        pretty_format_batches(&batches).unwrap().to_string(),
+    );
+
+    conn.table_drop(table_name).unwrap();
+}
+
 pub fn ident_escaping<C>(conn: &mut C, table_name_prefix: &str)
 where
     C: Connector + SchemaEdit + SchemaGet,
@@ -211,3 +256,915 @@ pub fn streaming<C: Connector>(conn: &mut C) {
     // ... Maybe it is the postgres that hangs before returning the first result batch?
     // ... Maybe it tries to return the full result and not in batches?
 }
+
+pub fn prepared_only<C: Connector>(conn: C) {
+    let mut conn = connector_arrow::prepared_only::PreparedOnlyConnector::new(conn);
+    conn.register("add_one", "SELECT CAST($1 as integer) + 1 as a");
+
+    // registered statement, executed by key
+    let results = {
+        let mut stmt = conn.query("add_one").unwrap();
+        let reader = stmt.start([&41_i32 as &dyn ArrowValue]).unwrap();
+        reader.collect::<Result<Vec<_>, _>>().unwrap()
+    };
+    similar_asserts::assert_eq!(
+        "+----+\n\
+         | a  |\n\
+         +----+\n\
+         | 42 |\n\
+         +----+",
+        pretty_format_batches(&results).unwrap().to_string(),
+    );
+
+    // arbitrary SQL is rejected
+    let err = conn.query("SELECT 1").err().unwrap();
+    assert!(matches!(err, ConnectorError::StatementNotRegistered(_)));
+}
+
+pub fn delete_by_keys<C>(conn: &mut C, table_name: &str, ident_quote_char: char)
+where
+    C: Connector + SchemaEdit,
+{
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", arrow::datatypes::DataType::Int64, false),
+        Field::new("b", arrow::datatypes::DataType::Int64, false),
+    ]));
+    let int64_array =
+        |values: Vec<i64>| Arc::new(arrow::array::Int64Array::from(values)) as ArrayRef;
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            int64_array(vec![1, 1, 2, 2, 3]),
+            int64_array(vec![1, 2, 1, 2, 1]),
+        ],
+    )
+    .unwrap();
+    load_into_table(conn, schema.clone(), &[batch], table_name).unwrap();
+
+    let keys = RecordBatch::try_new(
+        schema.clone(),
+        vec![int64_array(vec![1, 2, 4]), int64_array(vec![2, 1, 4])],
+    )
+    .unwrap();
+    conn.delete_by_keys(table_name, &["a", "b"], &keys).unwrap();
+
+    let (_, batches) = query_table(conn, table_name, ident_quote_char).unwrap();
+    similar_asserts::assert_eq!(
+        "+---+---+\n\
+         | a | b |\n\
+         +---+---+\n\
+         | 1 | 1 |\n\
+         | 2 | 2 |\n\
+         | 3 | 1 |\n\
+         +---+---+",
+        pretty_format_batches(&batches).unwrap().to_string(),
+    );
+}
+
+pub fn merge<C>(conn: &mut C, table_name: &str)
+where
+    C: Connector + SchemaEdit,
+{
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", arrow::datatypes::DataType::Int64, false),
+        Field::new("b", arrow::datatypes::DataType::Int64, true),
+    ]));
+    let int64_array =
+        |values: Vec<i64>| Arc::new(arrow::array::Int64Array::from(values)) as ArrayRef;
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![int64_array(vec![1, 2]), int64_array(vec![10, 20])],
+    )
+    .unwrap();
+    load_into_table(conn, schema.clone(), &[batch], table_name).unwrap();
+
+    // row 2 overlaps and is updated, row 3 is inserted
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![int64_array(vec![2, 3]), int64_array(vec![200, 300])],
+    )
+    .unwrap();
+    conn.merge(table_name, &["a"], &batch).unwrap();
+
+    let query = format!("SELECT a, b FROM {table_name} ORDER BY a");
+    let batches = connector_arrow::query(conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        "+---+-----+\n\
+         | a | b   |\n\
+         +---+-----+\n\
+         | 1 | 10  |\n\
+         | 2 | 200 |\n\
+         | 3 | 300 |\n\
+         +---+-----+",
+        pretty_format_batches(&batches).unwrap().to_string(),
+    );
+}
+
+#[cfg(feature = "ipc")]
+pub fn write_ipc_stream<C: Connector>(conn: &mut C) {
+    let query = "SELECT 1 as a, 'hello' as b";
+    let expected = connector_arrow::query(conn, query).unwrap();
+
+    let buffer = {
+        let mut stmt = conn.query(query).unwrap();
+        let reader = stmt.start([]).unwrap();
+        connector_arrow::util::write_ipc_stream(reader, Vec::new()).unwrap()
+    };
+
+    let reader = arrow::ipc::reader::StreamReader::try_new(buffer.as_slice(), None).unwrap();
+    assert_eq!(reader.schema(), expected[0].schema());
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    similar_asserts::assert_eq!(expected, batches);
+}
+
+#[cfg(feature = "ipc")]
+pub fn read_ipc_stream_into<C: Connector + SchemaEdit>(conn: &mut C, table_name: &str) {
+    let _ = conn.table_drop(table_name);
+
+    let query = "SELECT 1 as a, 'hello' as b UNION ALL SELECT 2, 'world'";
+    let buffer = {
+        let mut stmt = conn.query(query).unwrap();
+        let reader = stmt.start([]).unwrap();
+        connector_arrow::util::write_ipc_stream(reader, Vec::new()).unwrap()
+    };
+
+    let rows =
+        connector_arrow::util::read_ipc_stream_into(conn, table_name, buffer.as_slice(), true)
+            .unwrap();
+    assert_eq!(rows, 2);
+
+    // table exists, so it is not created again
+    let rows =
+        connector_arrow::util::read_ipc_stream_into(conn, table_name, buffer.as_slice(), true)
+            .unwrap();
+    assert_eq!(rows, 2);
+
+    let query = format!("SELECT * FROM {table_name} ORDER BY a");
+    let batches = connector_arrow::query(conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        pretty_format_batches(&batches).unwrap().to_string(),
+        "+---+-------+
+| a | b     |
++---+-------+
+| 1 | hello |
+| 1 | hello |
+| 2 | world |
+| 2 | world |
++---+-------+"
+    );
+    conn.table_drop(table_name).unwrap();
+}
+
+#[cfg(feature = "sink_csv")]
+pub fn write_csv<C: Connector>(conn: &mut C) {
+    let query = "SELECT 1 AS a, 'say \"hi\", bye' AS b UNION ALL SELECT NULL, 'x'";
+    let batches = connector_arrow::query(conn, query).unwrap();
+    let schema = conn.query_schema(query).unwrap();
+
+    let options = CsvOptions::default();
+    let csv = connector_arrow::sink::write_csv(&schema, &batches, Vec::new(), &options).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "a,b\n1,\"say \"\"hi\"\", bye\"\n,x\n"
+    );
+
+    let options = CsvOptions {
+        delimiter: b';',
+        header: false,
+        null: "NULL".into(),
+    };
+    let csv = connector_arrow::sink::write_csv(&schema, &batches, Vec::new(), &options).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "1;\"say \"\"hi\"\", bye\"\nNULL;x\n"
+    );
+
+    // header is written even without batches
+    let options = CsvOptions::default();
+    let csv = connector_arrow::sink::write_csv(&schema, &[], Vec::new(), &options).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), "a,b\n");
+}
+
+pub fn execute<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a INTEGER");
+
+    let inserted = conn
+        .execute(&format!("INSERT INTO {table_name} VALUES (1), (2), (3)"))
+        .unwrap();
+    assert_eq!(inserted, 3);
+
+    let updated = conn
+        .execute(&format!("UPDATE {table_name} SET a = a + 10 WHERE a > 1"))
+        .unwrap();
+    assert_eq!(updated, 2);
+
+    let deleted = conn
+        .execute(&format!("DELETE FROM {table_name} WHERE a = 1"))
+        .unwrap();
+    assert_eq!(deleted, 1);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn query_returning<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "id INTEGER, x INTEGER");
+    conn.execute(&format!(
+        "INSERT INTO {table_name} VALUES (1, 10), (4, 40), (5, 50)"
+    ))
+    .unwrap();
+
+    let column_as_i64 = |batch: &RecordBatch, index: usize| {
+        let column = arrow::compute::cast(batch.column(index), &DataType::Int64).unwrap();
+        column.as_primitive::<Int64Type>().values().to_vec()
+    };
+
+    let update = format!("UPDATE {table_name} SET x = x + 1 WHERE id < 5 RETURNING id, x");
+    let batches = connector_arrow::query(conn, &update).unwrap();
+    let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).name(), "id");
+    assert_eq!(schema.field(1).name(), "x");
+    let mut rows: Vec<_> = (column_as_i64(&batch, 0).into_iter())
+        .zip(column_as_i64(&batch, 1))
+        .collect();
+    rows.sort_unstable();
+    assert_eq!(rows, vec![(1, 11), (4, 41)]);
+
+    let delete = format!("DELETE FROM {table_name} WHERE id = 5 RETURNING x");
+    let batches = connector_arrow::query(conn, &delete).unwrap();
+    let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+    assert_eq!(column_as_i64(&batch, 0), vec![50]);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+#[cfg(feature = "pool")]
+pub fn pooled<M>(pool: connector_arrow::pool::PooledConnector<M>)
+where
+    M: connector_arrow::pool::r2d2::ManageConnection,
+    M::Connection: connector_arrow::pool::IntoConnector,
+{
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                let mut conn = pool.checkout().unwrap();
+                let query = format!("SELECT {i} AS a");
+                let batches = connector_arrow::query(&mut conn, &query).unwrap();
+                pretty_format_batches(&batches).unwrap().to_string()
+            })
+        })
+        .collect();
+
+    for (i, thread) in threads.into_iter().enumerate() {
+        let result = thread.join().unwrap();
+        assert!(result.contains(&format!("| {i} |")), "{}", result);
+    }
+}
+
+#[cfg(feature = "pool")]
+pub fn query_partitioned<M>(pool: connector_arrow::pool::PooledConnector<M>, table_name: &str)
+where
+    M: connector_arrow::pool::r2d2::ManageConnection,
+    M::Connection: connector_arrow::pool::IntoConnector,
+{
+    let mut conn = pool.checkout().unwrap();
+    let _ = conn.execute(&format!("DROP TABLE {table_name}"));
+    conn.execute(&format!("CREATE TABLE {table_name} (id INTEGER, v TEXT)"))
+        .unwrap();
+    let values = std::iter::once("(NULL, 'null')".to_string())
+        .chain((1..=103).map(|i| format!("({i}, 'v{i}')")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES {values}"))
+        .unwrap();
+    drop(conn);
+
+    let query = format!("SELECT id, v FROM {table_name}");
+    let mut conn = pool.checkout().unwrap();
+    let expected = connector_arrow::query(&mut conn, &query).unwrap();
+    drop(conn);
+
+    let batches = pool.query_partitioned(&query, "id", 4).unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 104);
+    let schema = expected[0].schema();
+    similar_asserts::assert_eq!(
+        arrow::compute::concat_batches(&schema, &batches).unwrap(),
+        arrow::compute::concat_batches(&schema, &expected).unwrap()
+    );
+
+    // more partitions than distinct values
+    let batches = pool.query_partitioned(&query, "id", 1000).unwrap();
+    similar_asserts::assert_eq!(
+        arrow::compute::concat_batches(&schema, &batches).unwrap(),
+        arrow::compute::concat_batches(&schema, &expected).unwrap()
+    );
+
+    let mut conn = pool.checkout().unwrap();
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn execute_batch<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a INTEGER");
+
+    let insert = format!("INSERT INTO {table_name} VALUES (1), (2)");
+    let update = format!("UPDATE {table_name} SET a = a + 1");
+    let affected = conn.execute_batch(&[&insert, &update]).unwrap();
+    assert_eq!(affected, vec![2, 2]);
+
+    // second statement fails, so the first one is rolled back
+    let insert = format!("INSERT INTO {table_name} VALUES (3)");
+    let failing = format!("INSERT INTO {table_name}_nonexistent VALUES (4)");
+    let delete = format!("DELETE FROM {table_name}");
+    conn.execute_batch(&[&insert, &failing, &delete])
+        .unwrap_err();
+
+    let query = format!("SELECT a FROM {table_name} ORDER BY a");
+    let batches = connector_arrow::query(conn, &query).unwrap();
+    let count: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(count, 2);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn transaction<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a BIGINT");
+
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(arrow::array::Int64Array::from(vec![1, 2])) as ArrayRef],
+    )
+    .unwrap();
+    fn count<C: Connector>(conn: &mut C, table_name: &str) -> usize {
+        let query = format!("SELECT a FROM {table_name}");
+        let batches = connector_arrow::query(conn, &query).unwrap();
+        batches.iter().map(|b| b.num_rows()).sum()
+    }
+
+    // dropped without commit, so both appends are rolled back
+    {
+        let mut tx = conn.transaction().unwrap();
+        for _ in 0..2 {
+            let mut appender = tx.append(table_name).unwrap();
+            appender.append(batch.clone()).unwrap();
+            appender.finish().unwrap();
+        }
+        assert_eq!(count(&mut tx, table_name), 4);
+    }
+    assert_eq!(count(conn, table_name), 0);
+
+    let mut tx = conn.transaction().unwrap();
+    tx.execute(&format!("INSERT INTO {table_name} VALUES (3)"))
+        .unwrap();
+    let mut appender = tx.append(table_name).unwrap();
+    appender.append(batch).unwrap();
+    appender.finish().unwrap();
+    tx.commit().unwrap();
+    assert_eq!(count(conn, table_name), 3);
+
+    let mut tx = conn.transaction().unwrap();
+    tx.execute(&format!("DELETE FROM {table_name}")).unwrap();
+    tx.rollback().unwrap();
+    assert_eq!(count(conn, table_name), 3);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn schema_diff<C>(conn: &mut C, table_name: &str)
+where
+    C: Connector + SchemaEdit + SchemaGet,
+{
+    let _ = conn.table_drop(table_name);
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Utf8, true),
+        Field::new("c", DataType::Int64, true),
+    ]);
+    conn.table_create(table_name, Arc::new(schema)).unwrap();
+    let current = conn.table_get(table_name).unwrap();
+    let field = |name: &str| current.field_with_name(name).unwrap().clone();
+
+    // same columns in a different order
+    let desired = Schema::new(vec![field("c"), field("a"), field("b")]);
+    assert_eq!(
+        connector_arrow::util::schema_diff(&current, &desired),
+        vec![]
+    );
+
+    let d = Field::new("d", DataType::Utf8, true);
+    let desired = Schema::new(vec![
+        field("c").with_name("c2"),
+        field("a").with_data_type(DataType::Utf8),
+        field("b").with_nullable(false),
+        d.clone(),
+    ]);
+    let changes = vec![
+        SchemaChange::DropColumn("c".into()),
+        SchemaChange::ChangeType {
+            name: "a".into(),
+            from: field("a").data_type().clone(),
+            to: DataType::Utf8,
+        },
+        SchemaChange::ChangeNullability {
+            name: "b".into(),
+            nullable: false,
+        },
+        SchemaChange::AddColumn(Arc::new(field("c").with_name("c2"))),
+        SchemaChange::AddColumn(Arc::new(d)),
+    ];
+    assert_eq!(
+        connector_arrow::util::schema_diff(&current, &desired),
+        changes
+    );
+
+    // with a hint, the column is renamed instead of dropped and added
+    let mut changes = changes;
+    changes.remove(3);
+    changes[0] = SchemaChange::RenameColumn {
+        from: "c".into(),
+        to: "c2".into(),
+    };
+    assert_eq!(
+        schema_diff_with_renames(&current, &desired, &[("c", "c2")]),
+        changes
+    );
+
+    conn.table_drop(table_name).unwrap();
+}
+
+pub fn table_list<C: Connector + SchemaGet>(conn: &mut C, table_name: &str) {
+    let view_name = format!("{table_name}_view");
+    conn.execute(&format!("DROP VIEW IF EXISTS {view_name}"))
+        .unwrap();
+    create_table(conn, table_name, "a INTEGER");
+    conn.execute(&format!(
+        "CREATE VIEW {view_name} AS SELECT a FROM {table_name}"
+    ))
+    .unwrap();
+
+    let tables = conn.table_list().unwrap();
+    assert!(tables.contains(&table_name.to_string()));
+    assert!(!tables.contains(&view_name));
+
+    conn.execute(&format!("DROP VIEW {view_name}")).unwrap();
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn table_primary_key<C: Connector + SchemaGet>(conn: &mut C, table_name: &str) {
+    create_table(
+        conn,
+        table_name,
+        "a INTEGER, b INTEGER, c INTEGER, PRIMARY KEY (b, a)",
+    );
+    let key = conn.table_primary_key(table_name).unwrap();
+    assert_eq!(key, vec!["b".to_string(), "a".to_string()]);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+    conn.execute(&format!("CREATE TABLE {table_name} (a INTEGER)"))
+        .unwrap();
+    let key = conn.table_primary_key(table_name).unwrap();
+    assert!(key.is_empty());
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn append_dictionary<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a TEXT");
+
+    let array: DictionaryArray<Int32Type> = vec![Some("x"), None, Some("y"), Some("x")]
+        .into_iter()
+        .collect();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        array.data_type().clone(),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+
+    let mut appender = conn.append(table_name).unwrap();
+    appender.append(batch).unwrap();
+    appender.finish().unwrap();
+
+    let query = format!("SELECT a FROM {table_name}");
+    let batches = connector_arrow::query(conn, &query).unwrap();
+    let expected = StringArray::from(vec![Some("x"), None, Some("y"), Some("x")]);
+    assert_eq!(batches[0].column(0).as_string::<i32>(), &expected);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn table_exists<C: Connector + SchemaGet + SchemaEdit>(conn: &mut C, table_name: &str) {
+    conn.table_drop_if_exists(table_name).unwrap();
+    // dropping a nonexistent table is not an error
+    conn.table_drop_if_exists(table_name).unwrap();
+    assert!(!conn.table_exists(table_name).unwrap());
+
+    conn.execute(&format!("CREATE TABLE {table_name} (a INTEGER)"))
+        .unwrap();
+    assert!(conn.table_exists(table_name).unwrap());
+
+    conn.table_drop_if_exists(table_name).unwrap();
+    assert!(!conn.table_exists(table_name).unwrap());
+}
+
+pub fn table_foreign_keys<C: Connector + SchemaGet>(conn: &mut C, table_name: &str) {
+    let parent = format!("{table_name}_parent");
+    let child = format!("{table_name}_child");
+    conn.execute(&format!("DROP TABLE IF EXISTS {child}"))
+        .unwrap();
+    conn.execute(&format!("DROP TABLE IF EXISTS {parent}"))
+        .unwrap();
+    conn.execute(&format!(
+        "CREATE TABLE {parent} (a INTEGER, b INTEGER, PRIMARY KEY (a, b))"
+    ))
+    .unwrap();
+    conn.execute(&format!(
+        "CREATE TABLE {child} (x INTEGER, y INTEGER, \
+        FOREIGN KEY (y, x) REFERENCES {parent} (a, b))"
+    ))
+    .unwrap();
+
+    let keys = conn.table_foreign_keys(&child).unwrap();
+    assert_eq!(
+        keys,
+        vec![ForeignKey {
+            columns: vec!["y".to_string(), "x".to_string()],
+            referenced_table: parent.clone(),
+            referenced_columns: vec!["a".to_string(), "b".to_string()],
+        }]
+    );
+    let keys = conn.table_foreign_keys(&parent).unwrap();
+    assert!(keys.is_empty());
+
+    conn.execute(&format!("DROP TABLE {child}")).unwrap();
+    conn.execute(&format!("DROP TABLE {parent}")).unwrap();
+}
+
+pub fn list_catalogs<C: Connector>(conn: &mut C, current: &str) {
+    let catalogs = conn.list_catalogs().unwrap();
+    assert!(
+        catalogs.iter().any(|c| c == current),
+        "{} not in {:?}",
+        current,
+        catalogs
+    );
+}
+
+pub fn rename<C>(conn: &mut C, table_name: &str)
+where
+    C: Connector + SchemaEdit + SchemaGet,
+{
+    let renamed = format!("{table_name}::\"renamed\"");
+    let _ = conn.table_drop(table_name);
+    let _ = conn.table_drop(&renamed);
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", arrow::datatypes::DataType::Int64, true),
+        Field::new("b", arrow::datatypes::DataType::Int64, true),
+    ]));
+    conn.table_create(table_name, schema).unwrap();
+
+    conn.rename_table(table_name, &renamed).unwrap();
+    let tables = conn.table_list().unwrap();
+    assert!(tables.contains(&renamed));
+    assert!(!tables.contains(&table_name.to_string()));
+
+    conn.rename_column(&renamed, "a", "a \"quoted\"").unwrap();
+    let schema = conn.table_get(&renamed).unwrap();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["a \"quoted\"", "b"]);
+
+    conn.table_drop(&renamed).unwrap();
+}
+
+pub fn append_or_create<C>(conn: &mut C, table_name: &str)
+where
+    C: Connector + SchemaEdit + SchemaGet,
+{
+    let _ = conn.table_drop(table_name);
+
+    let int64_field = |name: &str| Field::new(name, arrow::datatypes::DataType::Int64, true);
+    let int64_array =
+        |values: Vec<i64>| Arc::new(arrow::array::Int64Array::from(values)) as ArrayRef;
+
+    let schema = Arc::new(Schema::new(vec![int64_field("a"), int64_field("b")]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![int64_array(vec![1, 2]), int64_array(vec![3, 4])],
+    )
+    .unwrap();
+
+    // first call creates the table, second call appends into it
+    conn.append_or_create(table_name, &batch).unwrap();
+    conn.append_or_create(table_name, &batch).unwrap();
+    assert_eq!(conn.estimate_row_count(table_name).unwrap(), 4);
+
+    let schema = Arc::new(Schema::new(vec![int64_field("a"), int64_field("c")]));
+    let batch =
+        RecordBatch::try_new(schema, vec![int64_array(vec![1]), int64_array(vec![2])]).unwrap();
+    let err = conn.append_or_create(table_name, &batch).unwrap_err();
+    let columns = match err {
+        ConnectorError::TableSchemaMismatch { columns, .. } => columns,
+        err => panic!("unexpected error: {}", err),
+    };
+    assert_eq!(
+        columns,
+        vec!["c (missing in table)", "b (missing in batch)"]
+    );
+
+    conn.table_drop(table_name).unwrap();
+}
+
+pub fn append_conflict<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a BIGINT PRIMARY KEY, b BIGINT");
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", arrow::datatypes::DataType::Int64, false),
+        Field::new("b", arrow::datatypes::DataType::Int64, true),
+    ]));
+    let batch = |a: Vec<i64>, b: Vec<i64>| {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(arrow::array::Int64Array::from(a)) as ArrayRef,
+                Arc::new(arrow::array::Int64Array::from(b)) as ArrayRef,
+            ],
+        )
+        .unwrap()
+    };
+    let mut append = |conflict: ConflictMode, batch: RecordBatch| {
+        let options = AppenderOptions {
+            conflict,
+            ..Default::default()
+        };
+        let mut appender = conn.append_with(table_name, options).unwrap();
+        appender.append(batch).unwrap();
+        appender.finish().unwrap();
+    };
+
+    append(ConflictMode::Error, batch(vec![1, 2], vec![10, 20]));
+    append(ConflictMode::Ignore, batch(vec![2, 3], vec![200, 300]));
+    append(
+        ConflictMode::Replace(vec!["a".into()]),
+        batch(vec![1, 4], vec![100, 400]),
+    );
+
+    let query = format!("SELECT a, b FROM {table_name} ORDER BY a");
+    let batches = connector_arrow::query(conn, &query).unwrap();
+    similar_asserts::assert_eq!(
+        "+---+-----+\n\
+         | a | b   |\n\
+         +---+-----+\n\
+         | 1 | 100 |\n\
+         | 2 | 20  |\n\
+         | 3 | 300 |\n\
+         | 4 | 400 |\n\
+         +---+-----+",
+        pretty_format_batches(&batches).unwrap().to_string(),
+    );
+
+    // conflict columns are checked when the appender is created
+    let options = AppenderOptions {
+        conflict: ConflictMode::Replace(vec!["c".into()]),
+        ..Default::default()
+    };
+    let res = conn.append_with(table_name, options);
+    assert!(matches!(res, Err(ConnectorError::ColumnNotFound { .. })));
+}
+
+pub fn query_schema<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a INTEGER, b TEXT");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES (1, 'x')"))
+        .unwrap();
+
+    let query = format!("SELECT b, a FROM {table_name}");
+    let schema = conn.query_schema(&query).unwrap();
+
+    let batches = connector_arrow::query(conn, &query).unwrap();
+    let expected = batches[0].schema();
+    assert_eq!(schema.fields().len(), 2);
+    for (field, expected) in schema.fields().iter().zip(expected.fields()) {
+        assert_eq!(field.name(), expected.name());
+        assert_eq!(field.data_type(), expected.data_type());
+    }
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn estimate_row_count<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a INTEGER");
+
+    let values: Vec<_> = (0..100).map(|i| format!("({i})")).collect();
+    let values = values.join(", ");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES {values}"))
+        .unwrap();
+    conn.execute(&format!("ANALYZE {table_name}")).unwrap();
+
+    let estimate = conn.estimate_row_count(table_name).unwrap();
+    assert!((90..=110).contains(&estimate), "estimate: {}", estimate);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn explain_cost<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a INTEGER");
+
+    let values: Vec<_> = (0..1000).map(|i| format!("({i})")).collect();
+    let values = values.join(", ");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES {values}"))
+        .unwrap();
+    conn.execute(&format!("ANALYZE {table_name}")).unwrap();
+
+    let cost = conn
+        .explain_cost(&format!("SELECT a FROM {table_name}"))
+        .unwrap();
+    let rows = cost.estimated_rows;
+    assert!((900..=1100).contains(&rows), "estimated rows: {}", rows);
+    assert!(cost.estimated_cost > 0.0, "{:?}", cost);
+
+    // the query is not executed
+    conn.explain_cost(&format!("DELETE FROM {table_name}"))
+        .unwrap();
+    assert_eq!(conn.estimate_row_count(table_name).unwrap(), 1000);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn query_iter<C: Connector>(conn: &mut C, table_name: &str) {
+    create_table(conn, table_name, "a INTEGER");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES (1), (2), (3)"))
+        .unwrap();
+
+    // the iterator owns the statement, so it can be returned
+    fn rows<'c, C: Connector>(
+        conn: &'c mut C,
+        table_name: &str,
+    ) -> connector_arrow::QueryIter<'c, C> {
+        connector_arrow::query_iter(conn, &format!("SELECT a FROM {table_name}")).unwrap()
+    }
+
+    let mut iter = rows(conn, table_name);
+    let schema = iter.get_schema().unwrap();
+    assert_eq!(schema.field(0).name(), "a");
+    let mut row_count = 0;
+    for batch in iter {
+        row_count += batch.unwrap().num_rows();
+    }
+    assert_eq!(row_count, 3);
+
+    // dropping the iterator before reading all batches releases the connection
+    let mut iter = rows(conn, table_name);
+    assert!(iter.next().is_some());
+    drop(iter);
+
+    let res = connector_arrow::query_iter(conn, "SELECT * FROM query_iter_nonexistent");
+    assert!(res.is_err());
+    drop(res);
+
+    conn.execute(&format!("DROP TABLE {table_name}")).unwrap();
+}
+
+pub fn query_with_coercions<C: Connector>(conn: &mut C) {
+    let query = "SELECT CAST(1 AS INTEGER) AS a, '2024-01-02' AS d, 'x' AS s";
+    let coercions = HashMap::from([
+        ("a".to_string(), DataType::Int64),
+        ("d".to_string(), DataType::Date32),
+    ]);
+    let batches = connector_arrow::query_with_coercions(conn, query, coercions).unwrap();
+    let batch = &batches[0];
+
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+    assert_eq!(schema.field(1).data_type(), &DataType::Date32);
+    assert_eq!(batch.column(0).as_ref(), &Int64Array::from(vec![1]));
+    // 2024-01-02 is 19724 days after the epoch
+    assert_eq!(batch.column(1).as_ref(), &Date32Array::from(vec![19724]));
+
+    // text that is not a date
+    let coercions = HashMap::from([("s".to_string(), DataType::Date32)]);
+    let res = connector_arrow::query_with_coercions(conn, query, coercions);
+    assert!(matches!(res, Err(ConnectorError::Arrow(_))));
+
+    // column that is not in the result
+    let coercions = HashMap::from([("b".to_string(), DataType::Int64)]);
+    let res = connector_arrow::query_with_coercions(conn, query, coercions);
+    assert!(matches!(res, Err(ConnectorError::Arrow(_))));
+}
+
+pub fn query_cached<C: Connector + SchemaGet + SchemaEdit>(conn: C, table_name: &str) {
+    let mut conn = connector_arrow::query_cache::CachedConnector::new(conn);
+
+    create_table(&mut conn, table_name, "a INTEGER");
+    conn.execute(&format!("INSERT INTO {table_name} VALUES (1)"))
+        .unwrap();
+
+    let query = format!("SELECT COUNT(*) AS c FROM {table_name}");
+    let first = conn.query_cached(&query).unwrap();
+
+    // the query is not re-executed, so the new row is not counted
+    conn.execute(&format!("INSERT INTO {table_name} VALUES (2)"))
+        .unwrap();
+    let second = conn.query_cached(&query).unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+
+    conn.invalidate(&query);
+    let third = conn.query_cached(&query).unwrap();
+    assert!(!Arc::ptr_eq(&first, &third));
+    similar_asserts::assert_eq!(
+        "+---+\n\
+         | c |\n\
+         +---+\n\
+         | 2 |\n\
+         +---+",
+        pretty_format_batches(&third).unwrap().to_string(),
+    );
+
+    // schema operations are passed through to the inner connector
+    assert!(conn.table_exists(table_name).unwrap());
+    conn.table_drop(table_name).unwrap();
+    assert!(!conn.table_exists(table_name).unwrap());
+}
+
+pub fn start_named<C: Connector>(conn: &mut C) {
+    let query = "SELECT CAST(:a AS integer) + CAST(:a AS integer) AS s, CAST(:b AS text) AS t";
+    let mut stmt = conn.query(query).unwrap();
+
+    let a = 21_i32;
+    let b = "hello".to_string();
+    let reader = stmt
+        .start_named(&[("b", &b as &dyn ArrowValue), ("a", &a as &dyn ArrowValue)])
+        .unwrap();
+    let results = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    similar_asserts::assert_eq!(
+        "+----+-------+\n\
+         | s  | t     |\n\
+         +----+-------+\n\
+         | 42 | hello |\n\
+         +----+-------+",
+        pretty_format_batches(&results).unwrap().to_string(),
+    );
+
+    let err = stmt
+        .start_named(&[("a", &a as &dyn ArrowValue)])
+        .err()
+        .unwrap();
+    assert!(matches!(err, ConnectorError::UnboundParameter(name) if name == "b"));
+}
+
+pub fn cancel<C: Connector>(conn: &mut C, long_query: &str) {
+    let token = conn.cancel_token().unwrap();
+    let done = Arc::new(AtomicBool::new(false));
+
+    // keep cancelling, since a request that arrives before the query starts is ignored
+    let canceller = std::thread::spawn({
+        let done = done.clone();
+        move || {
+            while !done.load(Ordering::SeqCst) {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                token.cancel().unwrap();
+            }
+        }
+    });
+
+    let res = connector_arrow::query(conn, long_query);
+    done.store(true, Ordering::SeqCst);
+    canceller.join().unwrap();
+    assert!(matches!(res, Err(ConnectorError::Cancelled)), "{:?}", res);
+
+    // connection is still usable
+    connector_arrow::query(conn, "SELECT 1").unwrap();
+}
+
+pub fn query_with_timeout<C: Connector>(conn: &mut C, long_query: &str) {
+    let timeout = Duration::from_millis(200);
+    let res = connector_arrow::query_with_timeout(conn, long_query, timeout);
+    assert!(
+        matches!(res, Err(ConnectorError::Timeout(t)) if t == timeout),
+        "{:?}",
+        res
+    );
+
+    // timeout does not apply to following queries
+    let batches =
+        connector_arrow::query_with_timeout(conn, "SELECT 1", Duration::from_secs(60)).unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+    std::thread::sleep(timeout * 2);
+    connector_arrow::query(conn, "SELECT 1").unwrap();
+
+    // timeout applies to a single execution of a statement
+    let token = conn.cancel_token().unwrap();
+    let mut stmt = conn.query(long_query).unwrap();
+    let res = stmt
+        .start_with_timeout([], token, timeout)
+        .and_then(|reader| reader.collect::<Result<Vec<_>, _>>());
+    assert!(
+        matches!(res, Err(ConnectorError::Timeout(t)) if t == timeout),
+        "{:?}",
+        res
+    );
+    drop(stmt);
+    connector_arrow::query(conn, "SELECT 1").unwrap();
+}
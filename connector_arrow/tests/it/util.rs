@@ -56,6 +56,15 @@ where
     Ok(())
 }
 
+/// Drops the table if it exists and creates it with columns given in SQL,
+/// for tests of types that cannot be created from Arrow schema.
+pub fn create_table<C: Connector>(conn: &mut C, table_name: &str, columns: &str) {
+    conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"))
+        .unwrap();
+    conn.execute(&format!("CREATE TABLE {table_name} ({columns})"))
+        .unwrap();
+}
+
 pub fn query_table<C: Connector>(
     conn: &mut C,
     table_name: &str,